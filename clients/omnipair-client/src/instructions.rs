@@ -0,0 +1,1162 @@
+use anchor_lang::{
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program, sysvar,
+    },
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::{associated_token, metadata::mpl_token_metadata, token, token_2022};
+use omnipair::{
+    accounts, instruction,
+    state::{Pair, PositionOrder, RevenueRecipients, RiskParams, UserPosition},
+    AddLiquidityArgs, AdjustCollateralArgs, AdjustDebtArgs, ClosePositionArgs, CreateRateModelArgs,
+    EmitValueArgs, FlashloanArgs, InitFutarchyAuthorityArgs, InitializeAndBootstrapArgs,
+    LiquidateWithRepayArgs, OpenLeveragedArgs, PairViewKind, PlacePositionOrderArgs,
+    RemoveLiquidityArgs, SetGlobalReduceOnlyArgs, SetMarginModeArgs, SetPairCapsArgs,
+    SetPairInsuranceShareArgs, SetPairReduceOnlyArgs, SetPairRiskParamsArgs,
+    SetPositionDelegateArgs, SwapArgs, SwapExactOutArgs, SwapRouteArgs, TransferPositionArgs,
+    UpdateFutarchyAuthorityArgs, UpdateProtocolRevenueArgs, UpdateRateModelArgs,
+    UpdateRevenueRecipientsArgs, UserPositionViewKind,
+};
+
+use crate::pda::*;
+
+/// Addresses of a pair and of the program accounts its instructions touch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PairAccounts {
+    pub pair: Pubkey,
+    pub token0: Pubkey,
+    pub token1: Pubkey,
+    pub lp_mint: Pubkey,
+    pub rate_model0: Pubkey,
+    pub rate_model1: Pubkey,
+    pub reserve0_vault: Pubkey,
+    pub reserve1_vault: Pubkey,
+    pub collateral0_vault: Pubkey,
+    pub collateral1_vault: Pubkey,
+}
+
+impl PairAccounts {
+    /// Resolves the accounts of an existing pair from its fetched state.
+    pub fn new(pair: Pubkey, state: &Pair) -> Self {
+        Self {
+            pair,
+            token0: state.token0,
+            token1: state.token1,
+            lp_mint: state.lp_mint,
+            rate_model0: state.rate_model0,
+            rate_model1: state.rate_model1,
+            reserve0_vault: find_reserve_vault_address(&pair, &state.token0).0,
+            reserve1_vault: find_reserve_vault_address(&pair, &state.token1).0,
+            collateral0_vault: find_collateral_vault_address(&pair, &state.token0).0,
+            collateral1_vault: find_collateral_vault_address(&pair, &state.token1).0,
+        }
+    }
+
+    pub fn is_token0(&self, mint: &Pubkey) -> bool {
+        *mint == self.token0
+    }
+
+    pub fn other_token(&self, mint: &Pubkey) -> Pubkey {
+        match self.is_token0(mint) {
+            true => self.token1,
+            false => self.token0,
+        }
+    }
+
+    pub fn reserve_vault(&self, mint: &Pubkey) -> Pubkey {
+        match self.is_token0(mint) {
+            true => self.reserve0_vault,
+            false => self.reserve1_vault,
+        }
+    }
+
+    pub fn collateral_vault(&self, mint: &Pubkey) -> Pubkey {
+        match self.is_token0(mint) {
+            true => self.collateral0_vault,
+            false => self.collateral1_vault,
+        }
+    }
+
+    pub fn user_position(&self, owner: &Pubkey, position_index: u16) -> Pubkey {
+        find_user_position_address(&self.pair, owner, position_index).0
+    }
+}
+
+/// A position of `owner` in a pair, see `UserPosition::position_index`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PositionKey {
+    pub owner: Pubkey,
+    pub position_index: u16,
+}
+
+impl PositionKey {
+    /// The default position of `owner`, index 0.
+    pub fn new(owner: Pubkey) -> Self {
+        Self { owner, position_index: 0 }
+    }
+}
+
+impl From<&UserPosition> for PositionKey {
+    fn from(user_position: &UserPosition) -> Self {
+        Self { owner: user_position.owner, position_index: user_position.position_index }
+    }
+}
+
+/// Accounts of `initialize` that cannot be derived from the mints and parameters.
+#[derive(Clone, Debug)]
+pub struct InitializeAccounts {
+    pub deployer: Pubkey,
+    pub token0_mint: Pubkey,
+    pub token1_mint: Pubkey,
+    /// Fresh keypair, signs the transaction
+    pub lp_mint: Pubkey,
+    pub deployer_token0_account: Pubkey,
+    pub deployer_token1_account: Pubkey,
+    /// `FutarchyAuthority::recipients.team_treasury`
+    pub team_treasury: Pubkey,
+    /// Wrapped SOL account owned by the team treasury, receives the pair creation fee
+    pub team_treasury_wsol_account: Pubkey,
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: omnipair::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn event_authority() -> Pubkey {
+    find_event_authority_address().0
+}
+
+fn futarchy_authority() -> Pubkey {
+    find_futarchy_authority_address().0
+}
+
+/* View */
+
+pub fn view_pair_data(pair: &PairAccounts, getter: PairViewKind, args: EmitValueArgs) -> Instruction {
+    build(
+        accounts::ViewPairData {
+            pair: pair.pair,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+        },
+        instruction::ViewPairData { getter, args },
+    )
+}
+
+/// Builds `view_pair_data(FullSnapshot)`, passing the reserve vaults so the claimable protocol fees are included.
+pub fn view_pair_full_snapshot(pair: &PairAccounts) -> Instruction {
+    let mut ix = view_pair_data(
+        pair,
+        PairViewKind::FullSnapshot,
+        EmitValueArgs { amount: None, token_mint: None, debt_amount: None },
+    );
+    ix.accounts.extend([
+        AccountMeta::new_readonly(pair.reserve0_vault, false),
+        AccountMeta::new_readonly(pair.reserve1_vault, false),
+    ]);
+    ix
+}
+
+pub fn view_user_position_data(pair: &PairAccounts, position: &PositionKey, getter: UserPositionViewKind) -> Instruction {
+    build(
+        accounts::ViewUserPositionData {
+            pair: pair.pair,
+            user_position: pair.user_position(&position.owner, position.position_index),
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+        },
+        instruction::ViewUserPositionData { getter },
+    )
+}
+
+/* Futarchy */
+
+pub fn init_futarchy_authority(deployer: &Pubkey, args: InitFutarchyAuthorityArgs) -> Instruction {
+    build(
+        accounts::InitFutarchyAuthority {
+            deployer: *deployer,
+            futarchy_authority: futarchy_authority(),
+            program_data: find_program_data_address().0,
+            system_program: system_program::ID,
+        },
+        instruction::InitFutarchyAuthority { args },
+    )
+}
+
+pub fn update_futarchy_authority(authority_signer: &Pubkey, args: UpdateFutarchyAuthorityArgs) -> Instruction {
+    build(
+        accounts::UpdateFutarchyAuthority {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+            system_program: system_program::ID,
+        },
+        instruction::UpdateFutarchyAuthority { args },
+    )
+}
+
+pub fn update_protocol_revenue(authority_signer: &Pubkey, args: UpdateProtocolRevenueArgs) -> Instruction {
+    build(
+        accounts::UpdateProtocolRevenue {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+            system_program: system_program::ID,
+        },
+        instruction::UpdateProtocolRevenue { args },
+    )
+}
+
+pub fn update_revenue_recipients(authority_signer: &Pubkey, args: UpdateRevenueRecipientsArgs) -> Instruction {
+    build(
+        accounts::UpdateRevenueRecipients {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+            system_program: system_program::ID,
+        },
+        instruction::UpdateRevenueRecipients { args },
+    )
+}
+
+/// `recipients` must be the current `FutarchyAuthority::recipients`; their associated token accounts
+/// are created by the instruction if missing.
+pub fn claim_protocol_fees(caller: &Pubkey, pair: &PairAccounts, recipients: &RevenueRecipients) -> Instruction {
+    let ata = |owner: &Pubkey, mint: &Pubkey| associated_token_address(owner, mint, &token::ID);
+    build(
+        accounts::ClaimProtocolFees {
+            caller: *caller,
+            pair: pair.pair,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            reserve0_vault: pair.reserve0_vault,
+            reserve1_vault: pair.reserve1_vault,
+            token0_mint: pair.token0,
+            token1_mint: pair.token1,
+            futarchy_treasury_token0: ata(&recipients.futarchy_treasury, &pair.token0),
+            futarchy_treasury_token1: ata(&recipients.futarchy_treasury, &pair.token1),
+            futarchy_treasury: recipients.futarchy_treasury,
+            buybacks_vault_token0: ata(&recipients.buybacks_vault, &pair.token0),
+            buybacks_vault_token1: ata(&recipients.buybacks_vault, &pair.token1),
+            buybacks_vault: recipients.buybacks_vault,
+            team_treasury_token0: ata(&recipients.team_treasury, &pair.token0),
+            team_treasury_token1: ata(&recipients.team_treasury, &pair.token1),
+            team_treasury: recipients.team_treasury,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::ClaimProtocolFees {},
+    )
+}
+
+pub fn set_global_reduce_only(authority_signer: &Pubkey, args: SetGlobalReduceOnlyArgs) -> Instruction {
+    build(
+        accounts::SetGlobalReduceOnly {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+        },
+        instruction::SetGlobalReduceOnly { args },
+    )
+}
+
+pub fn set_pair_reduce_only(authority_signer: &Pubkey, pair: &Pubkey, args: SetPairReduceOnlyArgs) -> Instruction {
+    build(
+        accounts::SetPairReduceOnly {
+            authority_signer: *authority_signer,
+            pair: *pair,
+        },
+        instruction::SetPairReduceOnly { args },
+    )
+}
+
+/// Sides left `None` keep their rate model; at least one must be set.
+pub fn set_pair_rate_model(
+    authority_signer: &Pubkey,
+    pair: &PairAccounts,
+    new_rate_model0: Option<&Pubkey>,
+    new_rate_model1: Option<&Pubkey>,
+) -> Instruction {
+    build(
+        accounts::SetPairRateModel {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+            pair: pair.pair,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            new_rate_model0: new_rate_model0.copied(),
+            new_rate_model1: new_rate_model1.copied(),
+            system_program: system_program::ID,
+        },
+        instruction::SetPairRateModel {},
+    )
+}
+
+pub fn set_pair_insurance_share(
+    authority_signer: &Pubkey,
+    pair: &PairAccounts,
+    args: SetPairInsuranceShareArgs,
+) -> Instruction {
+    build(
+        accounts::SetPairInsuranceShare {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+            pair: pair.pair,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::SetPairInsuranceShare { args },
+    )
+}
+
+pub fn set_global_risk_params(authority_signer: &Pubkey, risk_params: RiskParams) -> Instruction {
+    build(
+        accounts::SetGlobalRiskParams {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+        },
+        instruction::SetGlobalRiskParams { risk_params },
+    )
+}
+
+/// `args.risk_params: None` drops the pair's override so it follows the global risk params again.
+pub fn set_pair_risk_params(authority_signer: &Pubkey, pair: &Pubkey, args: SetPairRiskParamsArgs) -> Instruction {
+    build(
+        accounts::SetPairRiskParams {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+            pair: *pair,
+        },
+        instruction::SetPairRiskParams { args },
+    )
+}
+
+pub fn set_pair_caps(authority_signer: &Pubkey, pair: &Pubkey, args: SetPairCapsArgs) -> Instruction {
+    build(
+        accounts::SetPairCaps {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+            pair: *pair,
+        },
+        instruction::SetPairCaps { args },
+    )
+}
+
+/// `rate_model` is a fresh keypair that signs the transaction.
+pub fn create_rate_model(authority_signer: &Pubkey, rate_model: &Pubkey, args: CreateRateModelArgs) -> Instruction {
+    build(
+        accounts::CreateRateModel {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+            rate_model: *rate_model,
+            system_program: system_program::ID,
+        },
+        instruction::CreateRateModel { args },
+    )
+}
+
+/// Queues new parameters for `rate_model` behind its update delay.
+pub fn update_rate_model(authority_signer: &Pubkey, rate_model: &Pubkey, args: UpdateRateModelArgs) -> Instruction {
+    build(
+        accounts::UpdateRateModel {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+            rate_model: *rate_model,
+        },
+        instruction::UpdateRateModel { args },
+    )
+}
+
+/// Applies the queued update of `rate_model`. `pairs` must hold every pair using it on either side,
+/// their interest is accrued with the old parameters first.
+pub fn apply_rate_model_update(authority_signer: &Pubkey, rate_model: &Pubkey, pairs: &[PairAccounts]) -> Instruction {
+    let mut ix = build(
+        accounts::ApplyRateModelUpdate {
+            authority_signer: *authority_signer,
+            futarchy_authority: futarchy_authority(),
+            rate_model: *rate_model,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::ApplyRateModelUpdate {},
+    );
+    for pair in pairs {
+        ix.accounts.extend([
+            AccountMeta::new(pair.pair, false),
+            AccountMeta::new_readonly(pair.rate_model0, false),
+            AccountMeta::new_readonly(pair.rate_model1, false),
+        ]);
+    }
+    ix
+}
+
+/// Grows a pair created before the per-side rate models to the current layout. Permissionless.
+/// `rate_model` is the pair's rate model, migrated first.
+pub fn migrate_pair(payer: &Pubkey, pair: &Pubkey, rate_model: &Pubkey) -> Instruction {
+    build(
+        accounts::MigratePair {
+            pair: *pair,
+            rate_model: *rate_model,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigratePair {},
+    )
+}
+
+/// Grows a rate model created before the kinked variant to the current layout. Permissionless.
+pub fn migrate_rate_model(payer: &Pubkey, rate_model: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateRateModel {
+            rate_model: *rate_model,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateRateModel {},
+    )
+}
+
+pub fn migrate_futarchy_authority(payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateFutarchyAuthority {
+            futarchy_authority: futarchy_authority(),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateFutarchyAuthority {},
+    )
+}
+
+/// Grows a user position created before cross margin to the current layout. Permissionless.
+pub fn migrate_user_position(payer: &Pubkey, user_position: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateUserPosition {
+            user_position: *user_position,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateUserPosition {},
+    )
+}
+
+/* Liquidity */
+
+/// Use [`crate::PairParams::initialize_args`] to build `args` with a matching `params_hash`.
+pub fn initialize(accounts: &InitializeAccounts, args: InitializeAndBootstrapArgs) -> Instruction {
+    let (pair, _) = find_pair_address(&accounts.token0_mint, &accounts.token1_mint, &args.params_hash);
+    build(
+        accounts::InitializeAndBootstrap {
+            deployer: accounts.deployer,
+            token0_mint: accounts.token0_mint,
+            token1_mint: accounts.token1_mint,
+            pair,
+            futarchy_authority: futarchy_authority(),
+            rate_model: find_rate_model_address(&pair).0,
+            lp_mint: accounts.lp_mint,
+            lp_token_metadata: find_lp_metadata_address(&accounts.lp_mint).0,
+            deployer_lp_token_account: associated_token_address(&accounts.deployer, &accounts.lp_mint, &token::ID),
+            reserve0_vault: find_reserve_vault_address(&pair, &accounts.token0_mint).0,
+            reserve1_vault: find_reserve_vault_address(&pair, &accounts.token1_mint).0,
+            collateral0_vault: find_collateral_vault_address(&pair, &accounts.token0_mint).0,
+            collateral1_vault: find_collateral_vault_address(&pair, &accounts.token1_mint).0,
+            deployer_token0_account: accounts.deployer_token0_account,
+            deployer_token1_account: accounts.deployer_token1_account,
+            team_treasury: accounts.team_treasury,
+            team_treasury_wsol_account: accounts.team_treasury_wsol_account,
+            system_program: system_program::ID,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            token_metadata_program: mpl_token_metadata::ID,
+            associated_token_program: associated_token::ID,
+            rent: sysvar::rent::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::Initialize { args },
+    )
+}
+
+/// The LP tokens are minted to the user's associated token account, created if missing.
+pub fn add_liquidity(
+    user: &Pubkey,
+    pair: &PairAccounts,
+    user_token0_account: &Pubkey,
+    user_token1_account: &Pubkey,
+    args: AddLiquidityArgs,
+) -> Instruction {
+    build(
+        accounts::AdjustLiquidity {
+            pair: pair.pair,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            reserve0_vault: pair.reserve0_vault,
+            reserve1_vault: pair.reserve1_vault,
+            user_token0_account: *user_token0_account,
+            user_token1_account: *user_token1_account,
+            token0_mint: pair.token0,
+            token1_mint: pair.token1,
+            lp_mint: pair.lp_mint,
+            user_lp_token_account: associated_token_address(user, &pair.lp_mint, &token::ID),
+            user: *user,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            instructions_sysvar: sysvar::instructions::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::AddLiquidity { args },
+    )
+}
+
+pub fn remove_liquidity(
+    user: &Pubkey,
+    pair: &PairAccounts,
+    user_token0_account: &Pubkey,
+    user_token1_account: &Pubkey,
+    args: RemoveLiquidityArgs,
+) -> Instruction {
+    build(
+        accounts::RemoveLiquidity {
+            pair: pair.pair,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            reserve0_vault: pair.reserve0_vault,
+            reserve1_vault: pair.reserve1_vault,
+            user_token0_account: *user_token0_account,
+            user_token1_account: *user_token1_account,
+            token0_mint: pair.token0,
+            token1_mint: pair.token1,
+            lp_mint: pair.lp_mint,
+            user_lp_token_account: associated_token_address(user, &pair.lp_mint, &token::ID),
+            user: *user,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            instructions_sysvar: sysvar::instructions::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::RemoveLiquidity { args },
+    )
+}
+
+/* Spot */
+
+fn swap_accounts(
+    user: &Pubkey,
+    pair: &PairAccounts,
+    token_in_mint: &Pubkey,
+    user_token_in_account: &Pubkey,
+    user_token_out_account: &Pubkey,
+) -> accounts::Swap {
+    let token_out_mint = pair.other_token(token_in_mint);
+    accounts::Swap {
+        pair: pair.pair,
+        rate_model0: pair.rate_model0,
+        rate_model1: pair.rate_model1,
+        futarchy_authority: futarchy_authority(),
+        token_in_vault: pair.reserve_vault(token_in_mint),
+        token_out_vault: pair.reserve_vault(&token_out_mint),
+        user_token_in_account: *user_token_in_account,
+        user_token_out_account: *user_token_out_account,
+        token_in_mint: *token_in_mint,
+        token_out_mint,
+        user: *user,
+        token_program: token::ID,
+        token_2022_program: token_2022::ID,
+        event_authority: event_authority(),
+        program: omnipair::ID,
+    }
+}
+
+pub fn swap(
+    user: &Pubkey,
+    pair: &PairAccounts,
+    token_in_mint: &Pubkey,
+    user_token_in_account: &Pubkey,
+    user_token_out_account: &Pubkey,
+    args: SwapArgs,
+) -> Instruction {
+    build(
+        swap_accounts(user, pair, token_in_mint, user_token_in_account, user_token_out_account),
+        instruction::Swap { args },
+    )
+}
+
+pub fn swap_exact_out(
+    user: &Pubkey,
+    pair: &PairAccounts,
+    token_in_mint: &Pubkey,
+    user_token_in_account: &Pubkey,
+    user_token_out_account: &Pubkey,
+    args: SwapExactOutArgs,
+) -> Instruction {
+    build(
+        swap_accounts(user, pair, token_in_mint, user_token_in_account, user_token_out_account),
+        instruction::SwapExactOut { args },
+    )
+}
+
+/// Routes `token_in_mint` through `hops` in order; the output token of each hop is the input of the next.
+/// Every hop's accounts are appended as remaining accounts, `SWAP_ROUTE_ACCOUNTS_PER_HOP` per hop.
+pub fn swap_route(
+    user: &Pubkey,
+    hops: &[PairAccounts],
+    token_in_mint: &Pubkey,
+    user_token_in_account: &Pubkey,
+    user_token_out_account: &Pubkey,
+    args: SwapRouteArgs,
+) -> Instruction {
+    let mut remaining_accounts = Vec::with_capacity(hops.len() * omnipair::SWAP_ROUTE_ACCOUNTS_PER_HOP);
+    let mut hop_token_in = *token_in_mint;
+    for hop in hops {
+        let hop_token_out = hop.other_token(&hop_token_in);
+        remaining_accounts.extend([
+            AccountMeta::new(hop.pair, false),
+            AccountMeta::new_readonly(hop.rate_model0, false),
+            AccountMeta::new_readonly(hop.rate_model1, false),
+            AccountMeta::new(hop.reserve_vault(&hop_token_in), false),
+            AccountMeta::new(hop.reserve_vault(&hop_token_out), false),
+            AccountMeta::new_readonly(hop_token_out, false),
+        ]);
+        hop_token_in = hop_token_out;
+    }
+
+    let mut ix = build(
+        accounts::SwapRoute {
+            futarchy_authority: futarchy_authority(),
+            user_token_in_account: *user_token_in_account,
+            user_token_out_account: *user_token_out_account,
+            token_in_mint: *token_in_mint,
+            token_out_mint: hop_token_in,
+            user: *user,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::SwapRoute { args },
+    );
+    ix.accounts.extend(remaining_accounts);
+    ix
+}
+
+/* Lending */
+
+/// `user` pays the collateral into the position of `position_owner`, usually `user` itself. Others can
+/// only top up a position that already exists.
+pub fn add_collateral(
+    user: &Pubkey,
+    position_owner: &Pubkey,
+    pair: &PairAccounts,
+    collateral_token_mint: &Pubkey,
+    user_collateral_token_account: &Pubkey,
+    args: AdjustCollateralArgs,
+) -> Instruction {
+    build(
+        accounts::AddCollateral {
+            pair: pair.pair,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            user_position: pair.user_position(position_owner, args.position_index),
+            collateral_vault: pair.collateral_vault(collateral_token_mint),
+            user_collateral_token_account: *user_collateral_token_account,
+            collateral_token_mint: *collateral_token_mint,
+            position_owner: *position_owner,
+            user: *user,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::AddCollateral { args },
+    )
+}
+
+/// `user` is the owner of the position or a delegate allowed to manage it; the collateral goes to
+/// `user_collateral_token_account`, which must belong to `position_owner`.
+pub fn remove_collateral(
+    user: &Pubkey,
+    position_owner: &Pubkey,
+    pair: &PairAccounts,
+    collateral_token_mint: &Pubkey,
+    user_collateral_token_account: &Pubkey,
+    args: AdjustCollateralArgs,
+) -> Instruction {
+    build(
+        accounts::CommonAdjustCollateral {
+            pair: pair.pair,
+            user_position: pair.user_position(position_owner, args.position_index),
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            collateral_vault: pair.collateral_vault(collateral_token_mint),
+            user_collateral_token_account: *user_collateral_token_account,
+            collateral_token_mint: *collateral_token_mint,
+            user: *user,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            system_program: system_program::ID,
+            instructions_sysvar: sysvar::instructions::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::RemoveCollateral { args },
+    )
+}
+
+/// `user` is the owner of `position` or a delegate allowed to manage it; the borrowed tokens go to
+/// `user_reserve_token_account`, which must belong to the owner.
+pub fn borrow(
+    user: &Pubkey,
+    position: &PositionKey,
+    pair: &PairAccounts,
+    reserve_token_mint: &Pubkey,
+    user_reserve_token_account: &Pubkey,
+    args: AdjustDebtArgs,
+) -> Instruction {
+    build(
+        accounts::Borrow {
+            pair: pair.pair,
+            user_position: pair.user_position(&position.owner, position.position_index),
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            reserve_vault: pair.reserve_vault(reserve_token_mint),
+            user_reserve_token_account: *user_reserve_token_account,
+            reserve_token_mint: *reserve_token_mint,
+            user: *user,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            system_program: system_program::ID,
+            instructions_sysvar: sysvar::instructions::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::Borrow { args },
+    )
+}
+
+/// `user` pays the repayment of `position`, which may belong to anyone.
+pub fn repay(
+    user: &Pubkey,
+    position: &PositionKey,
+    pair: &PairAccounts,
+    reserve_token_mint: &Pubkey,
+    user_reserve_token_account: &Pubkey,
+    args: AdjustDebtArgs,
+) -> Instruction {
+    build(
+        accounts::CommonAdjustDebt {
+            pair: pair.pair,
+            user_position: pair.user_position(&position.owner, position.position_index),
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            reserve_vault: pair.reserve_vault(reserve_token_mint),
+            user_reserve_token_account: *user_reserve_token_account,
+            reserve_token_mint: *reserve_token_mint,
+            user: *user,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::Repay { args },
+    )
+}
+
+/// `collateral_token_mint` is the side of `position` being seized;
+/// the liquidation incentive is paid to `caller_token_account`.
+pub fn liquidate(
+    payer: &Pubkey,
+    pair: &PairAccounts,
+    position: &PositionKey,
+    collateral_token_mint: &Pubkey,
+    caller_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::Liquidate {
+            pair: pair.pair,
+            user_position: pair.user_position(&position.owner, position.position_index),
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            collateral_vault: pair.collateral_vault(collateral_token_mint),
+            caller_token_account: *caller_token_account,
+            collateral_token_mint: *collateral_token_mint,
+            reserve_vault: pair.reserve_vault(collateral_token_mint),
+            position_owner: position.owner,
+            payer: *payer,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::Liquidate {},
+    )
+}
+
+pub fn liquidate_with_repay(
+    payer: &Pubkey,
+    pair: &PairAccounts,
+    position: &PositionKey,
+    collateral_token_mint: &Pubkey,
+    caller_collateral_token_account: &Pubkey,
+    caller_debt_token_account: &Pubkey,
+    args: LiquidateWithRepayArgs,
+) -> Instruction {
+    let debt_token_mint = pair.other_token(collateral_token_mint);
+    build(
+        accounts::LiquidateWithRepay {
+            pair: pair.pair,
+            user_position: pair.user_position(&position.owner, position.position_index),
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            collateral_vault: pair.collateral_vault(collateral_token_mint),
+            collateral_reserve_vault: pair.reserve_vault(collateral_token_mint),
+            debt_reserve_vault: pair.reserve_vault(&debt_token_mint),
+            caller_collateral_token_account: *caller_collateral_token_account,
+            caller_debt_token_account: *caller_debt_token_account,
+            collateral_token_mint: *collateral_token_mint,
+            debt_token_mint,
+            position_owner: position.owner,
+            payer: *payer,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::LiquidateWithRepay { args },
+    )
+}
+
+pub fn open_leveraged(
+    user: &Pubkey,
+    pair: &PairAccounts,
+    collateral_token_mint: &Pubkey,
+    user_collateral_token_account: &Pubkey,
+    args: OpenLeveragedArgs,
+) -> Instruction {
+    build(
+        accounts::OpenLeveraged {
+            pair: pair.pair,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            user_position: pair.user_position(user, args.position_index),
+            collateral_vault: pair.collateral_vault(collateral_token_mint),
+            collateral_reserve_vault: pair.reserve_vault(collateral_token_mint),
+            user_collateral_token_account: *user_collateral_token_account,
+            collateral_token_mint: *collateral_token_mint,
+            user: *user,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            system_program: system_program::ID,
+            instructions_sysvar: sysvar::instructions::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::OpenLeveraged { args },
+    )
+}
+
+pub fn close_position(
+    user: &Pubkey,
+    position_index: u16,
+    pair: &PairAccounts,
+    collateral_token_mint: &Pubkey,
+    user_collateral_token_account: &Pubkey,
+    args: ClosePositionArgs,
+) -> Instruction {
+    build(
+        accounts::ClosePosition {
+            pair: pair.pair,
+            user_position: pair.user_position(user, position_index),
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            collateral_vault: pair.collateral_vault(collateral_token_mint),
+            collateral_reserve_vault: pair.reserve_vault(collateral_token_mint),
+            user_collateral_token_account: *user_collateral_token_account,
+            collateral_token_mint: *collateral_token_mint,
+            user: *user,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            instructions_sysvar: sysvar::instructions::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::ClosePosition { args },
+    )
+}
+
+pub fn set_margin_mode(user: &Pubkey, position_index: u16, pair: &PairAccounts, args: SetMarginModeArgs) -> Instruction {
+    build(
+        accounts::SetMarginMode {
+            pair: pair.pair,
+            user_position: pair.user_position(user, position_index),
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            user: *user,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::SetMarginMode { args },
+    )
+}
+
+pub fn set_position_delegate(
+    user: &Pubkey,
+    position_index: u16,
+    pair: &Pubkey,
+    args: SetPositionDelegateArgs,
+) -> Instruction {
+    build(
+        accounts::SetPositionDelegate {
+            user_position: find_user_position_address(pair, user, position_index).0,
+            user: *user,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::SetPositionDelegate { args },
+    )
+}
+
+/// Moves position `position_index` of `user` into position `args.new_position_index` of `new_owner`,
+/// who signs as well.
+pub fn transfer_position(
+    user: &Pubkey,
+    position_index: u16,
+    pair: &Pubkey,
+    new_owner: &Pubkey,
+    args: TransferPositionArgs,
+) -> Instruction {
+    build(
+        accounts::TransferPosition {
+            pair: *pair,
+            user_position: find_user_position_address(pair, user, position_index).0,
+            new_user_position: find_user_position_address(pair, new_owner, args.new_position_index).0,
+            new_owner: *new_owner,
+            user: *user,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::TransferPosition { args },
+    )
+}
+
+pub fn close_position_account(user: &Pubkey, position_index: u16, pair: &Pubkey) -> Instruction {
+    build(
+        accounts::ClosePositionAccount {
+            user_position: find_user_position_address(pair, user, position_index).0,
+            user: *user,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::ClosePositionAccount {},
+    )
+}
+
+/// Places order `args.order_index` on position `position_index` of `user`.
+pub fn place_position_order(
+    user: &Pubkey,
+    position_index: u16,
+    pair: &Pubkey,
+    args: PlacePositionOrderArgs,
+) -> Instruction {
+    let user_position = find_user_position_address(pair, user, position_index).0;
+    build(
+        accounts::PlacePositionOrder {
+            user_position,
+            position_order: find_position_order_address(&user_position, args.order_index).0,
+            user: *user,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::PlacePositionOrder { args },
+    )
+}
+
+pub fn cancel_position_order(user: &Pubkey, position_index: u16, pair: &Pubkey, order_index: u16) -> Instruction {
+    let user_position = find_user_position_address(pair, user, position_index).0;
+    build(
+        accounts::CancelPositionOrder {
+            position_order: find_position_order_address(&user_position, order_index).0,
+            user_position,
+            user: *user,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::CancelPositionOrder {},
+    )
+}
+
+/// Executes a fetched `order`. The bounty is paid to `keeper_collateral_token_account` and the rest
+/// of the released collateral to `owner_collateral_token_account`, both of the order's collateral token.
+pub fn execute_position_order(
+    keeper: &Pubkey,
+    pair: &PairAccounts,
+    order: &PositionOrder,
+    owner_collateral_token_account: &Pubkey,
+    keeper_collateral_token_account: &Pubkey,
+) -> Instruction {
+    let collateral_token_mint = match order.is_collateral_token0 {
+        true => pair.token0,
+        false => pair.token1,
+    };
+    build(
+        accounts::ExecutePositionOrder {
+            pair: pair.pair,
+            user_position: order.user_position,
+            position_order: find_position_order_address(&order.user_position, order.order_index).0,
+            owner: order.owner,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            collateral_vault: pair.collateral_vault(&collateral_token_mint),
+            collateral_reserve_vault: pair.reserve_vault(&collateral_token_mint),
+            owner_collateral_token_account: *owner_collateral_token_account,
+            keeper_collateral_token_account: *keeper_collateral_token_account,
+            collateral_token_mint,
+            keeper: *keeper,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            instructions_sysvar: sysvar::instructions::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::ExecutePositionOrder {},
+    )
+}
+
+/// `remaining_accounts` are forwarded to the receiver program's callback.
+pub fn flashloan(
+    user: &Pubkey,
+    pair: &PairAccounts,
+    receiver_program: &Pubkey,
+    receiver_token0_account: &Pubkey,
+    receiver_token1_account: &Pubkey,
+    args: FlashloanArgs,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut ix = build(
+        accounts::Flashloan {
+            pair: pair.pair,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            futarchy_authority: futarchy_authority(),
+            reserve0_vault: pair.reserve0_vault,
+            reserve1_vault: pair.reserve1_vault,
+            token0_mint: pair.token0,
+            token1_mint: pair.token1,
+            receiver_token0_account: *receiver_token0_account,
+            receiver_token1_account: *receiver_token1_account,
+            receiver_program: *receiver_program,
+            user: *user,
+            token_program: token::ID,
+            token_2022_program: token_2022::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: omnipair::ID,
+        },
+        instruction::Flashloan { args },
+    );
+    ix.accounts.extend(remaining_accounts);
+    ix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    fn test_pair() -> PairAccounts {
+        let (token0, token1) = canonical_token_order(Pubkey::new_unique(), Pubkey::new_unique());
+        let pair = Pubkey::new_unique();
+        PairAccounts {
+            pair,
+            token0,
+            token1,
+            lp_mint: Pubkey::new_unique(),
+            rate_model0: find_rate_model_address(&pair).0,
+            rate_model1: Pubkey::new_unique(),
+            reserve0_vault: find_reserve_vault_address(&pair, &token0).0,
+            reserve1_vault: find_reserve_vault_address(&pair, &token1).0,
+            collateral0_vault: find_collateral_vault_address(&pair, &token0).0,
+            collateral1_vault: find_collateral_vault_address(&pair, &token1).0,
+        }
+    }
+
+    #[test]
+    fn swap_resolves_vaults_from_input_mint() {
+        let pair = test_pair();
+        let user = Pubkey::new_unique();
+        let ix = swap(
+            &user,
+            &pair,
+            &pair.token1,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            SwapArgs { amount_in: 10, min_amount_out: 9 },
+        );
+
+        assert_eq!(ix.program_id, omnipair::ID);
+        assert_eq!(&ix.data[..8], instruction::Swap::DISCRIMINATOR);
+        assert_eq!(ix.accounts[4].pubkey, pair.reserve1_vault);
+        assert_eq!(ix.accounts[5].pubkey, pair.reserve0_vault);
+        assert_eq!(ix.accounts[9].pubkey, pair.token0);
+        assert!(ix.accounts.iter().any(|meta| meta.pubkey == user && meta.is_signer));
+    }
+
+    #[test]
+    fn swap_route_chains_hops() {
+        let first = test_pair();
+        let mut second = test_pair();
+        second.token0 = first.token1;
+        second.reserve0_vault = find_reserve_vault_address(&second.pair, &second.token0).0;
+
+        let ix = swap_route(
+            &Pubkey::new_unique(),
+            &[first.clone(), second.clone()],
+            &first.token0,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            SwapRouteArgs { amount_in: 10, min_amount_out: 1 },
+        );
+
+        let per_hop = omnipair::SWAP_ROUTE_ACCOUNTS_PER_HOP;
+        let remaining = &ix.accounts[ix.accounts.len() - 2 * per_hop..];
+        assert_eq!(remaining[3].pubkey, first.reserve0_vault);
+        assert_eq!(remaining[5].pubkey, first.token1);
+        assert_eq!(remaining[per_hop + 3].pubkey, second.reserve0_vault);
+        assert_eq!(remaining[per_hop + 5].pubkey, second.token1);
+        // token_out_mint of the route is the output of the last hop
+        assert_eq!(ix.accounts[4].pubkey, second.token1);
+    }
+}
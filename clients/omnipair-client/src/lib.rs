@@ -0,0 +1,23 @@
+//! Rust client for the Omnipair program.
+//!
+//! - [`pda`]: program-derived addresses used by the program
+//! - [`params`]: pair parameters and the `params_hash` checked by `initialize`
+//! - [`instructions`]: typed builders for every program instruction
+//! - [`views`]: decoders for the return data of the view instructions
+//!
+//! Builders only assemble [`Instruction`]s; signing and sending is left to the caller.
+
+pub mod instructions;
+pub mod params;
+pub mod pda;
+pub mod views;
+
+pub use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+pub use anchor_lang::solana_program::pubkey::Pubkey;
+pub use instructions::*;
+pub use params::*;
+pub use pda::*;
+pub use views::*;
+
+pub use omnipair;
+pub use omnipair::ID as PROGRAM_ID;
@@ -0,0 +1,89 @@
+use omnipair::{constants::VERSION, InitializeAndBootstrapArgs};
+
+/// Parameters fixed at pair creation. Together with the two mints they determine the pair address.
+///
+/// Optional parameters left as `None` use the program defaults, but hash as zero, so
+/// `Some(0)` and `None` produce the same `params_hash`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PairParams {
+    pub swap_fee_bps: u16,
+    pub half_life: u64,
+    pub fixed_cf_bps: Option<u16>,
+    pub target_util_start_bps: Option<u64>,
+    pub target_util_end_bps: Option<u64>,
+    pub rate_half_life_ms: Option<u64>,
+    pub min_rate_bps: Option<u64>,
+    pub max_rate_bps: Option<u64>,
+    /// Not part of `params_hash`
+    pub initial_rate_bps: Option<u64>,
+}
+
+impl PairParams {
+    /// Same computation as `InitializeAndBootstrap::validate`.
+    pub fn params_hash(&self) -> [u8; 32] {
+        self.initialize_args(0, 0, 0, String::new(), String::new(), String::new())
+            .compute_params_hash()
+    }
+
+    /// Arguments of `initialize` for these parameters, with `params_hash` and `version` filled in.
+    pub fn initialize_args(
+        &self,
+        amount0_in: u64,
+        amount1_in: u64,
+        min_liquidity_out: u64,
+        lp_name: String,
+        lp_symbol: String,
+        lp_uri: String,
+    ) -> InitializeAndBootstrapArgs {
+        let mut args = InitializeAndBootstrapArgs {
+            swap_fee_bps: self.swap_fee_bps,
+            half_life: self.half_life,
+            fixed_cf_bps: self.fixed_cf_bps,
+            target_util_start_bps: self.target_util_start_bps,
+            target_util_end_bps: self.target_util_end_bps,
+            rate_half_life_ms: self.rate_half_life_ms,
+            min_rate_bps: self.min_rate_bps,
+            max_rate_bps: self.max_rate_bps,
+            initial_rate_bps: self.initial_rate_bps,
+            params_hash: [0; 32],
+            version: VERSION,
+            amount0_in,
+            amount1_in,
+            min_liquidity_out,
+            lp_name,
+            lp_symbol,
+            lp_uri,
+        };
+        args.params_hash = args.compute_params_hash();
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::hash::hash;
+
+    #[test]
+    fn params_hash_matches_documented_layout() {
+        let params = PairParams {
+            swap_fee_bps: 30,
+            half_life: 3_600_000,
+            fixed_cf_bps: Some(8_000),
+            max_rate_bps: Some(50_000),
+            ..Default::default()
+        };
+
+        let mut data = vec![VERSION];
+        data.extend_from_slice(&30u16.to_le_bytes());
+        data.extend_from_slice(&3_600_000u64.to_le_bytes());
+        data.extend_from_slice(&8_000u16.to_le_bytes());
+        for value in [0u64, 0, 0, 0, 50_000] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(params.params_hash(), hash(&data).to_bytes());
+
+        let with_initial_rate = PairParams { initial_rate_bps: Some(500), ..params.clone() };
+        assert_eq!(with_initial_rate.params_hash(), params.params_hash());
+    }
+}
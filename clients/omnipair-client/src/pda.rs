@@ -0,0 +1,76 @@
+use anchor_lang::solana_program::{bpf_loader_upgradeable, pubkey::Pubkey};
+use anchor_spl::{associated_token::get_associated_token_address_with_program_id, metadata::mpl_token_metadata};
+use omnipair::{constants::*, state::UserPosition};
+
+/// Seed of the `#[event_cpi]` authority, as generated by Anchor.
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Orders two mints the way pairs require them (`token0 < token1`).
+pub fn canonical_token_order(mint_a: Pubkey, mint_b: Pubkey) -> (Pubkey, Pubkey) {
+    match mint_a < mint_b {
+        true => (mint_a, mint_b),
+        false => (mint_b, mint_a),
+    }
+}
+
+pub fn find_pair_address(token0: &Pubkey, token1: &Pubkey, params_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAIR_SEED_PREFIX, token0.as_ref(), token1.as_ref(), params_hash.as_ref()],
+        &omnipair::ID,
+    )
+}
+
+pub fn find_futarchy_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FUTARCHY_AUTHORITY_SEED_PREFIX], &omnipair::ID)
+}
+
+/// Rate model created by `initialize` for both sides. A pair may later point either side to another
+/// one via `set_pair_rate_model`, so prefer `Pair::rate_model0`/`Pair::rate_model1` once the pair exists.
+pub fn find_rate_model_address(pair: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RATE_MODEL_SEED_PREFIX, pair.as_ref()], &omnipair::ID)
+}
+
+pub fn find_reserve_vault_address(pair: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RESERVE_VAULT_SEED_PREFIX, pair.as_ref(), mint.as_ref()], &omnipair::ID)
+}
+
+pub fn find_collateral_vault_address(pair: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COLLATERAL_VAULT_SEED_PREFIX, pair.as_ref(), mint.as_ref()], &omnipair::ID)
+}
+
+/// Position `position_index` of `owner` in the pair; index 0 is the default position.
+pub fn find_user_position_address(pair: &Pubkey, owner: &Pubkey, position_index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POSITION_SEED_PREFIX, pair.as_ref(), owner.as_ref(), &UserPosition::index_seed(position_index)],
+        &omnipair::ID,
+    )
+}
+
+/// Order `order_index` placed on a user position.
+pub fn find_position_order_address(user_position: &Pubkey, order_index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POSITION_ORDER_SEED_PREFIX, user_position.as_ref(), &order_index.to_le_bytes()],
+        &omnipair::ID,
+    )
+}
+
+pub fn find_event_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &omnipair::ID)
+}
+
+/// Program data account of the upgradeable program, checked by `init_futarchy_authority`.
+pub fn find_program_data_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[omnipair::ID.as_ref()], &bpf_loader_upgradeable::ID)
+}
+
+/// Metaplex metadata account of an LP mint.
+pub fn find_lp_metadata_address(lp_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[METADATA_SEED_PREFIX, mpl_token_metadata::ID.as_ref(), lp_mint.as_ref()],
+        &mpl_token_metadata::ID,
+    )
+}
+
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
@@ -0,0 +1,118 @@
+use std::{fmt, io};
+
+use anchor_lang::{solana_program::pubkey::Pubkey, AnchorDeserialize};
+use omnipair::{constants::VIEW_RETURN_DATA_VERSION, HealthReport, PairSnapshot, ViewReturnData};
+
+#[derive(Debug)]
+pub enum ViewDecodeError {
+    /// Return data was set by another program
+    UnexpectedProgram(Pubkey),
+    /// Layout version this client does not know
+    UnsupportedVersion(u8),
+    InvalidData(io::Error),
+}
+
+impl fmt::Display for ViewDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ViewDecodeError::UnexpectedProgram(program_id) => write!(f, "return data set by unexpected program {}", program_id),
+            ViewDecodeError::UnsupportedVersion(version) => write!(f, "unsupported view return data version {}", version),
+            ViewDecodeError::InvalidData(err) => write!(f, "invalid view return data: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ViewDecodeError {}
+
+/// Reads `data` followed by an endless run of zero bytes.
+///
+/// The runtime strips trailing zero bytes from transaction return data,
+/// so a view result ending in zeros comes back shorter than it was set.
+struct ZeroPadded<'a>(&'a [u8]);
+
+impl io::Read for ZeroPadded<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.0.len().min(buf.len());
+        buf[..len].copy_from_slice(&self.0[..len]);
+        buf[len..].fill(0);
+        self.0 = &self.0[len..];
+        Ok(buf.len())
+    }
+}
+
+fn decode<T: AnchorDeserialize>(data: &[u8]) -> Result<T, ViewDecodeError> {
+    // Every view layout starts with the version byte; empty data is an all-zero (version 0) payload
+    let version = data.first().copied().unwrap_or(0);
+    if version != VIEW_RETURN_DATA_VERSION {
+        return Err(ViewDecodeError::UnsupportedVersion(version));
+    }
+    T::deserialize_reader(&mut ZeroPadded(data)).map_err(ViewDecodeError::InvalidData)
+}
+
+/// Checks that simulation return data came from the Omnipair program and returns its payload.
+pub fn omnipair_return_data<'a>(program_id: &Pubkey, data: &'a [u8]) -> Result<&'a [u8], ViewDecodeError> {
+    match *program_id == omnipair::ID {
+        true => Ok(data),
+        false => Err(ViewDecodeError::UnexpectedProgram(*program_id)),
+    }
+}
+
+/// Return data of every `view_pair_data` / `view_user_position_data` getter except the struct-valued ones below.
+pub fn decode_view_return_data(data: &[u8]) -> Result<ViewReturnData, ViewDecodeError> {
+    decode(data)
+}
+
+/// Return data of `PairViewKind::FullSnapshot`.
+pub fn decode_pair_snapshot(data: &[u8]) -> Result<PairSnapshot, ViewDecodeError> {
+    decode(data)
+}
+
+/// Return data of `UserPositionViewKind::HealthReport`.
+pub fn decode_health_report(data: &[u8]) -> Result<HealthReport, ViewDecodeError> {
+    decode(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+    use omnipair::{OptionalUint, PositionSideHealth};
+
+    #[test]
+    fn decodes_return_data_with_trailing_zeros_stripped() {
+        let value = ViewReturnData {
+            version: VIEW_RETURN_DATA_VERSION,
+            value0: OptionalUint::U64(42),
+            value1: OptionalUint::OptionalU64(None),
+            value2: OptionalUint::U128(0),
+        };
+        let mut data = Vec::new();
+        value.serialize(&mut data).unwrap();
+        let trimmed_len = data.iter().rposition(|&byte| byte != 0).unwrap() + 1;
+
+        let decoded = decode_view_return_data(&data[..trimmed_len]).unwrap();
+        assert!(matches!(decoded.value0, OptionalUint::U64(42)));
+        assert!(matches!(decoded.value1, OptionalUint::OptionalU64(None)));
+        assert!(matches!(decoded.value2, OptionalUint::U128(0)));
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let report = HealthReport {
+            version: VIEW_RETURN_DATA_VERSION + 1,
+            debt0: PositionSideHealth::default(),
+            debt1: PositionSideHealth::default(),
+        };
+        let mut data = Vec::new();
+        report.serialize(&mut data).unwrap();
+
+        assert!(matches!(
+            decode_health_report(&data),
+            Err(ViewDecodeError::UnsupportedVersion(version)) if version == VIEW_RETURN_DATA_VERSION + 1
+        ));
+        assert!(matches!(
+            omnipair_return_data(&Pubkey::new_unique(), &data),
+            Err(ViewDecodeError::UnexpectedProgram(_))
+        ));
+    }
+}
@@ -0,0 +1,83 @@
+//! `close_position_account`: only empty positions without open orders are closed, and the rent goes
+//! back to the owner.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use omnipair::{errors::ErrorCode, AdjustCollateralArgs};
+use omnipair_client::PositionKey;
+
+const COLLATERAL: u64 = 1_000_000_000;
+
+/// `owner`'s default position after adding and removing all of its collateral.
+fn empty_position(market: &mut Market, owner: &Pubkey) -> PositionKey {
+    let position = PositionKey::new(*owner);
+    let token0 = market.token0();
+    market.add_collateral(owner, &position, &token0, COLLATERAL).unwrap();
+    let instruction = omnipair_client::remove_collateral(
+        owner,
+        owner,
+        &market.pair,
+        &token0,
+        &market.token_account(owner, &token0),
+        AdjustCollateralArgs { amount: COLLATERAL, position_index: 0 },
+    );
+    market.program_test.process_transaction(&[instruction], &[*owner]).unwrap();
+    position
+}
+
+fn close_position_account(market: &mut Market, position: &PositionKey) -> Result<(), TransactionError> {
+    let instruction =
+        omnipair_client::close_position_account(&position.owner, position.position_index, &market.pair.pair);
+    market.program_test.process_transaction(&[instruction], &[position.owner])
+}
+
+#[test]
+fn empty_position_is_closed_and_its_rent_returned() {
+    let mut market = Market::new();
+    let owner = market.user(COLLATERAL);
+    let position = empty_position(&mut market, &owner);
+    let address = market.pair.user_position(&owner, 0);
+    let rent = market.program_test.lamports(&address);
+    let owner_lamports = market.program_test.lamports(&owner);
+    assert!(rent > 0);
+
+    close_position_account(&mut market, &position).unwrap();
+
+    assert!(market.program_test.get_account(&address).is_none());
+    assert_eq!(market.program_test.lamports(&owner), owner_lamports + rent);
+
+    // `add_collateral` opens it again at the same address
+    let token0 = market.token0();
+    market.add_collateral(&owner, &position, &token0, COLLATERAL).unwrap();
+    assert_eq!(market.position(&position).unwrap().collateral0, COLLATERAL);
+}
+
+#[test]
+fn position_with_collateral_is_not_closed() {
+    let mut market = Market::new();
+    let owner = market.user(COLLATERAL);
+    let position = PositionKey::new(owner);
+    let token0 = market.token0();
+    market.add_collateral(&owner, &position, &token0, COLLATERAL).unwrap();
+
+    assert_error(close_position_account(&mut market, &position), ErrorCode::UserPositionNotEmpty);
+    assert_eq!(market.position(&position).unwrap().collateral0, COLLATERAL);
+}
+
+#[test]
+fn position_with_open_orders_is_not_closed() {
+    let mut market = Market::new();
+    let owner = market.user(COLLATERAL);
+    let position = empty_position(&mut market, &owner);
+    market.place_order(&position, 0, never_triggered(), 0).unwrap();
+
+    assert_error(close_position_account(&mut market, &position), ErrorCode::PositionHasOpenOrders);
+    assert_eq!(market.position(&position).unwrap().open_orders, 1);
+
+    let instruction = omnipair_client::cancel_position_order(&owner, 0, &market.pair.pair, 0);
+    market.program_test.process_transaction(&[instruction], &[owner]).unwrap();
+    close_position_account(&mut market, &position).unwrap();
+    assert!(market.position(&position).is_none());
+}
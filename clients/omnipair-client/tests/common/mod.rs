@@ -0,0 +1,159 @@
+//! A pair with liquidity, and the lending instructions the position tests build on.
+
+#![allow(dead_code)]
+
+mod program_test;
+
+use anchor_lang::prelude::Pubkey;
+use omnipair::{
+    state::{OrderPriceSource, OrderTrigger, PositionOrder, TriggerCondition, UserPosition},
+    AdjustCollateralArgs, AdjustDebtArgs, PlacePositionOrderArgs,
+};
+use omnipair_client::{find_position_order_address, PairAccounts, PositionKey};
+pub use program_test::*;
+
+pub const RESERVE: u64 = 1_000_000_000_000;
+/// Lamports of every user created by `Market::user`, enough for the rent of the accounts they open
+pub const USER_LAMPORTS: u64 = 1_000_000_000;
+
+pub struct Market {
+    pub program_test: ProgramTest,
+    /// Futarchy authority of the protocol
+    pub authority: Pubkey,
+    pub pair: PairAccounts,
+}
+
+impl Market {
+    pub fn new() -> Self {
+        let mut program_test = ProgramTest::new();
+        let authority = program_test.new_wallet(USER_LAMPORTS);
+        program_test.create_futarchy_authority(&authority);
+        let pair = program_test.create_pair(Pubkey::new_unique(), RESERVE, Pubkey::new_unique(), RESERVE);
+        Self { program_test, authority, pair }
+    }
+
+    pub fn token0(&self) -> Pubkey {
+        self.pair.token0
+    }
+
+    pub fn token1(&self) -> Pubkey {
+        self.pair.token1
+    }
+
+    /// A wallet holding `USER_LAMPORTS` and `amount` of both tokens in its associated token accounts.
+    pub fn user(&mut self, amount: u64) -> Pubkey {
+        let user = self.program_test.new_wallet(USER_LAMPORTS);
+        for mint in [self.token0(), self.token1()] {
+            self.program_test.create_associated_token_account(&user, &mint, amount);
+        }
+        user
+    }
+
+    pub fn token_account(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        omnipair_client::associated_token_address(owner, mint, &anchor_spl::token::ID)
+    }
+
+    pub fn balance(&self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        self.program_test.token_balance(&self.token_account(owner, mint))
+    }
+
+    pub fn position(&self, position: &PositionKey) -> Option<UserPosition> {
+        let address = self.pair.user_position(&position.owner, position.position_index);
+        self.program_test.get_anchor_account(&address)
+    }
+
+    pub fn order(&self, position: &PositionKey, order_index: u16) -> Option<PositionOrder> {
+        let user_position = self.pair.user_position(&position.owner, position.position_index);
+        self.program_test.get_anchor_account(&find_position_order_address(&user_position, order_index).0)
+    }
+
+    /// `user` adds `amount` of `mint` from their own account to `position`.
+    pub fn add_collateral(
+        &mut self,
+        user: &Pubkey,
+        position: &PositionKey,
+        mint: &Pubkey,
+        amount: u64,
+    ) -> Result<(), TransactionError> {
+        let instruction = omnipair_client::add_collateral(
+            user,
+            &position.owner,
+            &self.pair,
+            mint,
+            &self.token_account(user, mint),
+            AdjustCollateralArgs { amount, position_index: position.position_index },
+        );
+        self.program_test.process_transaction(&[instruction], &[*user])
+    }
+
+    /// `user` borrows `amount` of `mint` against `position`, paid to `recipient`'s account.
+    pub fn borrow(
+        &mut self,
+        user: &Pubkey,
+        position: &PositionKey,
+        mint: &Pubkey,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> Result<(), TransactionError> {
+        let instruction = omnipair_client::borrow(
+            user,
+            position,
+            &self.pair,
+            mint,
+            &self.token_account(recipient, mint),
+            AdjustDebtArgs { amount },
+        );
+        self.program_test.process_transaction(&[instruction], &[*user])
+    }
+
+    /// `owner` places order `order_index` on `position`, closing its token0 collateral once `trigger` is met.
+    pub fn place_order(
+        &mut self,
+        position: &PositionKey,
+        order_index: u16,
+        trigger: OrderTrigger,
+        keeper_bounty: u64,
+    ) -> Result<(), TransactionError> {
+        let instruction = omnipair_client::place_position_order(
+            &position.owner,
+            position.position_index,
+            &self.pair.pair,
+            PlacePositionOrderArgs {
+                order_index,
+                is_collateral_token0: true,
+                trigger,
+                min_collateral_out: 0,
+                keeper_bounty,
+            },
+        );
+        self.program_test.process_transaction(&[instruction], &[position.owner])
+    }
+
+    /// `owner`'s default position with `collateral` of token0 backing `debt` of token1.
+    pub fn open_position(&mut self, owner: &Pubkey, collateral: u64, debt: u64) -> PositionKey {
+        let position = PositionKey::new(*owner);
+        let (token0, token1) = (self.token0(), self.token1());
+        self.add_collateral(owner, &position, &token0, collateral).unwrap();
+        self.borrow(owner, &position, &token1, owner, debt).unwrap();
+        position
+    }
+}
+
+/// Triggered as long as token0 has a price, i.e. always.
+pub fn always_triggered() -> OrderTrigger {
+    OrderTrigger {
+        price_source: OrderPriceSource::Ema,
+        is_price0: true,
+        condition: TriggerCondition::Above,
+        price_nad: 1,
+    }
+}
+
+/// Triggered once token0 is worth less than a billionth of token1, i.e. never in these tests.
+pub fn never_triggered() -> OrderTrigger {
+    OrderTrigger { condition: TriggerCondition::Below, ..always_triggered() }
+}
+
+pub fn assert_error(result: Result<(), TransactionError>, error: impl Into<u32>) {
+    assert_eq!(result.unwrap_err().custom_code(), Some(error.into()));
+}
@@ -0,0 +1,317 @@
+//! `ProgramTest`: a LiteSVM bank running the program built by `anchor build`, with the accounts the
+//! initializing instructions leave stored directly.
+
+use std::{collections::HashMap, fmt};
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, program_pack::Pack},
+    AccountDeserialize, AccountSerialize, Discriminator, Owner, Space,
+};
+use anchor_spl::token::{self, spl_token};
+use litesvm::LiteSVM;
+use omnipair::{
+    constants::*,
+    state::{FutarchyAuthority, Pair, RateModel, VaultBumps},
+};
+use omnipair_client::*;
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const PROGRAM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/deploy/omnipair.so");
+/// Lamports of the fee payer of every transaction
+const PAYER_LAMPORTS: u64 = 1_000_000_000_000;
+/// Compute unit limit requested by every transaction, the most a transaction may use
+const COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+pub const SWAP_FEE_BPS: u16 = 30;
+pub const FIXED_CF_BPS: u16 = 8_000;
+/// Protocol share of swap fees and of interest
+pub const PROTOCOL_SWAP_BPS: u16 = 2_000;
+pub const PROTOCOL_INTEREST_BPS: u16 = 1_000;
+
+/// A failed transaction. None of its changes are kept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionError {
+    pub error: solana_sdk::transaction::TransactionError,
+    pub logs: Vec<String>,
+}
+
+impl TransactionError {
+    /// Code of a program or Anchor error, e.g. `u32::from(omnipair::errors::ErrorCode::AmountZero)`.
+    pub fn custom_code(&self) -> Option<u32> {
+        match self.error {
+            solana_sdk::transaction::TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+                Some(code)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n{}", self.error, self.logs.join("\n"))
+    }
+}
+
+/// LiteSVM with the Omnipair program loaded from `target/deploy/omnipair.so`.
+///
+/// Every transaction is paid for by a payer of its own, so wallets only spend the rent of the accounts
+/// they open. Wallets signing transactions are created by `new_wallet`, which keeps their keypair.
+pub struct ProgramTest {
+    svm: LiteSVM,
+    payer: Keypair,
+    wallets: HashMap<Pubkey, Keypair>,
+}
+
+impl ProgramTest {
+    pub fn new() -> Self {
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(omnipair::ID, PROGRAM_PATH)
+            .expect("target/deploy/omnipair.so not found, run `anchor build` first");
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), PAYER_LAMPORTS).unwrap();
+        Self { svm, payer, wallets: HashMap::new() }
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.svm.get_sysvar::<Clock>().slot
+    }
+
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        self.svm.warp_to_slot(slot);
+    }
+
+    /// The account at `address`, `None` once closed (drained of its lamports) as on a validator.
+    pub fn get_account(&self, address: &Pubkey) -> Option<Account> {
+        self.svm.get_account(address).filter(|account| account.lamports > 0)
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.svm.set_account(address, account).unwrap();
+    }
+
+    /// A wallet holding `lamports`, which `process_transaction` can sign for.
+    pub fn new_wallet(&mut self, lamports: u64) -> Pubkey {
+        let wallet = Keypair::new();
+        let address = wallet.pubkey();
+        self.svm.airdrop(&address, lamports).unwrap();
+        self.wallets.insert(address, wallet);
+        address
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.svm.get_balance(address).unwrap_or(0)
+    }
+
+    /// Deserializes the Anchor account at `address`, if it exists and is a `T`.
+    pub fn get_anchor_account<T: AccountDeserialize>(&self, address: &Pubkey) -> Option<T> {
+        T::try_deserialize(&mut self.get_account(address)?.data.as_slice()).ok()
+    }
+
+    /// Stores `state` at `address` as a rent-exempt account of `T::owner()` sized like `init` would.
+    pub fn set_anchor_account<T>(&mut self, address: Pubkey, state: &T)
+    where
+        T: AccountSerialize + Owner + Space + Discriminator,
+    {
+        let mut data = Vec::with_capacity(T::DISCRIMINATOR.len() + T::INIT_SPACE);
+        state.try_serialize(&mut data).unwrap();
+        data.resize(data.len().max(T::DISCRIMINATOR.len() + T::INIT_SPACE), 0);
+        self.set_rent_exempt_account(address, data, T::owner());
+    }
+
+    /// Executes `instructions` in one transaction signed by `signers`, wallets created by `new_wallet`.
+    pub fn process_transaction(&mut self, instructions: &[Instruction], signers: &[Pubkey]) -> Result<(), TransactionError> {
+        let instructions: Vec<Instruction> =
+            std::iter::once(ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT))
+                .chain(instructions.iter().cloned())
+                .collect();
+        let mut keypairs = vec![&self.payer];
+        keypairs.extend(signers.iter().map(|signer| self.wallets.get(signer).expect("not a wallet of new_wallet")));
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&self.payer.pubkey()),
+            &keypairs,
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(transaction);
+        // A later identical transaction gets a new signature instead of being rejected as already processed
+        self.svm.expire_blockhash();
+        result.map(|_| ()).map_err(|failed| TransactionError { error: failed.err, logs: failed.meta.logs })
+    }
+
+    fn set_rent_exempt_account(&mut self, address: Pubkey, data: Vec<u8>, owner: Pubkey) {
+        let lamports = self.svm.minimum_balance_for_rent_exemption(data.len());
+        self.set_account(address, Account { lamports, data, owner, executable: false, rent_epoch: 0 });
+    }
+}
+
+/// SPL Token mints and accounts, written directly instead of going through the token program.
+impl ProgramTest {
+    /// Creates a mint without a mint authority; balances are funded by `create_token_account`.
+    pub fn create_mint(&mut self, mint: Pubkey, decimals: u8) {
+        let state = spl_token::state::Mint {
+            mint_authority: None.into(),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+        self.set_packed(mint, &state);
+    }
+
+    pub fn get_mint(&self, mint: &Pubkey) -> Option<spl_token::state::Mint> {
+        spl_token::state::Mint::unpack(&self.get_account(mint)?.data).ok()
+    }
+
+    /// Creates a token account of `mint` owned by `owner` holding `amount`, added to the mint's supply.
+    pub fn create_token_account(&mut self, address: Pubkey, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+        let mut mint_state = self.get_mint(mint).expect("mint not created");
+        mint_state.supply += amount;
+        self.set_packed(*mint, &mint_state);
+
+        let state = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        self.set_packed(address, &state);
+    }
+
+    /// `create_token_account` at the associated token address of `owner`, which is returned.
+    pub fn create_associated_token_account(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+        let address = associated_token_address(owner, mint, &token::ID);
+        self.create_token_account(address, mint, owner, amount);
+        address
+    }
+
+    /// Balance of a token account, 0 if it doesn't exist.
+    pub fn token_balance(&self, address: &Pubkey) -> u64 {
+        self.get_account(address)
+            .and_then(|account| spl_token::state::Account::unpack(&account.data).ok())
+            .map_or(0, |account| account.amount)
+    }
+
+    fn set_packed<T: Pack>(&mut self, address: Pubkey, state: &T) {
+        let mut data = vec![0; T::LEN];
+        T::pack_into_slice(state, &mut data);
+        self.set_rent_exempt_account(address, data, token::ID);
+    }
+}
+
+/// Omnipair accounts as `init_futarchy_authority` and `initialize` leave them, stored directly:
+/// `initialize` needs the Metaplex metadata program, which isn't loaded.
+impl ProgramTest {
+    pub fn create_futarchy_authority(&mut self, authority: &Pubkey) -> FutarchyAuthority {
+        let (address, bump) = find_futarchy_authority_address();
+        let futarchy_authority = FutarchyAuthority::initialize(
+            *authority,
+            PROTOCOL_SWAP_BPS,
+            PROTOCOL_INTEREST_BPS,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            10_000,
+            0,
+            0,
+            bump,
+        )
+        .unwrap();
+        self.set_anchor_account(address, &futarchy_authority);
+        futarchy_authority
+    }
+
+    pub fn get_futarchy_authority(&self) -> Option<FutarchyAuthority> {
+        self.get_anchor_account(&find_futarchy_authority_address().0)
+    }
+
+    /// Creates the pair of `mint_a` and `mint_b` with `reserve_a` / `reserve_b` of liquidity in its
+    /// reserve vaults, at the current slot.
+    ///
+    /// Missing mints are created with 6 decimals and a missing futarchy authority with a random
+    /// authority. Both sides use the default rate model at `find_rate_model_address(pair)`, and the
+    /// pair has a fixed collateral factor of `FIXED_CF_BPS`.
+    pub fn create_pair(&mut self, mint_a: Pubkey, reserve_a: u64, mint_b: Pubkey, reserve_b: u64) -> PairAccounts {
+        let futarchy_authority = match self.get_futarchy_authority() {
+            Some(futarchy_authority) => futarchy_authority,
+            None => self.create_futarchy_authority(&Pubkey::new_unique()),
+        };
+        for mint in [mint_a, mint_b] {
+            if self.get_mint(&mint).is_none() {
+                self.create_mint(mint, 6);
+            }
+        }
+        let ((token0, reserve0), (token1, reserve1)) = match mint_a < mint_b {
+            true => ((mint_a, reserve_a), (mint_b, reserve_b)),
+            false => ((mint_b, reserve_b), (mint_a, reserve_a)),
+        };
+
+        let params_hash = [0; 32];
+        let (pair_key, bump) = find_pair_address(&token0, &token1, &params_hash);
+        let vault_bumps = VaultBumps {
+            reserve0: find_reserve_vault_address(&pair_key, &token0).1,
+            reserve1: find_reserve_vault_address(&pair_key, &token1).1,
+            collateral0: find_collateral_vault_address(&pair_key, &token0).1,
+            collateral1: find_collateral_vault_address(&pair_key, &token1).1,
+        };
+
+        let rate_model = RateModel::new(
+            TARGET_UTIL_START_BPS,
+            TARGET_UTIL_END_BPS,
+            DEFAULT_RATE_HALF_LIFE_MS,
+            DEFAULT_MIN_RATE_BPS,
+            0,
+            DEFAULT_INITIAL_RATE_BPS,
+        );
+        let rate_model_key = find_rate_model_address(&pair_key).0;
+        self.set_anchor_account(rate_model_key, &rate_model);
+
+        let lp_mint = Pubkey::new_unique();
+        self.create_mint(lp_mint, 9);
+        let mut pair = Pair::initialize(
+            token0,
+            token1,
+            lp_mint,
+            self.get_mint(&token0).unwrap().decimals,
+            self.get_mint(&token1).unwrap().decimals,
+            rate_model_key,
+            SWAP_FEE_BPS,
+            MIN_HALF_LIFE_MS,
+            Some(FIXED_CF_BPS),
+            self.slot(),
+            params_hash,
+            VERSION,
+            bump,
+            vault_bumps,
+            rate_model.initial_rate,
+            futarchy_authority.risk_params,
+        );
+        (pair.reserve0, pair.reserve1) = (reserve0, reserve1);
+        (pair.cash_reserve0, pair.cash_reserve1) = (reserve0, reserve1);
+        pair.last_price0_ema.symmetric = pair.spot_price0_nad();
+        pair.last_price0_ema.directional = pair.spot_price0_nad();
+        pair.last_price1_ema.symmetric = pair.spot_price1_nad();
+        pair.last_price1_ema.directional = pair.spot_price1_nad();
+        self.set_anchor_account(pair_key, &pair);
+
+        let accounts = PairAccounts::new(pair_key, &pair);
+        self.create_token_account(accounts.reserve0_vault, &token0, &pair_key, reserve0);
+        self.create_token_account(accounts.reserve1_vault, &token1, &pair_key, reserve1);
+        self.create_token_account(accounts.collateral0_vault, &token0, &pair_key, 0);
+        self.create_token_account(accounts.collateral1_vault, &token1, &pair_key, 0);
+        accounts
+    }
+
+    pub fn get_pair(&self, pair: &PairAccounts) -> Pair {
+        self.get_anchor_account(&pair.pair).expect("pair not found")
+    }
+}
@@ -0,0 +1,95 @@
+//! `set_pair_caps`, and the caps it sets on borrows and collateral.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use omnipair::{errors::ErrorCode, state::PairCaps, AdjustCollateralArgs, SetPairCapsArgs};
+use omnipair_client::PositionKey;
+
+const COLLATERAL: u64 = 1_000_000_000;
+
+fn set_pair_caps(market: &mut Market, signer: &Pubkey, caps: PairCaps) -> Result<(), TransactionError> {
+    let instruction = omnipair_client::set_pair_caps(signer, &market.pair.pair, SetPairCapsArgs { caps });
+    market.program_test.process_transaction(&[instruction], &[*signer])
+}
+
+#[test]
+fn only_the_futarchy_authority_sets_valid_caps() {
+    let mut market = Market::new();
+    let other = market.user(0);
+    let authority = market.authority;
+    let caps = PairCaps { max_debt0: 1, max_utilization_bps: 5_000, ..Default::default() };
+
+    assert_error(set_pair_caps(&mut market, &other, caps), ErrorCode::InvalidFutarchyAuthority);
+    let invalid = PairCaps { max_utilization_bps: 10_001, ..Default::default() };
+    assert_error(set_pair_caps(&mut market, &authority, invalid), ErrorCode::InvalidPairCaps);
+    assert_eq!(market.program_test.get_pair(&market.pair).caps, PairCaps::default());
+
+    set_pair_caps(&mut market, &authority, caps).unwrap();
+    assert_eq!(market.program_test.get_pair(&market.pair).caps, caps);
+}
+
+#[test]
+fn borrow_above_the_debt_cap_is_rejected() {
+    let mut market = Market::new();
+    let authority = market.authority;
+    let max_debt1 = COLLATERAL / 10;
+    set_pair_caps(&mut market, &authority, PairCaps { max_debt1, ..Default::default() }).unwrap();
+    let owner = market.user(COLLATERAL);
+    let position = PositionKey::new(owner);
+    let (token0, token1) = (market.token0(), market.token1());
+    market.add_collateral(&owner, &position, &token0, COLLATERAL).unwrap();
+
+    let result = market.borrow(&owner, &position, &token1, &owner, max_debt1 + 1);
+    assert_error(result, ErrorCode::BorrowCapExceeded);
+    assert_eq!(market.position(&position).unwrap().debt1_shares, 0);
+
+    market.borrow(&owner, &position, &token1, &owner, max_debt1).unwrap();
+    assert_eq!(market.program_test.get_pair(&market.pair).total_debt1, max_debt1);
+}
+
+#[test]
+fn borrow_above_the_utilization_cap_is_rejected() {
+    let mut market = Market::new();
+    let authority = market.authority;
+    set_pair_caps(&mut market, &authority, PairCaps { max_utilization_bps: 1_000, ..Default::default() }).unwrap();
+    let owner = market.user(RESERVE / 2);
+    let position = PositionKey::new(owner);
+    let (token0, token1) = (market.token0(), market.token1());
+    market.add_collateral(&owner, &position, &token0, RESERVE / 2).unwrap();
+
+    // A fifth of the token1 reserve
+    let result = market.borrow(&owner, &position, &token1, &owner, RESERVE / 5);
+    assert_error(result, ErrorCode::UtilizationCapExceeded);
+
+    market.borrow(&owner, &position, &token1, &owner, RESERVE / 20).unwrap();
+}
+
+#[test]
+fn collateral_above_the_cap_is_rejected() {
+    let mut market = Market::new();
+    let authority = market.authority;
+    let caps = PairCaps { max_collateral0: COLLATERAL, ..Default::default() };
+    set_pair_caps(&mut market, &authority, caps).unwrap();
+    let owner = market.user(2 * COLLATERAL);
+    let position = PositionKey::new(owner);
+    let token0 = market.token0();
+
+    market.add_collateral(&owner, &position, &token0, COLLATERAL).unwrap();
+    assert_error(market.add_collateral(&owner, &position, &token0, 1), ErrorCode::CollateralCapExceeded);
+    assert_eq!(market.program_test.get_pair(&market.pair).total_collateral0, COLLATERAL);
+
+    // Lowering the cap below the total doesn't block withdrawals
+    set_pair_caps(&mut market, &authority, PairCaps { max_collateral0: 1, ..Default::default() }).unwrap();
+    let instruction = omnipair_client::remove_collateral(
+        &owner,
+        &owner,
+        &market.pair,
+        &token0,
+        &market.token_account(&owner, &token0),
+        AdjustCollateralArgs { amount: COLLATERAL, position_index: 0 },
+    );
+    market.program_test.process_transaction(&[instruction], &[owner]).unwrap();
+    assert_eq!(market.balance(&owner, &token0), 2 * COLLATERAL);
+}
@@ -0,0 +1,89 @@
+//! Delegates signing `borrow` and `remove_collateral` for the owner of a position.
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorErrorCode, prelude::Pubkey};
+use common::*;
+use omnipair::{errors::ErrorCode, state::DelegatePermission, AdjustCollateralArgs, SetPositionDelegateArgs};
+use omnipair_client::PositionKey;
+
+const COLLATERAL: u64 = 1_000_000_000;
+const DEBT: u64 = 100_000_000;
+
+/// `owner`'s default position with `COLLATERAL` of token0, delegated to a new wallet which is returned.
+fn delegated_position(market: &mut Market, permission: DelegatePermission, expiry_slot: u64) -> (PositionKey, Pubkey) {
+    let owner = market.user(COLLATERAL);
+    let delegate = market.user(0);
+    let position = PositionKey::new(owner);
+    let token0 = market.token0();
+    market.add_collateral(&owner, &position, &token0, COLLATERAL).unwrap();
+
+    let instruction = omnipair_client::set_position_delegate(
+        &owner,
+        0,
+        &market.pair.pair,
+        SetPositionDelegateArgs { delegate, permission, expiry_slot },
+    );
+    market.program_test.process_transaction(&[instruction], &[owner]).unwrap();
+    (position, delegate)
+}
+
+#[test]
+fn delegate_borrow_pays_the_owner() {
+    let mut market = Market::new();
+    let (position, delegate) = delegated_position(&mut market, DelegatePermission::Manage, u64::MAX);
+    let token1 = market.token1();
+
+    market.borrow(&delegate, &position, &token1, &position.owner, DEBT).unwrap();
+
+    assert_eq!(market.balance(&position.owner, &token1), COLLATERAL + DEBT);
+    assert_eq!(market.balance(&delegate, &token1), 0);
+    assert_eq!(market.program_test.get_pair(&market.pair).total_debt1, DEBT);
+}
+
+#[test]
+fn delegate_cannot_borrow_into_their_own_account() {
+    let mut market = Market::new();
+    let (position, delegate) = delegated_position(&mut market, DelegatePermission::Manage, u64::MAX);
+    let token1 = market.token1();
+
+    let result = market.borrow(&delegate, &position, &token1, &delegate, DEBT);
+
+    assert_error(result, AnchorErrorCode::ConstraintTokenOwner);
+    assert_eq!(market.position(&position).unwrap().debt1_shares, 0);
+}
+
+#[test]
+fn delegate_collateral_withdrawal_pays_the_owner() {
+    let mut market = Market::new();
+    let (position, delegate) = delegated_position(&mut market, DelegatePermission::Manage, u64::MAX);
+    let token0 = market.token0();
+
+    let instruction = omnipair_client::remove_collateral(
+        &delegate,
+        &position.owner,
+        &market.pair,
+        &token0,
+        &market.token_account(&position.owner, &token0),
+        AdjustCollateralArgs { amount: COLLATERAL, position_index: 0 },
+    );
+    market.program_test.process_transaction(&[instruction], &[delegate]).unwrap();
+
+    assert_eq!(market.balance(&position.owner, &token0), COLLATERAL);
+    assert_eq!(market.balance(&delegate, &token0), 0);
+}
+
+#[test]
+fn delegate_without_manage_permission_or_past_expiry_cannot_borrow() {
+    let mut market = Market::new();
+    let token1 = market.token1();
+    let (position, delegate) = delegated_position(&mut market, DelegatePermission::None, u64::MAX);
+    let result = market.borrow(&delegate, &position, &token1, &position.owner, DEBT);
+    assert_error(result, ErrorCode::PositionDelegateUnauthorized);
+
+    let expiry_slot = market.program_test.slot() + 10;
+    let (position, delegate) = delegated_position(&mut market, DelegatePermission::Manage, expiry_slot);
+    market.program_test.warp_to_slot(expiry_slot + 1);
+    let result = market.borrow(&delegate, &position, &token1, &position.owner, DEBT);
+    assert_error(result, ErrorCode::PositionDelegateUnauthorized);
+}
@@ -0,0 +1,122 @@
+//! `place_position_order`, `cancel_position_order` and `execute_position_order`.
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorErrorCode, prelude::Pubkey};
+use common::*;
+use omnipair::{errors::ErrorCode, simulation::Simulator, state::RateModel};
+use omnipair_client::{find_position_order_address, PositionKey};
+
+const COLLATERAL: u64 = 1_000_000_000;
+const DEBT: u64 = 100_000_000;
+const KEEPER_BOUNTY: u64 = 1_000;
+
+fn execute_order(
+    market: &mut Market,
+    keeper: &Pubkey,
+    position: &PositionKey,
+    order_index: u16,
+) -> Result<(), TransactionError> {
+    let order = market.order(position, order_index).unwrap();
+    let token0 = market.token0();
+    let instruction = omnipair_client::execute_position_order(
+        keeper,
+        &market.pair,
+        &order,
+        &market.token_account(&position.owner, &token0),
+        &market.token_account(keeper, &token0),
+    );
+    market.program_test.process_transaction(&[instruction], &[*keeper])
+}
+
+#[test]
+fn orders_are_counted_on_the_position_and_cancelling_returns_the_rent() {
+    let mut market = Market::new();
+    let owner = market.user(COLLATERAL);
+    let position = market.open_position(&owner, COLLATERAL, DEBT);
+    let owner_lamports = market.program_test.lamports(&owner);
+
+    market.place_order(&position, 0, never_triggered(), 0).unwrap();
+    market.place_order(&position, 1, never_triggered(), 0).unwrap();
+    assert_eq!(market.position(&position).unwrap().open_orders, 2);
+    let order = market.order(&position, 1).unwrap();
+    assert_eq!((order.owner, order.pair, order.order_index), (owner, market.pair.pair, 1));
+
+    for order_index in [0, 1] {
+        let instruction = omnipair_client::cancel_position_order(&owner, 0, &market.pair.pair, order_index);
+        market.program_test.process_transaction(&[instruction], &[owner]).unwrap();
+    }
+    assert_eq!(market.position(&position).unwrap().open_orders, 0);
+    assert!(market.order(&position, 0).is_none());
+    assert_eq!(market.program_test.lamports(&owner), owner_lamports);
+}
+
+#[test]
+fn only_the_owner_cancels_an_order() {
+    let mut market = Market::new();
+    let owner = market.user(COLLATERAL);
+    let other = market.user(0);
+    let position = market.open_position(&owner, COLLATERAL, DEBT);
+    market.place_order(&position, 0, never_triggered(), 0).unwrap();
+
+    let mut instruction = omnipair_client::cancel_position_order(&owner, 0, &market.pair.pair, 0);
+    for meta in instruction.accounts.iter_mut().filter(|meta| meta.pubkey == owner) {
+        meta.pubkey = other;
+    }
+    let result = market.program_test.process_transaction(&[instruction], &[other]);
+
+    assert_error(result, AnchorErrorCode::ConstraintRaw);
+    assert!(market.order(&position, 0).is_some());
+}
+
+#[test]
+fn untriggered_order_is_not_executed() {
+    let mut market = Market::new();
+    let owner = market.user(COLLATERAL);
+    let keeper = market.user(0);
+    let position = market.open_position(&owner, COLLATERAL, DEBT);
+    market.place_order(&position, 0, never_triggered(), KEEPER_BOUNTY).unwrap();
+
+    assert_error(execute_order(&mut market, &keeper, &position, 0), ErrorCode::PositionOrderNotTriggered);
+    assert_eq!(market.position(&position).unwrap().collateral0, COLLATERAL);
+}
+
+#[test]
+fn triggered_order_closes_the_side_and_pays_the_keeper() {
+    let mut market = Market::new();
+    let owner = market.user(COLLATERAL);
+    let keeper = market.user(0);
+    let position = market.open_position(&owner, COLLATERAL, DEBT);
+    market.place_order(&position, 0, always_triggered(), KEEPER_BOUNTY).unwrap();
+    let owner_lamports = market.program_test.lamports(&owner);
+    market.program_test.warp_to_slot(market.program_test.slot() + 1_000);
+
+    let program_test = &market.program_test;
+    let rate_model0: RateModel = program_test.get_anchor_account(&market.pair.rate_model0).unwrap();
+    let rate_model1: RateModel = program_test.get_anchor_account(&market.pair.rate_model1).unwrap();
+    let futarchy_authority = program_test.get_futarchy_authority().unwrap();
+    let expected = Simulator::new(&rate_model0, &rate_model1, &futarchy_authority, program_test.slot())
+        .execute_position_order(
+            &program_test.get_pair(&market.pair),
+            &market.position(&position).unwrap(),
+            &market.order(&position, 0).unwrap(),
+        )
+        .unwrap();
+    let order_address = find_position_order_address(&market.pair.user_position(&owner, 0), 0).0;
+    let order_rent = program_test.lamports(&order_address);
+
+    execute_order(&mut market, &keeper, &position, 0).unwrap();
+
+    let token0 = market.token0();
+    assert_eq!(market.balance(&keeper, &token0), KEEPER_BOUNTY);
+    assert_eq!(market.balance(&owner, &token0), expected.output.collateral_out - KEEPER_BOUNTY);
+    let user_position = market.position(&position).unwrap();
+    assert_eq!((user_position.collateral0, user_position.debt1_shares), (0, 0));
+    assert_eq!(user_position.open_orders, 0);
+    let pair = market.program_test.get_pair(&market.pair);
+    assert_eq!((pair.reserve0, pair.reserve1), (expected.pair.reserve0, expected.pair.reserve1));
+    assert_eq!(pair.total_debt1, expected.pair.total_debt1);
+    // The order is closed and its rent returned to the owner
+    assert!(market.order(&position, 0).is_none());
+    assert_eq!(market.program_test.lamports(&owner), owner_lamports + order_rent);
+}
@@ -0,0 +1,187 @@
+//! `swap_route` against the program: chained hops, and the route checks that reject a transaction.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use omnipair::{
+    errors::ErrorCode,
+    simulation::Simulator,
+    state::{Pair, RateModel},
+    SwapArgs, SwapRouteArgs,
+};
+use omnipair_client::PairAccounts;
+use common::{ProgramTest, TransactionError, USER_LAMPORTS};
+
+const RESERVE: u64 = 1_000_000_000_000;
+const AMOUNT_IN: u64 = 1_000_000_000;
+
+struct Route {
+    program_test: ProgramTest,
+    user: Pubkey,
+    /// Mints A, B, C, D
+    mints: [Pubkey; 4],
+    /// Pairs A/B, B/C, C/D
+    pairs: [PairAccounts; 3],
+    user_token_in: Pubkey,
+}
+
+impl Route {
+    fn new() -> Self {
+        let mut program_test = ProgramTest::new();
+        let mints = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        // Different depths, so each hop prices differently
+        let pairs = [
+            program_test.create_pair(mints[0], RESERVE, mints[1], 2 * RESERVE),
+            program_test.create_pair(mints[1], RESERVE, mints[2], RESERVE / 2),
+            program_test.create_pair(mints[2], 3 * RESERVE, mints[3], RESERVE),
+        ];
+        let user = program_test.new_wallet(USER_LAMPORTS);
+        let user_token_in = program_test.create_associated_token_account(&user, &mints[0], AMOUNT_IN);
+        Self { program_test, user, mints, pairs, user_token_in }
+    }
+
+    /// Creates the user's account of `mint` if needed and returns it.
+    fn user_token_account(&mut self, mint: &Pubkey) -> Pubkey {
+        let address = omnipair_client::associated_token_address(&self.user, mint, &anchor_spl::token::ID);
+        if self.program_test.get_account(&address).is_none() {
+            self.program_test.create_token_account(address, mint, &self.user, 0);
+        }
+        address
+    }
+
+    fn swap_route(
+        &mut self,
+        hops: &[PairAccounts],
+        token_out: &Pubkey,
+        min_amount_out: u64,
+    ) -> Result<(), TransactionError> {
+        let user_token_out = self.user_token_account(token_out);
+        let instruction = omnipair_client::swap_route(
+            &self.user,
+            hops,
+            &self.mints[0],
+            &self.user_token_in,
+            &user_token_out,
+            SwapRouteArgs { amount_in: AMOUNT_IN, min_amount_out },
+        );
+        self.program_test.process_transaction(&[instruction], &[self.user])
+    }
+
+    /// Output of swapping `AMOUNT_IN` of token A through `hops` one swap at a time, and each hop's pair after it.
+    fn simulate(&self, hops: &[PairAccounts]) -> (u64, Vec<Pair>) {
+        let futarchy_authority = self.program_test.get_futarchy_authority().unwrap();
+        let mut token_in = self.mints[0];
+        let mut amount_in = AMOUNT_IN;
+        let mut pairs = Vec::with_capacity(hops.len());
+        for hop in hops {
+            let pair = self.program_test.get_pair(hop);
+            let rate_model0: RateModel = self.program_test.get_anchor_account(&hop.rate_model0).unwrap();
+            let rate_model1: RateModel = self.program_test.get_anchor_account(&hop.rate_model1).unwrap();
+            let simulated = Simulator::new(&rate_model0, &rate_model1, &futarchy_authority, self.program_test.slot())
+                .swap(&pair, hop.is_token0(&token_in), &SwapArgs { amount_in, min_amount_out: 0 })
+                .unwrap();
+            amount_in = simulated.output.amount_out;
+            token_in = hop.other_token(&token_in);
+            pairs.push(simulated.pair);
+        }
+        (amount_in, pairs)
+    }
+
+    fn balance(&self, owner_mint: &Pubkey) -> u64 {
+        let address = omnipair_client::associated_token_address(&self.user, owner_mint, &anchor_spl::token::ID);
+        self.program_test.token_balance(&address)
+    }
+}
+
+fn assert_error(result: Result<(), TransactionError>, error: ErrorCode) {
+    assert_eq!(result.unwrap_err().custom_code(), Some(u32::from(error)));
+}
+
+#[test]
+fn two_hop_route_pays_out_the_chained_swaps() {
+    let mut route = Route::new();
+    let hops = [route.pairs[0].clone(), route.pairs[1].clone()];
+    let token_c = route.mints[2];
+    let (expected, _) = route.simulate(&hops);
+    let vault_b_before = route.program_test.token_balance(&hops[1].reserve_vault(&route.mints[1]));
+
+    route.swap_route(&hops, &token_c, expected).unwrap();
+
+    assert_eq!(route.balance(&token_c), expected);
+    assert_eq!(route.balance(&route.mints[0]), 0);
+    // The intermediate token moves from the first pair's vault to the second's, never through the user
+    assert_eq!(route.balance(&route.mints[1]), 0);
+    let moved_b = route.program_test.token_balance(&hops[1].reserve_vault(&route.mints[1])) - vault_b_before;
+    assert!(moved_b > 0);
+    assert_eq!(route.program_test.token_balance(&hops[0].reserve_vault(&route.mints[1])), 2 * RESERVE - moved_b);
+}
+
+#[test]
+fn three_hop_route_pays_out_the_chained_swaps() {
+    let mut route = Route::new();
+    let hops = route.pairs.clone();
+    let token_d = route.mints[3];
+    let (expected, expected_pairs) = route.simulate(&hops);
+
+    route.swap_route(&hops, &token_d, 0).unwrap();
+
+    assert_eq!(route.balance(&token_d), expected);
+    assert_eq!(route.balance(&route.mints[0]), 0);
+    for (hop, expected_pair) in hops.iter().zip(expected_pairs) {
+        let pair = route.program_test.get_pair(hop);
+        assert_eq!((pair.reserve0, pair.reserve1), (expected_pair.reserve0, expected_pair.reserve1));
+        assert_eq!(
+            (pair.last_price0_ema.symmetric, pair.last_price1_ema.symmetric),
+            (expected_pair.last_price0_ema.symmetric, expected_pair.last_price1_ema.symmetric)
+        );
+    }
+}
+
+#[test]
+fn route_through_the_same_pair_twice_is_rejected() {
+    let mut route = Route::new();
+    let pair_ab = route.pairs[0].clone();
+    let token_b = route.mints[1];
+
+    // A -> B -> A -> B
+    let result = route.swap_route(&[pair_ab.clone(), pair_ab.clone(), pair_ab], &token_b, 0);
+    assert_error(result, ErrorCode::InvalidSwapRoute);
+    assert_eq!(route.balance(&route.mints[0]), AMOUNT_IN);
+}
+
+#[test]
+fn min_amount_out_bounds_the_last_hop() {
+    let mut route = Route::new();
+    let hops = [route.pairs[0].clone(), route.pairs[1].clone()];
+    let token_c = route.mints[2];
+    let (expected, _) = route.simulate(&hops);
+    let reserves_before = hops.clone().map(|hop| {
+        let pair = route.program_test.get_pair(&hop);
+        (pair.reserve0, pair.reserve1)
+    });
+
+    let result = route.swap_route(&hops, &token_c, expected + 1);
+    assert_error(result, ErrorCode::SlippageExceeded);
+    assert_eq!(route.balance(&route.mints[0]), AMOUNT_IN);
+    assert_eq!(route.balance(&token_c), 0);
+    for (hop, reserves) in hops.iter().zip(reserves_before) {
+        let pair = route.program_test.get_pair(hop);
+        assert_eq!((pair.reserve0, pair.reserve1), reserves);
+    }
+
+    route.swap_route(&hops, &token_c, expected).unwrap();
+    assert_eq!(route.balance(&token_c), expected);
+}
+
+#[test]
+fn hop_not_trading_the_previous_output_is_rejected() {
+    let mut route = Route::new();
+    // A/B pays out B, which the C/D pair doesn't trade
+    let hops = [route.pairs[0].clone(), route.pairs[2].clone()];
+    // The token the builder names as the route's output
+    let token_out = hops[1].other_token(&route.mints[1]);
+
+    let result = route.swap_route(&hops, &token_out, 0);
+    assert_error(result, ErrorCode::InvalidSwapRoute);
+    assert_eq!(route.balance(&route.mints[0]), AMOUNT_IN);
+}
@@ -0,0 +1,68 @@
+//! `add_collateral` and `repay` paid by someone other than the position owner.
+
+mod common;
+
+use common::*;
+use omnipair::{errors::ErrorCode, AdjustDebtArgs};
+use omnipair_client::PositionKey;
+
+const AMOUNT: u64 = 1_000_000_000;
+
+#[test]
+fn third_party_tops_up_an_existing_position() {
+    let mut market = Market::new();
+    let owner = market.user(AMOUNT);
+    let payer = market.user(AMOUNT);
+    let position = PositionKey::new(owner);
+    let token0 = market.token0();
+    market.add_collateral(&owner, &position, &token0, AMOUNT).unwrap();
+
+    market.add_collateral(&payer, &position, &token0, AMOUNT / 2).unwrap();
+
+    assert_eq!(market.position(&position).unwrap().collateral0, AMOUNT + AMOUNT / 2);
+    assert_eq!(market.balance(&payer, &token0), AMOUNT / 2);
+    assert_eq!(market.balance(&owner, &token0), 0);
+    assert!(market.position(&PositionKey::new(payer)).is_none());
+}
+
+#[test]
+fn third_party_cannot_open_a_position() {
+    let mut market = Market::new();
+    let owner = market.user(0);
+    let payer = market.user(AMOUNT);
+    let position = PositionKey::new(owner);
+    let token0 = market.token0();
+
+    let result = market.add_collateral(&payer, &position, &token0, AMOUNT);
+
+    assert_error(result, ErrorCode::OnlyOwnerCanOpenPosition);
+    assert!(market.position(&position).is_none());
+    assert_eq!(market.balance(&payer, &token0), AMOUNT);
+}
+
+#[test]
+fn third_party_repays_the_owner_debt() {
+    let mut market = Market::new();
+    let owner = market.user(AMOUNT);
+    let payer = market.user(AMOUNT);
+    let debt = AMOUNT / 10;
+    let position = market.open_position(&owner, AMOUNT, debt);
+    let token1 = market.token1();
+
+    let instruction = omnipair_client::repay(
+        &payer,
+        &position,
+        &market.pair,
+        &token1,
+        &market.token_account(&payer, &token1),
+        AdjustDebtArgs { amount: u64::MAX },
+    );
+    market.program_test.process_transaction(&[instruction], &[payer]).unwrap();
+
+    let user_position = market.position(&position).unwrap();
+    assert_eq!(user_position.debt1_shares, 0);
+    assert_eq!(market.program_test.get_pair(&market.pair).total_debt1, 0);
+    // Borrowed in the same slot, so no interest accrued
+    assert_eq!(market.balance(&payer, &token1), AMOUNT - debt);
+    assert_eq!(market.balance(&owner, &token1), AMOUNT + debt);
+}
@@ -0,0 +1,80 @@
+//! `transfer_position`: both owners sign, and positions with open orders can't change hands.
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorErrorCode, prelude::Pubkey};
+use common::*;
+use omnipair::{errors::ErrorCode, TransferPositionArgs};
+use omnipair_client::{Instruction, PositionKey};
+
+fn transfer_position_instruction(market: &Market, position: &PositionKey, new_owner: &Pubkey) -> Instruction {
+    omnipair_client::transfer_position(
+        &position.owner,
+        position.position_index,
+        &market.pair.pair,
+        new_owner,
+        TransferPositionArgs { new_position_index: 0 },
+    )
+}
+
+fn transfer_position(market: &mut Market, position: &PositionKey, new_owner: &Pubkey) -> Result<(), TransactionError> {
+    let instruction = transfer_position_instruction(market, position, new_owner);
+    market.program_test.process_transaction(&[instruction], &[position.owner, *new_owner])
+}
+
+#[test]
+fn transfer_moves_collateral_and_debt_to_the_new_owner() {
+    let mut market = Market::new();
+    let owner = market.user(1_000_000_000);
+    let new_owner = market.user(0);
+    let position = market.open_position(&owner, 1_000_000_000, 100_000_000);
+    let before = market.position(&position).unwrap();
+    let pair_before = market.program_test.get_pair(&market.pair);
+
+    transfer_position(&mut market, &position, &new_owner).unwrap();
+
+    let new_position = market.position(&PositionKey::new(new_owner)).unwrap();
+    assert_eq!(new_position.owner, new_owner);
+    assert_eq!(new_position.pair, market.pair.pair);
+    assert_eq!((new_position.collateral0, new_position.debt1_shares), (before.collateral0, before.debt1_shares));
+    assert!(market.position(&position).unwrap().is_empty());
+    // The borrowed tokens stay with the old owner, the pair's totals don't move
+    assert_eq!(market.balance(&owner, &market.token1()), 1_100_000_000);
+    let pair = market.program_test.get_pair(&market.pair);
+    assert_eq!((pair.total_collateral0, pair.total_debt1), (pair_before.total_collateral0, pair_before.total_debt1));
+}
+
+#[test]
+fn transfer_without_the_new_owner_signature_is_rejected() {
+    let mut market = Market::new();
+    let owner = market.user(1_000_000_000);
+    let new_owner = market.user(0);
+    let position = market.open_position(&owner, 1_000_000_000, 100_000_000);
+
+    // An instruction that doesn't ask the new owner to sign
+    let mut instruction = transfer_position_instruction(&market, &position, &new_owner);
+    for meta in instruction.accounts.iter_mut().filter(|meta| meta.pubkey == new_owner) {
+        meta.is_signer = false;
+    }
+    let result = market.program_test.process_transaction(&[instruction], &[owner]);
+
+    assert_error(result, AnchorErrorCode::AccountNotSigner);
+    assert!(market.position(&PositionKey::new(new_owner)).is_none());
+    assert!(!market.position(&position).unwrap().is_empty());
+}
+
+#[test]
+fn transfer_with_open_orders_is_rejected() {
+    let mut market = Market::new();
+    let owner = market.user(1_000_000_000);
+    let new_owner = market.user(0);
+    let position = market.open_position(&owner, 1_000_000_000, 100_000_000);
+    market.place_order(&position, 0, never_triggered(), 0).unwrap();
+
+    assert_error(transfer_position(&mut market, &position, &new_owner), ErrorCode::PositionHasOpenOrders);
+
+    let instruction = omnipair_client::cancel_position_order(&owner, 0, &market.pair.pair, 0);
+    market.program_test.process_transaction(&[instruction], &[owner]).unwrap();
+    transfer_position(&mut market, &position, &new_owner).unwrap();
+    assert!(market.position(&position).unwrap().is_empty());
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+
+use anchor_lang::solana_program::{instruction::Instruction, pubkey::Pubkey};
+use anchor_spl::{
+    associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    token,
+};
+use omnipair::state::Pair;
+use omnipair_client::{associated_token_address, PairAccounts, PositionKey};
+
+use crate::{
+    opportunity::{find_opportunities, Opportunity, Prices},
+    state::ProgramState,
+};
+
+/// Chain access used by the keeper. The binary implements it over RPC.
+pub trait Cluster {
+    type Error: fmt::Display;
+
+    fn slot(&self) -> Result<u64, Self::Error>;
+
+    /// Address and data of every account owned by the Omnipair program.
+    fn program_accounts(&self) -> Result<Vec<(Pubkey, Vec<u8>)>, Self::Error>;
+
+    /// Sends `instructions` as one transaction paid for and signed by the keeper.
+    fn send_transaction(&mut self, instructions: &[Instruction]) -> Result<(), Self::Error>;
+}
+
+/// Outcome of one submitted liquidation.
+pub struct Submitted<E> {
+    pub opportunity: Opportunity,
+    pub result: Result<(), E>,
+}
+
+pub struct Keeper {
+    /// Signs and pays for the liquidations, receives the incentives
+    pub payer: Pubkey,
+    pub prices: Prices,
+    /// Opportunities valued below this are not worth the transaction fee
+    pub min_value: f64,
+    /// Liquidations submitted per scan
+    pub max_liquidations: usize,
+}
+
+impl Keeper {
+    pub fn new(payer: Pubkey) -> Self {
+        Self {
+            payer,
+            prices: Prices::new(),
+            min_value: 0.0,
+            max_liquidations: usize::MAX,
+        }
+    }
+
+    /// Liquidatable positions at the cluster's current slot, in submission order.
+    pub fn scan<C: Cluster>(&self, cluster: &C) -> Result<(ProgramState, Vec<Opportunity>), C::Error> {
+        let slot = cluster.slot()?;
+        let accounts = cluster.program_accounts()?;
+        let state = ProgramState::from_accounts(accounts.iter().map(|(address, data)| (*address, data.as_slice())));
+
+        let opportunities = find_opportunities(&state, slot, &self.prices)
+            .into_iter()
+            .filter(|opportunity| opportunity.value >= self.min_value)
+            .take(self.max_liquidations)
+            .collect();
+        Ok((state, opportunities))
+    }
+
+    /// Scans the cluster and submits one transaction per opportunity.
+    ///
+    /// A failed liquidation (e.g. front-run by another liquidator) does not stop the ones after it.
+    pub fn run_once<C: Cluster>(&self, cluster: &mut C) -> Result<Vec<Submitted<C::Error>>, C::Error> {
+        let (state, opportunities) = self.scan(cluster)?;
+        Ok(opportunities
+            .into_iter()
+            .map(|opportunity| {
+                let instructions = self.liquidation_instructions(&state.pairs[&opportunity.pair], &opportunity);
+                Submitted {
+                    opportunity,
+                    result: cluster.send_transaction(&instructions),
+                }
+            })
+            .collect())
+    }
+
+    /// Creates the keeper's collateral token account if needed and liquidates.
+    pub fn liquidation_instructions(&self, pair: &Pair, opportunity: &Opportunity) -> Vec<Instruction> {
+        let pair_accounts = PairAccounts::new(opportunity.pair, pair);
+        // `Liquidate` pays the incentive to an SPL Token account
+        let caller_token_account = associated_token_address(&self.payer, &opportunity.collateral_token, &token::ID);
+        vec![
+            create_associated_token_account_idempotent(
+                &self.payer,
+                &self.payer,
+                &opportunity.collateral_token,
+                &token::ID,
+            ),
+            omnipair_client::liquidate(
+                &self.payer,
+                &pair_accounts,
+                &PositionKey { owner: opportunity.position_owner, position_index: opportunity.position_index },
+                &opportunity.collateral_token,
+                &caller_token_account,
+            ),
+        ]
+    }
+}
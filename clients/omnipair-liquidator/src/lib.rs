@@ -0,0 +1,17 @@
+//! Liquidation keeper for the Omnipair program.
+//!
+//! - [`state`]: decodes the program's accounts into pairs, rate models and positions
+//! - [`opportunity`]: finds liquidatable positions and ranks them by the liquidator's incentive
+//! - [`keeper`]: scans a [`Cluster`] and submits the liquidations
+//!
+//! Positions are evaluated with [`omnipair::simulation`], i.e. the same `LiquidationAmounts`
+//! as `Liquidate::handle_liquidate`: virtual reserves at the pessimistic EMA price and the
+//! position's locked liquidation CF, on the pair brought up to the current slot.
+
+pub mod keeper;
+pub mod opportunity;
+pub mod state;
+
+pub use keeper::*;
+pub use opportunity::*;
+pub use state::*;
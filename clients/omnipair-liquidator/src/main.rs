@@ -0,0 +1,108 @@
+//! Polls the cluster and liquidates undercollateralized Omnipair positions.
+//!
+//! Configured through the environment:
+//! - `RPC_URL` (default `http://127.0.0.1:8899`)
+//! - `KEYPAIR_PATH`: payer keypair, receives the incentives (default `~/.config/solana/id.json`)
+//! - `POLL_INTERVAL_MS` (default 2000)
+//! - `MIN_VALUE`: skip liquidations with a smaller incentive, in debt token base units (default 0)
+//! - `MAX_LIQUIDATIONS`: liquidations submitted per scan (default unlimited)
+
+use std::{env, thread, time::Duration};
+
+use omnipair_liquidator::{Cluster, Keeper};
+use solana_client::{client_error::ClientError, rpc_client::RpcClient};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+
+struct RpcCluster {
+    client: RpcClient,
+    payer: Keypair,
+}
+
+impl Cluster for RpcCluster {
+    type Error = ClientError;
+
+    fn slot(&self) -> Result<u64, ClientError> {
+        self.client.get_slot()
+    }
+
+    fn program_accounts(&self) -> Result<Vec<(Pubkey, Vec<u8>)>, ClientError> {
+        Ok(self
+            .client
+            .get_program_accounts(&omnipair::ID)?
+            .into_iter()
+            .map(|(address, account)| (address, account.data))
+            .collect())
+    }
+
+    fn send_transaction(&mut self, instructions: &[Instruction]) -> Result<(), ClientError> {
+        let blockhash = self.client.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+        let signature = self.client.send_and_confirm_transaction(&transaction)?;
+        println!("liquidated: {}", signature);
+        Ok(())
+    }
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("invalid {}: {}", name, value)),
+        Err(_) => default,
+    }
+}
+
+fn main() {
+    let rpc_url = env_or("RPC_URL", "http://127.0.0.1:8899".to_string());
+    let keypair_path = env_or(
+        "KEYPAIR_PATH",
+        format!("{}/.config/solana/id.json", env::var("HOME").unwrap_or_default()),
+    );
+    let poll_interval = Duration::from_millis(env_or("POLL_INTERVAL_MS", 2_000));
+
+    let payer = read_keypair_file(&keypair_path)
+        .unwrap_or_else(|err| panic!("failed to read keypair {}: {}", keypair_path, err));
+    let mut keeper = Keeper::new(payer.pubkey());
+    keeper.min_value = env_or("MIN_VALUE", 0.0);
+    keeper.max_liquidations = env_or("MAX_LIQUIDATIONS", usize::MAX);
+
+    println!("rpc: {}, payer: {}", rpc_url, payer.pubkey());
+    let mut cluster = RpcCluster {
+        client: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
+        payer,
+    };
+
+    loop {
+        match keeper.run_once(&mut cluster) {
+            Ok(submitted) => {
+                for liquidation in submitted {
+                    let opportunity = &liquidation.opportunity;
+                    match liquidation.result {
+                        Ok(()) => println!(
+                            "position {} (pair {}): seized {} of {}, incentive {}",
+                            opportunity.user_position,
+                            opportunity.pair,
+                            opportunity.amounts.collateral_seized,
+                            opportunity.collateral_token,
+                            opportunity.amounts.caller_incentive,
+                        ),
+                        Err(err) => eprintln!("position {} (pair {}): liquidation failed: {}", opportunity.user_position, opportunity.pair, err),
+                    }
+                }
+            }
+            Err(err) => eprintln!("scan failed: {}", err),
+        }
+        thread::sleep(poll_interval);
+    }
+}
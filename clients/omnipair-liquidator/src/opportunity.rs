@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anchor_lang::solana_program::pubkey::Pubkey;
+use omnipair::{
+    constants::NAD,
+    simulation::Simulator,
+    state::{Pair, UserPosition},
+    LiquidationAmounts,
+};
+
+use crate::state::ProgramState;
+
+/// Price of one base unit of a mint in a common quote, used to rank opportunities across pairs.
+pub type Prices = HashMap<Pubkey, f64>;
+
+/// A liquidatable side of a position, as simulated at the scan slot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Opportunity {
+    pub pair: Pubkey,
+    pub user_position: Pubkey,
+    pub position_owner: Pubkey,
+    pub position_index: u16,
+    /// Collateral seized; the debt written off is in the other token of the pair
+    pub collateral_token: Pubkey,
+    pub debt_token: Pubkey,
+    pub amounts: LiquidationAmounts,
+    /// `amounts.caller_incentive` in the debt token at the collateral EMA price
+    pub incentive_in_debt_token: u64,
+    /// Ranking key: `incentive_in_debt_token` at the debt token's price, or 1 if it is not priced
+    pub value: f64,
+}
+
+/// Finds the liquidatable positions at `slot`, most valuable first.
+///
+/// Each opportunity is simulated against the pair and position as left by the ones ranked
+/// above it, so submitting them in order is not invalidated by the keeper's own liquidations.
+/// Positions whose pair, rate models or the futarchy authority is missing from `state` are skipped.
+pub fn find_opportunities(state: &ProgramState, slot: u64, prices: &Prices) -> Vec<Opportunity> {
+    let Some(futarchy_authority) = state.futarchy_authority.as_ref() else {
+        return Vec::new();
+    };
+    let simulator = |pair: &Pair| {
+        let rate_model0 = state.rate_models.get(&pair.rate_model0)?;
+        let rate_model1 = state.rate_models.get(&pair.rate_model1)?;
+        Some(Simulator::new(rate_model0, rate_model1, futarchy_authority, slot))
+    };
+
+    let mut candidates = Vec::new();
+    for (&user_position_key, user_position) in &state.user_positions {
+        let Some(pair) = state.pairs.get(&user_position.pair) else {
+            continue;
+        };
+        let Some(simulator) = simulator(pair) else {
+            continue;
+        };
+        for collateral_token in [pair.token0, pair.token1] {
+            if let Some((opportunity, _, _)) =
+                evaluate(&simulator, pair, user_position, user_position_key, &collateral_token, prices)
+            {
+                candidates.push(opportunity);
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.value.total_cmp(&a.value));
+
+    // Replay in ranked order; earlier liquidations move the pair's reserves and may touch the same position
+    let mut pairs: BTreeMap<Pubkey, Pair> = BTreeMap::new();
+    let mut user_positions: BTreeMap<Pubkey, UserPosition> = BTreeMap::new();
+    let mut opportunities = Vec::with_capacity(candidates.len());
+    for candidate in candidates {
+        let pair = pairs.entry(candidate.pair).or_insert_with(|| state.pairs[&candidate.pair].clone());
+        let user_position = user_positions
+            .entry(candidate.user_position)
+            .or_insert_with(|| state.user_positions[&candidate.user_position].clone());
+        let Some(simulator) = simulator(pair) else {
+            continue;
+        };
+
+        if let Some((opportunity, post_pair, post_user_position)) = evaluate(
+            &simulator,
+            pair,
+            user_position,
+            candidate.user_position,
+            &candidate.collateral_token,
+            prices,
+        ) {
+            *pair = post_pair;
+            *user_position = post_user_position;
+            opportunities.push(opportunity);
+        }
+    }
+    opportunities
+}
+
+/// Simulates the liquidation of the debt backed by `collateral_token`, `None` if it would fail.
+fn evaluate(
+    simulator: &Simulator,
+    pair: &Pair,
+    user_position: &UserPosition,
+    user_position_key: Pubkey,
+    collateral_token: &Pubkey,
+    prices: &Prices,
+) -> Option<(Opportunity, Pair, UserPosition)> {
+    let simulated = simulator.liquidate(pair, user_position, collateral_token).ok()?;
+    let amounts = simulated.output;
+
+    let collateral_ema_nad = match *collateral_token == simulated.pair.token0 {
+        true => simulated.pair.ema_price0_nad(),
+        false => simulated.pair.ema_price1_nad(),
+    };
+    let incentive_in_debt_token = u64::try_from(
+        amounts.caller_incentive as u128 * collateral_ema_nad as u128 / NAD as u128,
+    )
+    .unwrap_or(u64::MAX);
+    let debt_token = simulated.pair.get_debt_token(collateral_token);
+    let value = incentive_in_debt_token as f64 * prices.get(&debt_token).copied().unwrap_or(1.0);
+
+    let opportunity = Opportunity {
+        pair: user_position.pair,
+        user_position: user_position_key,
+        position_owner: user_position.owner,
+        position_index: user_position.position_index,
+        collateral_token: *collateral_token,
+        debt_token,
+        amounts,
+        incentive_in_debt_token,
+        value,
+    };
+    Some((opportunity, simulated.pair, simulated.user_position))
+}
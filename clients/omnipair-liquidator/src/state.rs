@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+
+use anchor_lang::{solana_program::pubkey::Pubkey, AccountDeserialize};
+use omnipair::state::{FutarchyAuthority, Pair, RateModel, UserPosition};
+
+/// Omnipair accounts needed to evaluate liquidations, decoded from `getProgramAccounts`.
+#[derive(Clone, Default)]
+pub struct ProgramState {
+    pub futarchy_authority: Option<FutarchyAuthority>,
+    pub pairs: BTreeMap<Pubkey, Pair>,
+    pub rate_models: BTreeMap<Pubkey, RateModel>,
+    /// Positions keyed by their account address
+    pub user_positions: BTreeMap<Pubkey, UserPosition>,
+}
+
+impl ProgramState {
+    /// Sorts accounts by their discriminator; accounts of other types are skipped.
+    pub fn from_accounts<'a>(accounts: impl IntoIterator<Item = (Pubkey, &'a [u8])>) -> Self {
+        let mut state = Self::default();
+        for (address, data) in accounts {
+            state.insert(address, data);
+        }
+        state
+    }
+
+    pub fn insert(&mut self, address: Pubkey, data: &[u8]) {
+        if let Ok(pair) = Pair::try_deserialize(&mut &data[..]) {
+            self.pairs.insert(address, pair);
+        } else if let Ok(user_position) = UserPosition::try_deserialize(&mut &data[..]) {
+            self.user_positions.insert(address, user_position);
+        } else if let Ok(rate_model) = RateModel::try_deserialize(&mut &data[..]) {
+            self.rate_models.insert(address, rate_model);
+        } else if let Ok(futarchy_authority) = FutarchyAuthority::try_deserialize(&mut &data[..]) {
+            self.futarchy_authority = Some(futarchy_authority);
+        }
+    }
+}
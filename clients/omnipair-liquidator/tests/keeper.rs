@@ -0,0 +1,396 @@
+//! Runs the keeper against `LocalCluster`, a LiteSVM bank executing its transactions with the program
+//! built by `anchor build` (`target/deploy/omnipair.so`).
+
+use std::collections::{BTreeSet, HashMap};
+
+use anchor_lang::{
+    solana_program::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey},
+    AccountDeserialize, AccountSerialize, Discriminator, Owner, Space,
+};
+use anchor_spl::token::{self, spl_token};
+use litesvm::LiteSVM;
+use omnipair::{
+    constants::*,
+    state::{FutarchyAuthority, Pair, RateModel, UserPosition, VaultBumps},
+    AdjustCollateralArgs, AdjustDebtArgs, SwapArgs,
+};
+use omnipair_client::{associated_token_address, PairAccounts, PositionKey};
+use omnipair_liquidator::{Cluster, Keeper, ProgramState};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const PROGRAM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/deploy/omnipair.so");
+
+struct LocalCluster {
+    svm: LiteSVM,
+    /// Signs and pays every transaction sent through `Cluster`
+    payer: Keypair,
+    /// Wallets signing the test's own transactions
+    wallets: HashMap<Pubkey, Keypair>,
+    /// Every address written by the test or passed to a transaction. LiteSVM has no `getProgramAccounts`,
+    /// so `program_accounts` looks these up.
+    addresses: BTreeSet<Pubkey>,
+}
+
+impl Cluster for LocalCluster {
+    type Error = String;
+
+    fn slot(&self) -> Result<u64, String> {
+        Ok(self.svm.get_sysvar::<Clock>().slot)
+    }
+
+    fn program_accounts(&self) -> Result<Vec<(Pubkey, Vec<u8>)>, String> {
+        Ok(self
+            .addresses
+            .iter()
+            .filter_map(|address| Some((*address, self.svm.get_account(address)?)))
+            .filter(|(_, account)| account.owner == omnipair::ID)
+            .map(|(address, account)| (address, account.data))
+            .collect())
+    }
+
+    fn send_transaction(&mut self, instructions: &[Instruction]) -> Result<(), String> {
+        self.process_transaction(instructions, &[])
+    }
+}
+
+impl LocalCluster {
+    fn new() -> Self {
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(omnipair::ID, PROGRAM_PATH)
+            .expect("target/deploy/omnipair.so not found, run `anchor build` first");
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), LAMPORTS).unwrap();
+        Self { svm, payer, wallets: HashMap::new(), addresses: BTreeSet::new() }
+    }
+
+    fn slot(&self) -> u64 {
+        self.svm.get_sysvar::<Clock>().slot
+    }
+
+    fn warp_to_slot(&mut self, slot: u64) {
+        self.svm.warp_to_slot(slot);
+    }
+
+    fn get_account(&self, address: &Pubkey) -> Option<Account> {
+        self.svm.get_account(address)
+    }
+
+    fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.svm.set_account(address, account).unwrap();
+        self.addresses.insert(address);
+    }
+
+    fn get_anchor_account<T: AccountDeserialize>(&self, address: &Pubkey) -> Option<T> {
+        T::try_deserialize(&mut self.get_account(address)?.data.as_slice()).ok()
+    }
+
+    /// Stores `state` at `address` as a rent-exempt account of `T::owner()` sized like `init` would.
+    fn set_anchor_account<T>(&mut self, address: Pubkey, state: &T)
+    where
+        T: AccountSerialize + Owner + Space + Discriminator,
+    {
+        let mut data = Vec::with_capacity(T::DISCRIMINATOR.len() + T::INIT_SPACE);
+        state.try_serialize(&mut data).unwrap();
+        data.resize(data.len().max(T::DISCRIMINATOR.len() + T::INIT_SPACE), 0);
+        self.set_rent_exempt_account(address, data, T::owner());
+    }
+
+    fn set_packed<T: Pack>(&mut self, address: Pubkey, state: &T) {
+        let mut data = vec![0; T::LEN];
+        T::pack_into_slice(state, &mut data);
+        self.set_rent_exempt_account(address, data, token::ID);
+    }
+
+    fn set_rent_exempt_account(&mut self, address: Pubkey, data: Vec<u8>, owner: Pubkey) {
+        let lamports = self.svm.minimum_balance_for_rent_exemption(data.len());
+        self.set_account(address, Account { lamports, data, owner, executable: false, rent_epoch: 0 });
+    }
+
+    /// A funded wallet whose signature `process_transaction` can add.
+    fn new_wallet(&mut self) -> Pubkey {
+        let wallet = Keypair::new();
+        let address = wallet.pubkey();
+        self.svm.airdrop(&address, LAMPORTS).unwrap();
+        self.wallets.insert(address, wallet);
+        address
+    }
+
+    /// Executes `instructions` paid by the keeper and signed by `signers` as well, all created by `new_wallet`.
+    fn process_transaction(&mut self, instructions: &[Instruction], signers: &[Pubkey]) -> Result<(), String> {
+        let mut keypairs = vec![&self.payer];
+        keypairs.extend(signers.iter().map(|signer| &self.wallets[signer]));
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &keypairs,
+            self.svm.latest_blockhash(),
+        );
+        self.addresses
+            .extend(instructions.iter().flat_map(|instruction| instruction.accounts.iter().map(|meta| meta.pubkey)));
+        let result = self.svm.send_transaction(transaction);
+        self.svm.expire_blockhash();
+        result.map(|_| ()).map_err(|failed| format!("{}\n{}", failed.err, failed.meta.logs.join("\n")))
+    }
+
+    fn create_mint(&mut self, mint: Pubkey, decimals: u8) {
+        let state = spl_token::state::Mint { decimals, is_initialized: true, ..Default::default() };
+        self.set_packed(mint, &state);
+    }
+
+    /// Creates the associated token account of `owner` for `mint`, holding `amount`.
+    fn create_associated_token_account(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+        let address = associated_token_address(owner, mint, &token::ID);
+        self.create_token_account(address, mint, owner, amount);
+        address
+    }
+
+    fn create_token_account(&mut self, address: Pubkey, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+        let mut mint_state = spl_token::state::Mint::unpack(&self.get_account(mint).unwrap().data).unwrap();
+        mint_state.supply += amount;
+        self.set_packed(*mint, &mint_state);
+        let state = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        self.set_packed(address, &state);
+    }
+
+    fn token_balance(&self, address: &Pubkey) -> u64 {
+        self.get_account(address)
+            .and_then(|account| spl_token::state::Account::unpack(&account.data).ok())
+            .map_or(0, |account| account.amount)
+    }
+
+    /// Stores the futarchy authority and a pair of two new mints with `RESERVE` of each, as
+    /// `init_futarchy_authority` and `initialize` leave them.
+    fn create_pair(&mut self) -> PairAccounts {
+        let (futarchy_authority_key, bump) = omnipair_client::find_futarchy_authority_address();
+        let futarchy_authority = FutarchyAuthority::initialize(
+            Pubkey::new_unique(),
+            2_000,
+            1_000,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            10_000,
+            0,
+            0,
+            bump,
+        )
+        .unwrap();
+        self.set_anchor_account(futarchy_authority_key, &futarchy_authority);
+
+        let (token0, token1) = omnipair_client::canonical_token_order(Pubkey::new_unique(), Pubkey::new_unique());
+        self.create_mint(token0, 6);
+        self.create_mint(token1, 6);
+        let params_hash = [0; 32];
+        let (pair_key, bump) = omnipair_client::find_pair_address(&token0, &token1, &params_hash);
+        let vault_bumps = VaultBumps {
+            reserve0: omnipair_client::find_reserve_vault_address(&pair_key, &token0).1,
+            reserve1: omnipair_client::find_reserve_vault_address(&pair_key, &token1).1,
+            collateral0: omnipair_client::find_collateral_vault_address(&pair_key, &token0).1,
+            collateral1: omnipair_client::find_collateral_vault_address(&pair_key, &token1).1,
+        };
+
+        let rate_model = RateModel::new(
+            TARGET_UTIL_START_BPS,
+            TARGET_UTIL_END_BPS,
+            DEFAULT_RATE_HALF_LIFE_MS,
+            DEFAULT_MIN_RATE_BPS,
+            0,
+            DEFAULT_INITIAL_RATE_BPS,
+        );
+        let rate_model_key = omnipair_client::find_rate_model_address(&pair_key).0;
+        self.set_anchor_account(rate_model_key, &rate_model);
+
+        let lp_mint = Pubkey::new_unique();
+        self.create_mint(lp_mint, 9);
+        let mut pair = Pair::initialize(
+            token0,
+            token1,
+            lp_mint,
+            6,
+            6,
+            rate_model_key,
+            30,
+            MIN_HALF_LIFE_MS,
+            Some(8_000),
+            self.slot(),
+            params_hash,
+            VERSION,
+            bump,
+            vault_bumps,
+            rate_model.initial_rate,
+            futarchy_authority.risk_params,
+        );
+        (pair.reserve0, pair.reserve1) = (RESERVE, RESERVE);
+        (pair.cash_reserve0, pair.cash_reserve1) = (RESERVE, RESERVE);
+        pair.last_price0_ema.symmetric = pair.spot_price0_nad();
+        pair.last_price0_ema.directional = pair.spot_price0_nad();
+        pair.last_price1_ema.symmetric = pair.spot_price1_nad();
+        pair.last_price1_ema.directional = pair.spot_price1_nad();
+        self.set_anchor_account(pair_key, &pair);
+
+        let accounts = PairAccounts::new(pair_key, &pair);
+        self.create_token_account(accounts.reserve0_vault, &token0, &pair_key, RESERVE);
+        self.create_token_account(accounts.reserve1_vault, &token1, &pair_key, RESERVE);
+        self.create_token_account(accounts.collateral0_vault, &token0, &pair_key, 0);
+        self.create_token_account(accounts.collateral1_vault, &token1, &pair_key, 0);
+        accounts
+    }
+}
+
+const START_SLOT: u64 = 1_000;
+const RESERVE: u64 = 1_000_000_000;
+/// Pays the keeper's transactions and the rent of its token accounts, and funds every wallet
+const LAMPORTS: u64 = 1_000_000_000;
+
+struct Market {
+    cluster: LocalCluster,
+    pair: PairAccounts,
+}
+
+impl Market {
+    fn new() -> Self {
+        let mut cluster = LocalCluster::new();
+        cluster.warp_to_slot(START_SLOT);
+        let pair = cluster.create_pair();
+        Self { cluster, pair }
+    }
+
+    fn payer(&self) -> Pubkey {
+        self.cluster.payer.pubkey()
+    }
+
+    /// A new wallet holding `amount0` of token0 and no token1.
+    fn user(&mut self, amount0: u64) -> Pubkey {
+        let user = self.cluster.new_wallet();
+        let (token0, token1) = (self.pair.token0, self.pair.token1);
+        self.cluster.create_associated_token_account(&user, &token0, amount0);
+        self.cluster.create_associated_token_account(&user, &token1, 0);
+        user
+    }
+
+    /// Opens position `position_index` of a new owner with `collateral0` of token0 collateral and
+    /// `debt1` of token1 debt.
+    fn open_position(&mut self, position_index: u16, collateral0: u64, debt1: u64) -> (Pubkey, Pubkey) {
+        let owner = self.user(collateral0);
+        let (token0, token1) = (self.pair.token0, self.pair.token1);
+        let instructions = [
+            omnipair_client::add_collateral(
+                &owner,
+                &owner,
+                &self.pair,
+                &token0,
+                &associated_token_address(&owner, &token0, &token::ID),
+                AdjustCollateralArgs { amount: collateral0, position_index },
+            ),
+            omnipair_client::borrow(
+                &owner,
+                &PositionKey { owner, position_index },
+                &self.pair,
+                &token1,
+                &associated_token_address(&owner, &token1, &token::ID),
+                AdjustDebtArgs { amount: debt1 },
+            ),
+        ];
+        self.cluster.process_transaction(&instructions, &[owner]).unwrap();
+        (owner, self.pair.user_position(&owner, position_index))
+    }
+
+    /// Sells token0 into the pair and lets the EMA catch up with the new price.
+    fn crash_token0(&mut self, amount_in: u64) {
+        let seller = self.user(amount_in);
+        let (token0, token1) = (self.pair.token0, self.pair.token1);
+        let instruction = omnipair_client::swap(
+            &seller,
+            &self.pair,
+            &token0,
+            &associated_token_address(&seller, &token0, &token::ID),
+            &associated_token_address(&seller, &token1, &token::ID),
+            SwapArgs { amount_in, min_amount_out: 0 },
+        );
+        self.cluster.process_transaction(&[instruction], &[seller]).unwrap();
+        let slot = self.cluster.slot();
+        self.cluster.warp_to_slot(slot + 100_000);
+    }
+
+    fn user_position(&self, address: &Pubkey) -> UserPosition {
+        self.cluster.get_anchor_account(address).unwrap()
+    }
+}
+
+#[test]
+fn liquidates_undercollateralized_positions_most_profitable_first() {
+    let mut market = Market::new();
+    let (small_owner, small_position) = market.open_position(0, 100_000_000, u64::MAX);
+    let (large_owner, large_position) = market.open_position(3, 300_000_000, u64::MAX);
+    let (_, healthy_position) = market.open_position(0, 100_000_000, 1_000_000);
+    market.crash_token0(1_000_000_000);
+
+    let keeper = Keeper::new(market.payer());
+    let (_, opportunities) = keeper.scan(&market.cluster).unwrap();
+    let owners: Vec<Pubkey> = opportunities.iter().map(|opportunity| opportunity.position_owner).collect();
+    assert_eq!(owners, vec![large_owner, small_owner]);
+    assert!(opportunities[0].value > opportunities[1].value);
+    assert!(opportunities.iter().all(|opportunity| opportunity.collateral_token == market.pair.token0));
+
+    let healthy_before = market.cluster.get_account(&healthy_position).unwrap();
+    let large_before = market.user_position(&large_position);
+    let small_before = market.user_position(&small_position);
+
+    let submitted = keeper.run_once(&mut market.cluster).unwrap();
+    assert_eq!(submitted.len(), 2);
+    assert!(submitted.iter().all(|liquidation| liquidation.result.is_ok()));
+
+    // Executed amounts match the scan
+    let large_after = market.user_position(&large_position);
+    let small_after = market.user_position(&small_position);
+    assert_eq!(large_before.debt1_shares - large_after.debt1_shares, opportunities[0].amounts.shares_to_writeoff);
+    assert_eq!(large_before.collateral0 - large_after.collateral0, opportunities[0].amounts.collateral_seized);
+    assert_eq!(small_before.debt1_shares - small_after.debt1_shares, opportunities[1].amounts.shares_to_writeoff);
+    assert_eq!(small_before.collateral0 - small_after.collateral0, opportunities[1].amounts.collateral_seized);
+    assert_eq!(market.cluster.get_account(&healthy_position), Some(healthy_before));
+
+    // The incentives are paid to the keeper's associated token account, created by the first liquidation
+    let incentives: u64 = opportunities.iter().map(|opportunity| opportunity.amounts.caller_incentive).sum();
+    assert!(incentives > 0);
+    let keeper_token_account = associated_token_address(&market.payer(), &market.pair.token0, &token::ID);
+    assert_eq!(market.cluster.token_balance(&keeper_token_account), incentives);
+}
+
+#[test]
+fn healthy_market_has_no_opportunities() {
+    let mut market = Market::new();
+    market.open_position(0, 100_000_000, u64::MAX);
+    market.open_position(0, 100_000_000, 1_000_000);
+    // Accounts of other types are ignored
+    let other = Account { lamports: 1, data: vec![7; 64], owner: omnipair::ID, executable: false, rent_epoch: 0 };
+    market.cluster.set_account(Pubkey::new_unique(), other);
+
+    let keeper = Keeper::new(market.payer());
+    let (state, opportunities) = keeper.scan(&market.cluster).unwrap();
+    assert_eq!(state.pairs.len(), 1);
+    assert_eq!(state.user_positions.len(), 2);
+    assert!(opportunities.is_empty());
+    assert!(keeper.run_once(&mut market.cluster).unwrap().is_empty());
+}
+
+#[test]
+fn decodes_program_accounts_by_discriminator() {
+    let market = Market::new();
+    let accounts = market.cluster.program_accounts().unwrap();
+    let state = ProgramState::from_accounts(accounts.iter().map(|(address, data)| (*address, data.as_slice())));
+    assert!(state.futarchy_authority.is_some());
+    assert_eq!(state.rate_models.len(), 1);
+    assert_eq!(state.pairs.len(), 1);
+    assert!(state.user_positions.is_empty());
+}
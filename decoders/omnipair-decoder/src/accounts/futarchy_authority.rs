@@ -16,5 +16,6 @@ pub struct FutarchyAuthority {
         pub revenue_share: RevenueShare,
         pub revenue_distribution: RevenueDistribution,
         pub global_reduce_only: bool,
-        pub bump: u8, 
+        pub bump: u8,
+        pub risk_params: RiskParams, 
 }
\ No newline at end of file
@@ -6,12 +6,14 @@ use carbon_core::deserialize::CarbonDeserialize;
 use super::OmnipairDecoder; 
 pub mod futarchy_authority; 
 pub mod pair; 
+pub mod position_order; 
 pub mod rate_model; 
 pub mod user_position; 
 
 pub enum OmnipairAccount { 
         FutarchyAuthority(futarchy_authority::FutarchyAuthority), 
         Pair(pair::Pair), 
+        PositionOrder(position_order::PositionOrder), 
         RateModel(rate_model::RateModel), 
         UserPosition(user_position::UserPosition), 
 }
@@ -41,6 +43,16 @@ impl<'a> AccountDecoder<'a> for OmnipairDecoder {
             }); 
         } 
          
+            if let Some(decoded_account) = position_order::PositionOrder::deserialize(account.data.as_slice()) { 
+            return Some(carbon_core::account::DecodedAccount { 
+                lamports: account.lamports, 
+                data: OmnipairAccount::PositionOrder(decoded_account), 
+                owner: account.owner, 
+                executable: account.executable, 
+                rent_epoch: account.rent_epoch, 
+            }); 
+        } 
+         
             if let Some(decoded_account) = rate_model::RateModel::deserialize(account.data.as_slice()) { 
             return Some(carbon_core::account::DecodedAccount { 
                 lamports: account.lamports, 
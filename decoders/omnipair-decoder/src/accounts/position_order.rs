@@ -0,0 +1,22 @@
+
+use super::super::types::*;
+ 
+use carbon_core::{borsh, CarbonDeserialize};
+
+#[derive(
+    CarbonDeserialize, Debug, serde::Deserialize, serde::Serialize, PartialEq, Eq, Clone, Hash,
+)] 
+ 
+
+#[carbon(discriminator = "0x1dfc26588c146230")] 
+pub struct PositionOrder {
+        pub user_position: solana_pubkey::Pubkey,
+        pub owner: solana_pubkey::Pubkey,
+        pub pair: solana_pubkey::Pubkey,
+        pub order_index: u16,
+        pub is_collateral_token0: bool,
+        pub trigger: OrderTrigger,
+        pub min_collateral_out: u64,
+        pub keeper_bounty: u64,
+        pub bump: u8, 
+}
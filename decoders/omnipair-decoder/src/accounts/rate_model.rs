@@ -1,4 +1,5 @@
 
+use super::super::types::*;
  
 use carbon_core::{borsh, CarbonDeserialize};
 
@@ -15,5 +16,13 @@ pub struct RateModel {
         pub half_life_ms: u64,
         pub min_rate: u64,
         pub max_rate: u64,
-        pub initial_rate: u64, 
+        pub initial_rate: u64,
+        pub kind: RateModelKind,
+        pub kink_util: u64,
+        pub base_rate: u64,
+        pub slope1: u64,
+        pub slope2: u64,
+        pub update_delay_slots: u64,
+        pub pending_update: Option<PendingRateModelUpdate>,
+        pub pair_references: u64, 
 }
\ No newline at end of file
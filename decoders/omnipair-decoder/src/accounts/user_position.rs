@@ -1,4 +1,5 @@
 
+use super::super::types::*;
  
 use carbon_core::{borsh, CarbonDeserialize};
 
@@ -17,5 +18,11 @@ pub struct UserPosition {
         pub collateral1: u64,
         pub debt0_shares: u128,
         pub debt1_shares: u128,
-        pub bump: u8, 
+        pub bump: u8,
+        pub cross_margin: bool,
+        pub position_index: u16,
+        pub delegate: solana_pubkey::Pubkey,
+        pub delegate_permission: DelegatePermission,
+        pub delegate_expiry_slot: u64,
+        pub open_orders: u16, 
 }
\ No newline at end of file
@@ -13,12 +13,14 @@ pub struct AddCollateral{
 #[derive(Debug, PartialEq, Eq, Clone, Hash, serde::Serialize, serde::Deserialize)]
 pub struct AddCollateralInstructionAccounts {
     pub pair: solana_pubkey::Pubkey,
-    pub rate_model: solana_pubkey::Pubkey,
+    pub rate_model0: solana_pubkey::Pubkey,
+    pub rate_model1: solana_pubkey::Pubkey,
     pub futarchy_authority: solana_pubkey::Pubkey,
     pub user_position: solana_pubkey::Pubkey,
     pub collateral_vault: solana_pubkey::Pubkey,
     pub user_collateral_token_account: solana_pubkey::Pubkey,
     pub collateral_token_mint: solana_pubkey::Pubkey,
+    pub position_owner: solana_pubkey::Pubkey,
     pub user: solana_pubkey::Pubkey,
     pub token_program: solana_pubkey::Pubkey,
     pub token_2022_program: solana_pubkey::Pubkey,
@@ -33,12 +35,14 @@ impl carbon_core::deserialize::ArrangeAccounts for AddCollateral {
     fn arrange_accounts(accounts: &[solana_instruction::AccountMeta]) -> Option<Self::ArrangedAccounts> {
         let mut iter = accounts.iter();
         let pair = next_account(&mut iter)?;
-        let rate_model = next_account(&mut iter)?;
+        let rate_model0 = next_account(&mut iter)?;
+        let rate_model1 = next_account(&mut iter)?;
         let futarchy_authority = next_account(&mut iter)?;
         let user_position = next_account(&mut iter)?;
         let collateral_vault = next_account(&mut iter)?;
         let user_collateral_token_account = next_account(&mut iter)?;
         let collateral_token_mint = next_account(&mut iter)?;
+        let position_owner = next_account(&mut iter)?;
         let user = next_account(&mut iter)?;
         let token_program = next_account(&mut iter)?;
         let token_2022_program = next_account(&mut iter)?;
@@ -48,12 +52,14 @@ impl carbon_core::deserialize::ArrangeAccounts for AddCollateral {
 
         Some(AddCollateralInstructionAccounts {
             pair,
-            rate_model,
+            rate_model0,
+            rate_model1,
             futarchy_authority,
             user_position,
             collateral_vault,
             user_collateral_token_account,
             collateral_token_mint,
+            position_owner,
             user,
             token_program,
             token_2022_program,
@@ -13,7 +13,8 @@ pub struct AddLiquidity{
 #[derive(Debug, PartialEq, Eq, Clone, Hash, serde::Serialize, serde::Deserialize)]
 pub struct AddLiquidityInstructionAccounts {
     pub pair: solana_pubkey::Pubkey,
-    pub rate_model: solana_pubkey::Pubkey,
+    pub rate_model0: solana_pubkey::Pubkey,
+    pub rate_model1: solana_pubkey::Pubkey,
     pub futarchy_authority: solana_pubkey::Pubkey,
     pub reserve0_vault: solana_pubkey::Pubkey,
     pub reserve1_vault: solana_pubkey::Pubkey,
@@ -38,7 +39,8 @@ impl carbon_core::deserialize::ArrangeAccounts for AddLiquidity {
     fn arrange_accounts(accounts: &[solana_instruction::AccountMeta]) -> Option<Self::ArrangedAccounts> {
         let mut iter = accounts.iter();
         let pair = next_account(&mut iter)?;
-        let rate_model = next_account(&mut iter)?;
+        let rate_model0 = next_account(&mut iter)?;
+        let rate_model1 = next_account(&mut iter)?;
         let futarchy_authority = next_account(&mut iter)?;
         let reserve0_vault = next_account(&mut iter)?;
         let reserve1_vault = next_account(&mut iter)?;
@@ -58,7 +60,8 @@ impl carbon_core::deserialize::ArrangeAccounts for AddLiquidity {
 
         Some(AddLiquidityInstructionAccounts {
             pair,
-            rate_model,
+            rate_model0,
+            rate_model1,
             futarchy_authority,
             reserve0_vault,
             reserve1_vault,
@@ -0,0 +1,18 @@
+use super::super::types::*;
+
+use carbon_core::{borsh, CarbonDeserialize};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+#[carbon(discriminator = "0xe445a52e51cb9a1d2c92463e028a4290")]
+pub struct BadDebtEvent{
+    pub position: solana_pubkey::Pubkey,
+    pub is_token0_debt: bool,
+    pub bad_debt: u64,
+    pub covered_by_insurance: u64,
+    pub socialized: u64,
+    pub insurance_reserve: u64,
+    pub total_bad_debt: u64,
+    pub total_socialized_debt: u64,
+    pub metadata: EventMetadata,
+}
@@ -12,7 +12,8 @@ pub struct ClaimProtocolFees{
 pub struct ClaimProtocolFeesInstructionAccounts {
     pub caller: solana_pubkey::Pubkey,
     pub pair: solana_pubkey::Pubkey,
-    pub rate_model: solana_pubkey::Pubkey,
+    pub rate_model0: solana_pubkey::Pubkey,
+    pub rate_model1: solana_pubkey::Pubkey,
     pub futarchy_authority: solana_pubkey::Pubkey,
     pub reserve0_vault: solana_pubkey::Pubkey,
     pub reserve1_vault: solana_pubkey::Pubkey,
@@ -40,7 +41,8 @@ impl carbon_core::deserialize::ArrangeAccounts for ClaimProtocolFees {
         let mut iter = accounts.iter();
         let caller = next_account(&mut iter)?;
         let pair = next_account(&mut iter)?;
-        let rate_model = next_account(&mut iter)?;
+        let rate_model0 = next_account(&mut iter)?;
+        let rate_model1 = next_account(&mut iter)?;
         let futarchy_authority = next_account(&mut iter)?;
         let reserve0_vault = next_account(&mut iter)?;
         let reserve1_vault = next_account(&mut iter)?;
@@ -63,7 +65,8 @@ impl carbon_core::deserialize::ArrangeAccounts for ClaimProtocolFees {
         Some(ClaimProtocolFeesInstructionAccounts {
             caller,
             pair,
-            rate_model,
+            rate_model0,
+            rate_model1,
             futarchy_authority,
             reserve0_vault,
             reserve1_vault,
@@ -12,7 +12,8 @@ pub struct Liquidate{
 pub struct LiquidateInstructionAccounts {
     pub pair: solana_pubkey::Pubkey,
     pub user_position: solana_pubkey::Pubkey,
-    pub rate_model: solana_pubkey::Pubkey,
+    pub rate_model0: solana_pubkey::Pubkey,
+    pub rate_model1: solana_pubkey::Pubkey,
     pub futarchy_authority: solana_pubkey::Pubkey,
     pub collateral_vault: solana_pubkey::Pubkey,
     pub caller_token_account: solana_pubkey::Pubkey,
@@ -34,7 +35,8 @@ impl carbon_core::deserialize::ArrangeAccounts for Liquidate {
         let mut iter = accounts.iter();
         let pair = next_account(&mut iter)?;
         let user_position = next_account(&mut iter)?;
-        let rate_model = next_account(&mut iter)?;
+        let rate_model0 = next_account(&mut iter)?;
+        let rate_model1 = next_account(&mut iter)?;
         let futarchy_authority = next_account(&mut iter)?;
         let collateral_vault = next_account(&mut iter)?;
         let caller_token_account = next_account(&mut iter)?;
@@ -51,7 +53,8 @@ impl carbon_core::deserialize::ArrangeAccounts for Liquidate {
         Some(LiquidateInstructionAccounts {
             pair,
             user_position,
-            rate_model,
+            rate_model0,
+            rate_model1,
             futarchy_authority,
             collateral_vault,
             caller_token_account,
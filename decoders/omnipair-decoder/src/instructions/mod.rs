@@ -25,16 +25,23 @@ pub mod view_user_position_data;
 pub mod adjust_collateral_event;
 pub mod adjust_debt_event;
 pub mod adjust_liquidity_event;
+pub mod bad_debt_event;
 pub mod burn_event;
 pub mod claim_protocol_fees_event;
 pub mod flashloan_event;
 pub mod mint_event;
 pub mod pair_created_event;
+pub mod position_order_closed_event;
+pub mod position_order_placed_event;
 pub mod swap_event;
 pub mod update_pair_event;
 pub mod user_liquidity_position_updated_event;
+pub mod user_position_closed_event;
 pub mod user_position_created_event;
+pub mod user_position_delegate_event;
 pub mod user_position_liquidated_event;
+pub mod user_position_margin_mode_event;
+pub mod user_position_transferred_event;
 pub mod user_position_updated_event;
 
 #[derive(carbon_core::InstructionType, serde::Serialize, serde::Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
@@ -61,16 +68,23 @@ pub enum OmnipairInstruction {
     AdjustCollateralEvent(adjust_collateral_event::AdjustCollateralEvent),
     AdjustDebtEvent(adjust_debt_event::AdjustDebtEvent),
     AdjustLiquidityEvent(adjust_liquidity_event::AdjustLiquidityEvent),
+    BadDebtEvent(bad_debt_event::BadDebtEvent),
     BurnEvent(burn_event::BurnEvent),
     ClaimProtocolFeesEvent(claim_protocol_fees_event::ClaimProtocolFeesEvent),
     FlashloanEvent(flashloan_event::FlashloanEvent),
     MintEvent(mint_event::MintEvent),
     PairCreatedEvent(pair_created_event::PairCreatedEvent),
+    PositionOrderClosedEvent(position_order_closed_event::PositionOrderClosedEvent),
+    PositionOrderPlacedEvent(position_order_placed_event::PositionOrderPlacedEvent),
     SwapEvent(swap_event::SwapEvent),
     UpdatePairEvent(update_pair_event::UpdatePairEvent),
     UserLiquidityPositionUpdatedEvent(user_liquidity_position_updated_event::UserLiquidityPositionUpdatedEvent),
+    UserPositionClosedEvent(user_position_closed_event::UserPositionClosedEvent),
     UserPositionCreatedEvent(user_position_created_event::UserPositionCreatedEvent),
+    UserPositionDelegateEvent(user_position_delegate_event::UserPositionDelegateEvent),
     UserPositionLiquidatedEvent(user_position_liquidated_event::UserPositionLiquidatedEvent),
+    UserPositionMarginModeEvent(user_position_margin_mode_event::UserPositionMarginModeEvent),
+    UserPositionTransferredEvent(user_position_transferred_event::UserPositionTransferredEvent),
     UserPositionUpdatedEvent(user_position_updated_event::UserPositionUpdatedEvent),
 }
 
@@ -104,16 +118,23 @@ impl<'a> carbon_core::instruction::InstructionDecoder<'a> for OmnipairDecoder {
             OmnipairInstruction::AdjustCollateralEvent => adjust_collateral_event::AdjustCollateralEvent,
             OmnipairInstruction::AdjustDebtEvent => adjust_debt_event::AdjustDebtEvent,
             OmnipairInstruction::AdjustLiquidityEvent => adjust_liquidity_event::AdjustLiquidityEvent,
+            OmnipairInstruction::BadDebtEvent => bad_debt_event::BadDebtEvent,
             OmnipairInstruction::BurnEvent => burn_event::BurnEvent,
             OmnipairInstruction::ClaimProtocolFeesEvent => claim_protocol_fees_event::ClaimProtocolFeesEvent,
             OmnipairInstruction::FlashloanEvent => flashloan_event::FlashloanEvent,
             OmnipairInstruction::MintEvent => mint_event::MintEvent,
             OmnipairInstruction::PairCreatedEvent => pair_created_event::PairCreatedEvent,
+            OmnipairInstruction::PositionOrderClosedEvent => position_order_closed_event::PositionOrderClosedEvent,
+            OmnipairInstruction::PositionOrderPlacedEvent => position_order_placed_event::PositionOrderPlacedEvent,
             OmnipairInstruction::SwapEvent => swap_event::SwapEvent,
             OmnipairInstruction::UpdatePairEvent => update_pair_event::UpdatePairEvent,
             OmnipairInstruction::UserLiquidityPositionUpdatedEvent => user_liquidity_position_updated_event::UserLiquidityPositionUpdatedEvent,
+            OmnipairInstruction::UserPositionClosedEvent => user_position_closed_event::UserPositionClosedEvent,
             OmnipairInstruction::UserPositionCreatedEvent => user_position_created_event::UserPositionCreatedEvent,
+            OmnipairInstruction::UserPositionDelegateEvent => user_position_delegate_event::UserPositionDelegateEvent,
             OmnipairInstruction::UserPositionLiquidatedEvent => user_position_liquidated_event::UserPositionLiquidatedEvent,
+            OmnipairInstruction::UserPositionMarginModeEvent => user_position_margin_mode_event::UserPositionMarginModeEvent,
+            OmnipairInstruction::UserPositionTransferredEvent => user_position_transferred_event::UserPositionTransferredEvent,
             OmnipairInstruction::UserPositionUpdatedEvent => user_position_updated_event::UserPositionUpdatedEvent,
         )
     }
@@ -0,0 +1,16 @@
+
+use super::super::types::*;
+
+use carbon_core::{borsh, CarbonDeserialize};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+#[carbon(discriminator = "0xe445a52e51cb9a1deab4004034ec9047")]
+pub struct PositionOrderClosedEvent{
+    pub order: solana_pubkey::Pubkey,
+    pub position: solana_pubkey::Pubkey,
+    pub executed: bool,
+    pub collateral_out: u64,
+    pub keeper_bounty: u64,
+    pub metadata: EventMetadata,
+}
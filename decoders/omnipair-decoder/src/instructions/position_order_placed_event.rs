@@ -0,0 +1,18 @@
+
+use super::super::types::*;
+
+use carbon_core::{borsh, CarbonDeserialize};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+#[carbon(discriminator = "0xe445a52e51cb9a1d20a74e92f89d1733")]
+pub struct PositionOrderPlacedEvent{
+    pub order: solana_pubkey::Pubkey,
+    pub position: solana_pubkey::Pubkey,
+    pub order_index: u16,
+    pub is_collateral_token0: bool,
+    pub trigger: OrderTrigger,
+    pub min_collateral_out: u64,
+    pub keeper_bounty: u64,
+    pub metadata: EventMetadata,
+}
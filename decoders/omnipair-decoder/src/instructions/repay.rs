@@ -14,7 +14,8 @@ pub struct Repay{
 pub struct RepayInstructionAccounts {
     pub pair: solana_pubkey::Pubkey,
     pub user_position: solana_pubkey::Pubkey,
-    pub rate_model: solana_pubkey::Pubkey,
+    pub rate_model0: solana_pubkey::Pubkey,
+    pub rate_model1: solana_pubkey::Pubkey,
     pub futarchy_authority: solana_pubkey::Pubkey,
     pub reserve_vault: solana_pubkey::Pubkey,
     pub user_reserve_token_account: solana_pubkey::Pubkey,
@@ -34,7 +35,8 @@ impl carbon_core::deserialize::ArrangeAccounts for Repay {
         let mut iter = accounts.iter();
         let pair = next_account(&mut iter)?;
         let user_position = next_account(&mut iter)?;
-        let rate_model = next_account(&mut iter)?;
+        let rate_model0 = next_account(&mut iter)?;
+        let rate_model1 = next_account(&mut iter)?;
         let futarchy_authority = next_account(&mut iter)?;
         let reserve_vault = next_account(&mut iter)?;
         let user_reserve_token_account = next_account(&mut iter)?;
@@ -49,7 +51,8 @@ impl carbon_core::deserialize::ArrangeAccounts for Repay {
         Some(RepayInstructionAccounts {
             pair,
             user_position,
-            rate_model,
+            rate_model0,
+            rate_model1,
             futarchy_authority,
             reserve_vault,
             user_reserve_token_account,
@@ -13,7 +13,8 @@ pub struct Swap{
 #[derive(Debug, PartialEq, Eq, Clone, Hash, serde::Serialize, serde::Deserialize)]
 pub struct SwapInstructionAccounts {
     pub pair: solana_pubkey::Pubkey,
-    pub rate_model: solana_pubkey::Pubkey,
+    pub rate_model0: solana_pubkey::Pubkey,
+    pub rate_model1: solana_pubkey::Pubkey,
     pub futarchy_authority: solana_pubkey::Pubkey,
     pub token_in_vault: solana_pubkey::Pubkey,
     pub token_out_vault: solana_pubkey::Pubkey,
@@ -34,7 +35,8 @@ impl carbon_core::deserialize::ArrangeAccounts for Swap {
     fn arrange_accounts(accounts: &[solana_instruction::AccountMeta]) -> Option<Self::ArrangedAccounts> {
         let mut iter = accounts.iter();
         let pair = next_account(&mut iter)?;
-        let rate_model = next_account(&mut iter)?;
+        let rate_model0 = next_account(&mut iter)?;
+        let rate_model1 = next_account(&mut iter)?;
         let futarchy_authority = next_account(&mut iter)?;
         let token_in_vault = next_account(&mut iter)?;
         let token_out_vault = next_account(&mut iter)?;
@@ -50,7 +52,8 @@ impl carbon_core::deserialize::ArrangeAccounts for Swap {
 
         Some(SwapInstructionAccounts {
             pair,
-            rate_model,
+            rate_model0,
+            rate_model1,
             futarchy_authority,
             token_in_vault,
             token_out_vault,
@@ -10,6 +10,7 @@ pub struct SwapEvent{
     pub reserve0: u64,
     pub reserve1: u64,
     pub is_token0_in: bool,
+    pub is_exact_out: bool,
     pub amount_in: u64,
     pub amount_out: u64,
     pub amount_in_after_fee: u64,
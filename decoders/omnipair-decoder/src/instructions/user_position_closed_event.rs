@@ -0,0 +1,12 @@
+
+use super::super::types::*;
+
+use carbon_core::{borsh, CarbonDeserialize};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+#[carbon(discriminator = "0xe445a52e51cb9a1d422023bbc5700fc3")]
+pub struct UserPositionClosedEvent{
+    pub position: solana_pubkey::Pubkey,
+    pub metadata: EventMetadata,
+}
@@ -9,4 +9,5 @@ use carbon_core::{borsh, CarbonDeserialize};
 pub struct UserPositionCreatedEvent{
     pub position: solana_pubkey::Pubkey,
     pub metadata: EventMetadata,
+    pub position_index: u16,
 }
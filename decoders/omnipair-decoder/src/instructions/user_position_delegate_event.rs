@@ -0,0 +1,15 @@
+
+use super::super::types::*;
+
+use carbon_core::{borsh, CarbonDeserialize};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+#[carbon(discriminator = "0xe445a52e51cb9a1d4d07015c4578f10f")]
+pub struct UserPositionDelegateEvent{
+    pub position: solana_pubkey::Pubkey,
+    pub delegate: solana_pubkey::Pubkey,
+    pub permission: DelegatePermission,
+    pub expiry_slot: u64,
+    pub metadata: EventMetadata,
+}
@@ -0,0 +1,12 @@
+use super::super::types::*;
+
+use carbon_core::{borsh, CarbonDeserialize};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+#[carbon(discriminator = "0xe445a52e51cb9a1d883bc8ce01038a14")]
+pub struct UserPositionMarginModeEvent{
+    pub position: solana_pubkey::Pubkey,
+    pub cross_margin: bool,
+    pub metadata: EventMetadata,
+}
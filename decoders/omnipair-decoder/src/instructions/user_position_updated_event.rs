@@ -8,6 +8,7 @@ use carbon_core::{borsh, CarbonDeserialize};
 #[carbon(discriminator = "0xe445a52e51cb9a1d53a8c558592a3a66")]
 pub struct UserPositionUpdatedEvent{
     pub position: solana_pubkey::Pubkey,
+    pub owner: solana_pubkey::Pubkey,
     pub collateral0: u64,
     pub collateral1: u64,
     pub debt0_shares: u128,
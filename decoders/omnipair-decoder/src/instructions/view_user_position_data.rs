@@ -14,7 +14,8 @@ pub struct ViewUserPositionData{
 pub struct ViewUserPositionDataInstructionAccounts {
     pub pair: solana_pubkey::Pubkey,
     pub user_position: solana_pubkey::Pubkey,
-    pub rate_model: solana_pubkey::Pubkey,
+    pub rate_model0: solana_pubkey::Pubkey,
+    pub rate_model1: solana_pubkey::Pubkey,
     pub futarchy_authority: solana_pubkey::Pubkey,
 }
 
@@ -25,13 +26,15 @@ impl carbon_core::deserialize::ArrangeAccounts for ViewUserPositionData {
         let mut iter = accounts.iter();
         let pair = next_account(&mut iter)?;
         let user_position = next_account(&mut iter)?;
-        let rate_model = next_account(&mut iter)?;
+        let rate_model0 = next_account(&mut iter)?;
+        let rate_model1 = next_account(&mut iter)?;
         let futarchy_authority = next_account(&mut iter)?;
 
         Some(ViewUserPositionDataInstructionAccounts {
             pair,
             user_position,
-            rate_model,
+            rate_model0,
+            rate_model1,
             futarchy_authority,
         })
     }
@@ -6,4 +6,5 @@ use carbon_core::{CarbonDeserialize, borsh};
 #[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
 pub struct AdjustCollateralArgs {
     pub amount: u64,
+    pub position_index: u16,
 }
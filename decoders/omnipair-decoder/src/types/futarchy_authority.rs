@@ -13,4 +13,5 @@ pub struct FutarchyAuthority {
     pub revenue_distribution: RevenueDistribution,
     pub global_reduce_only: bool,
     pub bump: u8,
+    pub risk_params: RiskParams,
 }
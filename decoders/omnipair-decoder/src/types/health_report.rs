@@ -0,0 +1,13 @@
+
+use super::*;
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+/// Return data of `view_user_position_data` with `UserPositionViewKind::HealthReport`.
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct HealthReport {
+    pub version: u8,
+    pub debt0: PositionSideHealth,
+    pub debt1: PositionSideHealth,
+}
@@ -10,10 +10,14 @@ pub mod adjust_debt_event;
 pub use adjust_debt_event::*;
 pub mod adjust_liquidity_event;
 pub use adjust_liquidity_event::*;
+pub mod bad_debt_event;
+pub use bad_debt_event::*;
 pub mod burn_event;
 pub use burn_event::*;
 pub mod claim_protocol_fees_event;
 pub use claim_protocol_fees_event::*;
+pub mod delegate_permission;
+pub use delegate_permission::*;
 pub mod emit_value_args;
 pub use emit_value_args::*;
 pub mod event_metadata;
@@ -24,6 +28,8 @@ pub mod flashloan_event;
 pub use flashloan_event::*;
 pub mod futarchy_authority;
 pub use futarchy_authority::*;
+pub mod health_report;
+pub use health_report::*;
 pub mod init_futarchy_authority_args;
 pub use init_futarchy_authority_args::*;
 pub mod initialize_and_bootstrap_args;
@@ -32,14 +38,38 @@ pub mod last_price_ema;
 pub use last_price_ema::*;
 pub mod mint_event;
 pub use mint_event::*;
+pub mod optional_uint;
+pub use optional_uint::*;
+pub mod order_price_source;
+pub use order_price_source::*;
+pub mod order_trigger;
+pub use order_trigger::*;
 pub mod pair;
 pub use pair::*;
+pub mod pair_caps;
+pub use pair_caps::*;
 pub mod pair_created_event;
 pub use pair_created_event::*;
+pub mod pair_snapshot;
+pub use pair_snapshot::*;
 pub mod pair_view_kind;
 pub use pair_view_kind::*;
+pub mod pending_rate_model_update;
+pub use pending_rate_model_update::*;
+pub mod position_order;
+pub use position_order::*;
+pub mod position_order_closed_event;
+pub use position_order_closed_event::*;
+pub mod position_order_placed_event;
+pub use position_order_placed_event::*;
+pub mod position_side_health;
+pub use position_side_health::*;
 pub mod rate_model;
 pub use rate_model::*;
+pub mod rate_model_kind;
+pub use rate_model_kind::*;
+pub mod rate_model_params;
+pub use rate_model_params::*;
 pub mod remove_liquidity_args;
 pub use remove_liquidity_args::*;
 pub mod revenue_distribution;
@@ -48,6 +78,8 @@ pub mod revenue_recipients;
 pub use revenue_recipients::*;
 pub mod revenue_share;
 pub use revenue_share::*;
+pub mod risk_params;
+pub use risk_params::*;
 pub mod set_global_reduce_only_args;
 pub use set_global_reduce_only_args::*;
 pub mod set_pair_reduce_only_args;
@@ -56,6 +88,8 @@ pub mod swap_args;
 pub use swap_args::*;
 pub mod swap_event;
 pub use swap_event::*;
+pub mod trigger_condition;
+pub use trigger_condition::*;
 pub mod update_futarchy_authority_args;
 pub use update_futarchy_authority_args::*;
 pub mod update_pair_event;
@@ -68,13 +102,23 @@ pub mod user_liquidity_position_updated_event;
 pub use user_liquidity_position_updated_event::*;
 pub mod user_position;
 pub use user_position::*;
+pub mod user_position_closed_event;
+pub use user_position_closed_event::*;
 pub mod user_position_created_event;
 pub use user_position_created_event::*;
+pub mod user_position_delegate_event;
+pub use user_position_delegate_event::*;
 pub mod user_position_liquidated_event;
 pub use user_position_liquidated_event::*;
+pub mod user_position_margin_mode_event;
+pub use user_position_margin_mode_event::*;
+pub mod user_position_transferred_event;
+pub use user_position_transferred_event::*;
 pub mod user_position_updated_event;
 pub use user_position_updated_event::*;
 pub mod user_position_view_kind;
 pub use user_position_view_kind::*;
 pub mod vault_bumps;
-pub use vault_bumps::*;
\ No newline at end of file
+pub use vault_bumps::*;
+pub mod view_return_data;
+pub use view_return_data::*;
\ No newline at end of file
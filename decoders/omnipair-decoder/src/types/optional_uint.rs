@@ -0,0 +1,16 @@
+
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub enum OptionalUint {
+    U64(u64),
+    U128(u128),
+    U16(u16),
+    OptionalU64(Option<u64>),
+    OptionalU128(Option<u128>),
+    OptionalU16(Option<u16>),
+}
+
+
@@ -0,0 +1,14 @@
+
+
+use super::*;
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct OrderTrigger {
+    pub price_source: OrderPriceSource,
+    pub is_price0: bool,
+    pub condition: TriggerCondition,
+    pub price_nad: u64,
+}
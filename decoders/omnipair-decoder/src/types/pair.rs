@@ -9,7 +9,7 @@ pub struct Pair {
     pub token0: solana_pubkey::Pubkey,
     pub token1: solana_pubkey::Pubkey,
     pub lp_mint: solana_pubkey::Pubkey,
-    pub rate_model: solana_pubkey::Pubkey,
+    pub rate_model0: solana_pubkey::Pubkey,
     pub swap_fee_bps: u16,
     pub half_life: u64,
     pub fixed_cf_bps: Option<u16>,
@@ -36,4 +36,16 @@ pub struct Pair {
     pub bump: u8,
     pub vault_bumps: VaultBumps,
     pub reduce_only: bool,
+    pub rate_model1: solana_pubkey::Pubkey,
+    pub insurance_reserve0: u64,
+    pub insurance_reserve1: u64,
+    pub insurance_penalty_share_bps: u16,
+    pub insurance_interest_share_bps: u16,
+    pub total_bad_debt0: u64,
+    pub total_bad_debt1: u64,
+    pub total_socialized_debt0: u64,
+    pub total_socialized_debt1: u64,
+    pub risk_params: RiskParams,
+    pub risk_params_overridden: bool,
+    pub caps: PairCaps,
 }
@@ -0,0 +1,13 @@
+
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct PairCaps {
+    pub max_debt0: u64,
+    pub max_debt1: u64,
+    pub max_utilization_bps: u16,
+    pub max_collateral0: u64,
+    pub max_collateral1: u64,
+}
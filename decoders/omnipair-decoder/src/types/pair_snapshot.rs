@@ -0,0 +1,37 @@
+
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+/// Return data of `view_pair_data` with `PairViewKind::FullSnapshot`.
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct PairSnapshot {
+    pub version: u8,
+    pub last_update: u64,
+    pub spot_price0_nad: u64,
+    pub spot_price1_nad: u64,
+    pub ema_price0_nad: u64,
+    pub ema_price1_nad: u64,
+    pub directional_ema_price0_nad: u64,
+    pub directional_ema_price1_nad: u64,
+    pub rate0: u64,
+    pub rate1: u64,
+    pub rate_model0: solana_pubkey::Pubkey,
+    pub rate_model1: solana_pubkey::Pubkey,
+    pub utilization0_nad: u64,
+    pub utilization1_nad: u64,
+    pub reserve0: u64,
+    pub reserve1: u64,
+    pub cash_reserve0: u64,
+    pub cash_reserve1: u64,
+    pub total_debt0: u64,
+    pub total_debt1: u64,
+    pub total_debt0_shares: u128,
+    pub total_debt1_shares: u128,
+    pub total_collateral0: u64,
+    pub total_collateral1: u64,
+    pub total_supply: u64,
+    pub k: u128,
+    pub claimable_protocol_fee0: Option<u64>,
+    pub claimable_protocol_fee1: Option<u64>,
+}
@@ -15,6 +15,10 @@ pub enum PairViewKind {
     Reserves,
     CashReserves,
     SwapQuote,
+    SimulateLiquidationPrice,
+    FullSnapshot,
+    DebtCaps,
+    CollateralCaps,
 }
 
 
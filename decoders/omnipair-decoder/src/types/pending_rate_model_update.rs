@@ -0,0 +1,12 @@
+use super::*;
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct PendingRateModelUpdate {
+    pub params: RateModelParams,
+    pub update_delay_slots: u64,
+    pub eta_slot: u64,
+}
+
@@ -0,0 +1,16 @@
+
+
+use super::*;
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct PositionOrderClosedEvent {
+    pub order: solana_pubkey::Pubkey,
+    pub position: solana_pubkey::Pubkey,
+    pub executed: bool,
+    pub collateral_out: u64,
+    pub keeper_bounty: u64,
+    pub metadata: EventMetadata,
+}
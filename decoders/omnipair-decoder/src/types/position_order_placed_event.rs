@@ -0,0 +1,18 @@
+
+
+use super::*;
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct PositionOrderPlacedEvent {
+    pub order: solana_pubkey::Pubkey,
+    pub position: solana_pubkey::Pubkey,
+    pub order_index: u16,
+    pub is_collateral_token0: bool,
+    pub trigger: OrderTrigger,
+    pub min_collateral_out: u64,
+    pub keeper_bounty: u64,
+    pub metadata: EventMetadata,
+}
@@ -0,0 +1,17 @@
+
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct PositionSideHealth {
+    pub collateral: u64,
+    pub debt: u64,
+    pub borrow_limit: u64,
+    pub liquidation_limit: u64,
+    pub liquidation_cf_bps: u16,
+    pub health_factor_bps: u64,
+    pub liquidation_price_nad: u64,
+    pub max_withdrawable_collateral: u64,
+    pub max_additional_borrow: u64,
+}
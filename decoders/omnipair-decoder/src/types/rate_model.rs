@@ -1,5 +1,7 @@
 
 
+use super::*;
+
 use carbon_core::{CarbonDeserialize, borsh};
 
 
@@ -12,4 +14,12 @@ pub struct RateModel {
     pub min_rate: u64,
     pub max_rate: u64,
     pub initial_rate: u64,
+    pub kind: RateModelKind,
+    pub kink_util: u64,
+    pub base_rate: u64,
+    pub slope1: u64,
+    pub slope2: u64,
+    pub update_delay_slots: u64,
+    pub pending_update: Option<PendingRateModelUpdate>,
+    pub pair_references: u64,
 }
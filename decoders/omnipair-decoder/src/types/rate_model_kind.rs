@@ -0,0 +1,9 @@
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub enum RateModelKind {
+    Exponential,
+    Kinked,
+}
+
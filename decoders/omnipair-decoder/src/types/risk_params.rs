@@ -0,0 +1,18 @@
+
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct RiskParams {
+    pub close_factor_bps: u16,
+    pub max_collateral_factor_bps: u16,
+    pub ltv_buffer_bps: u16,
+    pub liquidation_incentive_bps: u16,
+    pub liquidation_incentive_slope_bps: u16,
+    pub max_liquidation_incentive_bps: u16,
+    pub liquidation_penalty_bps: u16,
+    pub flashloan_fee_bps: u16,
+    pub liquidity_withdrawal_fee_bps: u16,
+    pub post_withdraw_debt_coverage_bps: u16,
+}
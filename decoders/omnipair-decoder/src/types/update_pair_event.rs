@@ -14,6 +14,8 @@ pub struct UpdatePairEvent {
     pub accrued_interest1: u128,
     pub lp_interest0: u64,
     pub lp_interest1: u64,
+    pub insurance_interest0: u64,
+    pub insurance_interest1: u64,
     pub protocol_interest0: u64,
     pub protocol_interest1: u64,
     pub cash_reserve0: u64,
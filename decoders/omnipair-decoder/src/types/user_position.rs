@@ -1,4 +1,5 @@
 
+use super::*;
 
 use carbon_core::{CarbonDeserialize, borsh};
 
@@ -14,4 +15,10 @@ pub struct UserPosition {
     pub debt0_shares: u128,
     pub debt1_shares: u128,
     pub bump: u8,
+    pub cross_margin: bool,
+    pub position_index: u16,
+    pub delegate: solana_pubkey::Pubkey,
+    pub delegate_permission: DelegatePermission,
+    pub delegate_expiry_slot: u64,
+    pub open_orders: u16,
 }
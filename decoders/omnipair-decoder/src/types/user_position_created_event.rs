@@ -8,4 +8,5 @@ use carbon_core::{CarbonDeserialize, borsh};
 pub struct UserPositionCreatedEvent {
     pub position: solana_pubkey::Pubkey,
     pub metadata: EventMetadata,
+    pub position_index: u16,
 }
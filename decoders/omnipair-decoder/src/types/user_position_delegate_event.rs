@@ -0,0 +1,15 @@
+
+
+use super::*;
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct UserPositionDelegateEvent {
+    pub position: solana_pubkey::Pubkey,
+    pub delegate: solana_pubkey::Pubkey,
+    pub permission: DelegatePermission,
+    pub expiry_slot: u64,
+    pub metadata: EventMetadata,
+}
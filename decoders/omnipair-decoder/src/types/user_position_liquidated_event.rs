@@ -15,6 +15,8 @@ pub struct UserPositionLiquidatedEvent {
     pub collateral_price: u64,
     pub shortfall: u128,
     pub liquidation_bonus_applied: u64,
+    pub liquidation_incentive_bps: u16,
+    pub debt_repaid_by_liquidator: u64,
     pub k0: u128,
     pub k1: u128,
     pub metadata: EventMetadata,
@@ -0,0 +1,18 @@
+
+
+use super::*;
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct UserPositionTransferredEvent {
+    pub from_position: solana_pubkey::Pubkey,
+    pub to_position: solana_pubkey::Pubkey,
+    pub new_owner: solana_pubkey::Pubkey,
+    pub collateral0: u64,
+    pub collateral1: u64,
+    pub debt0_shares: u128,
+    pub debt1_shares: u128,
+    pub metadata: EventMetadata,
+}
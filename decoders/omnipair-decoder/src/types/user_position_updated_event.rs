@@ -7,6 +7,7 @@ use carbon_core::{CarbonDeserialize, borsh};
 #[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
 pub struct UserPositionUpdatedEvent {
     pub position: solana_pubkey::Pubkey,
+    pub owner: solana_pubkey::Pubkey,
     pub collateral0: u64,
     pub collateral1: u64,
     pub debt0_shares: u128,
@@ -14,6 +14,7 @@ pub enum UserPositionViewKind {
     UserIsLiquidatable,
     UserCollateralValueWithImpact,
     UserLiquidationBorrowLimit,
+    HealthReport,
 }
 
 
@@ -0,0 +1,14 @@
+
+use super::*;
+
+use carbon_core::{CarbonDeserialize, borsh};
+
+
+/// Return data of `view_pair_data` / `view_user_position_data`.
+#[derive(CarbonDeserialize, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct ViewReturnData {
+    pub version: u8,
+    pub value0: OptionalUint,
+    pub value1: OptionalUint,
+    pub value2: OptionalUint,
+}
@@ -9,23 +9,53 @@ pub const NAD_DECIMALS: u8 = 9;
 #[constant]
 pub const BPS_DENOMINATOR: u16 = 10_000;
 #[constant]
-pub const CLOSE_FACTOR_BPS: u16 = 5_000; // 50%
+pub const DEFAULT_INSURANCE_PENALTY_SHARE_BPS: u16 = 2_000; // 20% of the LP part of liquidation penalties to the insurance reserve
 #[constant]
-pub const MAX_COLLATERAL_FACTOR_BPS: u16 = 8_500; // 85% cap for dynamic collateral factor
+pub const DEFAULT_INSURANCE_INTEREST_SHARE_BPS: u16 = 500; // 5% of LP interest to the insurance reserve
+
+// Default risk parameters (`RiskParams::default`), see `FutarchyAuthority::risk_params` and `Pair::risk_params`
+#[constant]
+pub const DEFAULT_CLOSE_FACTOR_BPS: u16 = 5_000; // 50%
+#[constant]
+pub const DEFAULT_MAX_COLLATERAL_FACTOR_BPS: u16 = 8_500; // 85% cap for dynamic collateral factor
+#[constant]
+pub const DEFAULT_LTV_BUFFER_BPS: u16 = 500; // 5% buffer between borrow limit and liquidation threshold
+#[constant]
+pub const DEFAULT_FLASHLOAN_FEE_BPS: u16 = 5; // 0.05%
+#[constant]
+pub const DEFAULT_LIQUIDATION_INCENTIVE_BPS: u16 = 50; // 0.5% liquidation incentive for caller at the liquidation threshold
 #[constant]
-pub const LTV_BUFFER_BPS: u16 = 500; // 5% buffer between borrow limit and liquidation threshold
+pub const DEFAULT_LIQUIDATION_INCENTIVE_SLOPE_BPS: u16 = 2_000; // +0.2 bps of incentive per bps the debt exceeds the borrow limit
+#[constant]
+pub const DEFAULT_MAX_LIQUIDATION_INCENTIVE_BPS: u16 = 250; // 2.5% cap, below the penalty so LPs keep a share of it
+#[constant]
+pub const DEFAULT_LIQUIDATION_PENALTY_BPS: u16 = 300; // 3% total liquidation penalty (incentive to liquidator, the rest to LPs)
+#[constant]
+pub const DEFAULT_LIQUIDITY_WITHDRAWAL_FEE_BPS: u16 = 100; // 1% fee on liquidity withdrawal (goes to remaining LPs)
+#[constant]
+pub const DEFAULT_POST_WITHDRAW_DEBT_COVERAGE_BPS: u16 = 11_500; // 115% debt coverage required after liquidity withdrawal
+
+// Hard bounds of the risk parameters (`RiskParams::validate`)
 #[constant]
-pub const FLASHLOAN_FEE_BPS: u16 = 5; // 0.05%
+pub const MIN_CLOSE_FACTOR_BPS: u16 = 1_000; // 10%
 #[constant]
-pub const LIQUIDATION_INCENTIVE_BPS: u16 = 50; // 0.5% liquidation incentive for caller
+pub const MAX_COLLATERAL_FACTOR_BPS: u16 = 9_500; // 95%
 #[constant]
-pub const LIQUIDATION_PENALTY_BPS: u16 = 300; // 3% total liquidation penalty (0.5% to liquidator, 2.5% to LPs)
+pub const MIN_LTV_BUFFER_BPS: u16 = 100; // 1%
 #[constant]
-pub const LIQUIDITY_WITHDRAWAL_FEE_BPS: u16 = 100; // 1% fee on liquidity withdrawal (goes to remaining LPs)
+pub const MAX_LTV_BUFFER_BPS: u16 = 3_000; // 30%
 #[constant]
-pub const POST_WITHDRAW_DEBT_COVERAGE_BPS: u16 = 11_500; // 115% debt coverage required after liquidity withdrawal
+pub const MAX_LIQUIDATION_PENALTY_BPS: u16 = 2_000; // 20%
+#[constant]
+pub const MAX_FLASHLOAN_FEE_BPS: u16 = 100; // 1%
+#[constant]
+pub const MAX_LIQUIDITY_WITHDRAWAL_FEE_BPS: u16 = 1_000; // 10%
+#[constant]
+pub const MAX_POST_WITHDRAW_DEBT_COVERAGE_BPS: u16 = 20_000; // 200%; the minimum is 100%
 #[constant]
 pub const PAIR_CREATION_FEE_LAMPORTS: u64 = 200_000_000; // 0.2 SOL
+#[constant]
+pub const MAX_SWAP_ROUTE_HOPS: u8 = 4; // max pairs traversed by a single swap_route
 // 3log2(100) = 19.93 secs (with 400ms slot time, this is ~50 slots)
 #[constant]
 pub const DIRECTIONAL_EMA_HALF_LIFE_MS: u64 = 3_000; // 3 seconds
@@ -94,12 +124,22 @@ pub const MIN_TARGET_UTIL_BPS: u64 = 100;  // 1% minimum for target_util_start
 #[constant]
 pub const MAX_TARGET_UTIL_BPS: u64 = 10_000;  // 100% maximum for target_util_end
 
+// Rate model update timelock (queued by update_rate_model, applied by apply_rate_model_update)
+#[constant]
+pub const DEFAULT_RATE_MODEL_UPDATE_DELAY_SLOTS: u64 = MS_PER_DAY / TARGET_MS_PER_SLOT;  // ~1 day
+#[constant]
+pub const MIN_RATE_MODEL_UPDATE_DELAY_SLOTS: u64 = MS_PER_DAY / 24 / TARGET_MS_PER_SLOT;  // ~1 hour
+#[constant]
+pub const MAX_RATE_MODEL_UPDATE_DELAY_SLOTS: u64 = 30 * MS_PER_DAY / TARGET_MS_PER_SLOT;  // ~30 days
+
 // Global Seeds for deterministic PDAs
 #[constant]
 pub const PAIR_SEED_PREFIX: &[u8] = b"gamm_pair";
 #[constant]
 pub const POSITION_SEED_PREFIX: &[u8] = b"gamm_position";
 #[constant]
+pub const POSITION_ORDER_SEED_PREFIX: &[u8] = b"gamm_position_order";
+#[constant]
 pub const FUTARCHY_AUTHORITY_SEED_PREFIX: &[u8] = b"futarchy_authority";
 #[constant]
 pub const METADATA_SEED_PREFIX: &[u8] = b"metadata";
@@ -111,6 +151,9 @@ pub const COLLATERAL_VAULT_SEED_PREFIX: &[u8] = b"collateral_vault";
 pub const RATE_MODEL_SEED_PREFIX: &[u8] = b"rate_model";
 #[constant]
 pub const VERSION: u8 = 1;
+/// Leading byte of the view instructions' return data; bump when the layout changes.
+#[constant]
+pub const VIEW_RETURN_DATA_VERSION: u8 = 2;
 
 /// Emergency signer authorized to toggle reduce-only mode.
 /// For Squads, use the vault/authority signer address.
@@ -200,7 +200,7 @@ pub enum ErrorCode {
     #[msg("Invalid token order")]
     InvalidTokenOrder,
 
-    #[msg("Invalid rate model - rate_model does not match pair.rate_model")]
+    #[msg("Invalid rate model - does not match pair.rate_model0 / pair.rate_model1")]
     InvalidRateModel,
 
     #[msg("Invalid pair - pair does not match user_position.pair")]
@@ -209,7 +209,7 @@ pub enum ErrorCode {
     #[msg("Invalid utilization bounds - must satisfy: MIN <= start < end <= MAX")]
     InvalidUtilBounds,
 
-    #[msg("Invalid rate parameters - check half_life_ms, min_rate_bps, max_rate_bps, initial_rate_bps bounds")]
+    #[msg("Invalid rate parameters - check half_life_ms, min_rate_bps, max_rate_bps, initial_rate_bps (or kinked) bounds")]
     InvalidRateParams,
 
     #[msg("Operation blocked: reduce-only mode is active")]
@@ -232,4 +232,73 @@ pub enum ErrorCode {
 
     #[msg("Invalid recipient - address does not match configured revenue recipient")]
     InvalidRecipient,
+
+    #[msg("Invalid swap route - check hop count, pairs, vaults and mints")]
+    InvalidSwapRoute,
+
+    #[msg("No rate model provided - pass new_rate_model0 and/or new_rate_model1")]
+    MissingRateModel,
+
+    #[msg("Pair account is already migrated to the current layout")]
+    PairAlreadyMigrated,
+
+    #[msg("Rate model account is already migrated to the current layout")]
+    RateModelAlreadyMigrated,
+
+    #[msg("Invalid rate model update delay - outside MIN/MAX_RATE_MODEL_UPDATE_DELAY_SLOTS")]
+    InvalidRateModelUpdateDelay,
+
+    #[msg("No rate model update is pending")]
+    NoPendingRateModelUpdate,
+
+    #[msg("Rate model update is still timelocked")]
+    RateModelUpdateNotReady,
+
+    #[msg("Invalid affected pairs - pass [pair, rate_model0, rate_model1] once for every pair using the rate model")]
+    InvalidAffectedPairs,
+
+    #[msg("Repay amount exceeds the debt liquidatable under the close factor")]
+    RepayExceedsCloseFactor,
+
+    #[msg("Invalid insurance share bps")]
+    InvalidInsuranceShareBps,
+
+    #[msg("Invalid risk parameters - outside of the hard bounds in constants.rs")]
+    InvalidRiskParams,
+
+    #[msg("Futarchy authority account is already migrated to the current layout")]
+    FutarchyAuthorityAlreadyMigrated,
+
+    #[msg("User position account is already migrated to the current layout")]
+    UserPositionAlreadyMigrated,
+
+    #[msg("Cross-margin collateral must be netted first - liquidate with the other token as collateral")]
+    CrossMarginNettingRequired,
+
+    #[msg("Signer is neither the position owner nor a delegate with the required permission")]
+    PositionDelegateUnauthorized,
+
+    #[msg("User position still holds collateral or debt")]
+    UserPositionNotEmpty,
+
+    #[msg("Position order trigger condition is not met")]
+    PositionOrderNotTriggered,
+
+    #[msg("Invalid pair caps - utilization cap above 100%")]
+    InvalidPairCaps,
+
+    #[msg("Borrow would exceed the pair's total debt cap")]
+    BorrowCapExceeded,
+
+    #[msg("Borrow would exceed the pair's utilization cap")]
+    UtilizationCapExceeded,
+
+    #[msg("Collateral would exceed the pair's total collateral cap")]
+    CollateralCapExceeded,
+
+    #[msg("User position still has open orders - cancel them first")]
+    PositionHasOpenOrders,
+
+    #[msg("Only the owner can open a position - others can add collateral to existing ones")]
+    OnlyOwnerCanOpenPosition,
 }
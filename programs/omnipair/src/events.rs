@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::state::{DelegatePermission, OrderTrigger};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct EventMetadata {
@@ -22,6 +23,8 @@ pub struct SwapEvent {
     pub reserve0: u64,
     pub reserve1: u64,
     pub is_token0_in: bool,
+    /// True for `swap_exact_out`, false for `swap`
+    pub is_exact_out: bool,
     pub amount_in: u64,
     pub amount_out: u64,
     pub amount_in_after_fee: u64,
@@ -110,14 +113,18 @@ pub struct UpdatePairEvent {
     pub price1_ema: u64,
     pub rate0: u64,
     pub rate1: u64,
-    /// Total interest (token0) applied to borrowers this update = lp_interest0 + protocol_interest0
+    /// Total interest (token0) applied to borrowers this update = lp_interest0 + insurance_interest0 + protocol_interest0
     pub accrued_interest0: u128,
-    /// Total interest (token1) applied to borrowers this update = lp_interest1 + protocol_interest1
+    /// Total interest (token1) applied to borrowers this update = lp_interest1 + insurance_interest1 + protocol_interest1
     pub accrued_interest1: u128,
     /// Interest (token0) to LPs this update, added to reserves
     pub lp_interest0: u64,
     /// Interest (token1) to LPs this update, added to reserves
     pub lp_interest1: u64,
+    /// Interest (token0) to the insurance reserve this update
+    pub insurance_interest0: u64,
+    /// Interest (token1) to the insurance reserve this update
+    pub insurance_interest1: u64,
     /// Interest (token0) to protocol this update
     pub protocol_interest0: u64,
     /// Interest (token1) to protocol this update
@@ -133,11 +140,47 @@ pub struct UpdatePairEvent {
 pub struct UserPositionCreatedEvent {
     pub position: Pubkey,
     pub metadata: EventMetadata,
+    pub position_index: u16,
+}
+
+/// Emitted when an empty position account is closed and its rent returned to the owner.
+#[event]
+pub struct UserPositionClosedEvent {
+    pub position: Pubkey,
+    pub metadata: EventMetadata,
+}
+
+/// Emitted when an owner places a stop-loss or take-profit order on a position.
+#[event]
+pub struct PositionOrderPlacedEvent {
+    pub order: Pubkey,
+    pub position: Pubkey,
+    pub order_index: u16,
+    pub is_collateral_token0: bool,
+    pub trigger: OrderTrigger,
+    pub min_collateral_out: u64,
+    pub keeper_bounty: u64,
+    pub metadata: EventMetadata,
+}
+
+/// Emitted when a position order is executed by a keeper or cancelled by its owner.
+#[event]
+pub struct PositionOrderClosedEvent {
+    pub order: Pubkey,
+    pub position: Pubkey,
+    pub executed: bool,
+    /// Collateral returned to the owner, 0 if cancelled
+    pub collateral_out: u64,
+    /// Collateral paid to the keeper, 0 if cancelled
+    pub keeper_bounty: u64,
+    pub metadata: EventMetadata,
 }
 
 #[event]
 pub struct UserPositionUpdatedEvent {
     pub position: Pubkey,
+    /// Owner of the position; `metadata.signer` is whoever acted on it, e.g. a payer or delegate
+    pub owner: Pubkey,
     pub collateral0: u64,
     pub collateral1: u64,
     pub debt0_shares: u128,
@@ -149,6 +192,37 @@ pub struct UserPositionUpdatedEvent {
     pub metadata: EventMetadata,
 }
 
+/// Emitted when a position switches between isolated and cross-margin mode.
+#[event]
+pub struct UserPositionMarginModeEvent {
+    pub position: Pubkey,
+    pub cross_margin: bool,
+    pub metadata: EventMetadata,
+}
+
+/// Emitted when the owner sets, changes or revokes the delegate of a position.
+#[event]
+pub struct UserPositionDelegateEvent {
+    pub position: Pubkey,
+    pub delegate: Pubkey,
+    pub permission: DelegatePermission,
+    pub expiry_slot: u64,
+    pub metadata: EventMetadata,
+}
+
+/// Emitted when an owner moves the balances of a position into a position of a new owner.
+#[event]
+pub struct UserPositionTransferredEvent {
+    pub from_position: Pubkey,
+    pub to_position: Pubkey,
+    pub new_owner: Pubkey,
+    pub collateral0: u64,
+    pub collateral1: u64,
+    pub debt0_shares: u128,
+    pub debt1_shares: u128,
+    pub metadata: EventMetadata,
+}
+
 #[event]
 pub struct UserPositionLiquidatedEvent {
     pub position: Pubkey,
@@ -160,11 +234,34 @@ pub struct UserPositionLiquidatedEvent {
     pub collateral_price: u64,
     pub shortfall: u128,
     pub liquidation_bonus_applied: u64,
+    /// Incentive rate the bonus was computed at, scaled by how far the debt exceeded the borrow limit
+    pub liquidation_incentive_bps: u16,
+    /// Debt tokens brought by the liquidator (`liquidate_with_repay`), 0 when the debt is written off
+    pub debt_repaid_by_liquidator: u64,
     pub k0: u128,
     pub k1: u128,
     pub metadata: EventMetadata,
 }
 
+/// Emitted when an insolvent liquidation writes off more debt than the collateral covers.
+#[event]
+pub struct BadDebtEvent {
+    pub position: Pubkey,
+    pub is_token0_debt: bool,
+    /// Shortfall (debt token units) of the liquidation
+    pub bad_debt: u64,
+    /// Part of the shortfall covered by the insurance reserve
+    pub covered_by_insurance: u64,
+    /// Part of the shortfall lost by LPs
+    pub socialized: u64,
+    /// Insurance reserve (debt token) after covering
+    pub insurance_reserve: u64,
+    /// Cumulative pair counters (debt token) after this liquidation
+    pub total_bad_debt: u64,
+    pub total_socialized_debt: u64,
+    pub metadata: EventMetadata,
+}
+
 #[event]
 pub struct FlashloanEvent {
     pub amount0: u64,
@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+use anchor_spl::token_interface::TokenAccount;
 use crate::state::{Pair, UserPosition, RateModel, FutarchyAuthority};
 use std::fmt;
 use crate::errors::ErrorCode;
@@ -47,6 +49,281 @@ impl OptionalUint {
     pub fn from_optional_u16(val: Option<u16>) -> Self { OptionalUint::OptionalU16(val) }
 }
 
+/// Return data of the view instructions, Borsh-encoded via `set_return_data`.
+/// The leading `version` byte (`VIEW_RETURN_DATA_VERSION`) lets consumers reject layouts they don't know.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ViewReturnData {
+    pub version: u8,
+    pub value0: OptionalUint,
+    pub value1: OptionalUint,
+    pub value2: OptionalUint,
+}
+
+/// Logs the view result and publishes it as return data for CPI callers and simulations.
+fn emit_view_value(getter: impl fmt::Display, value: (OptionalUint, OptionalUint, OptionalUint)) -> Result<()> {
+    msg!("{}: {:?}", getter, value);
+
+    let (value0, value1, value2) = value;
+    let return_data = ViewReturnData {
+        version: VIEW_RETURN_DATA_VERSION,
+        value0,
+        value1,
+        value2,
+    };
+    let mut data = Vec::new();
+    return_data.serialize(&mut data)?;
+    set_return_data(&data);
+    Ok(())
+}
+
+/// Return data of `PairViewKind::FullSnapshot`, Borsh-encoded via `set_return_data`.
+/// Prices are NAD-scaled (token1 per token0 for `*0`, token0 per token1 for `*1`); rates and utilization are NAD-scaled.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PairSnapshot {
+    pub version: u8,
+    pub last_update: u64,
+    pub spot_price0_nad: u64,
+    pub spot_price1_nad: u64,
+    pub ema_price0_nad: u64,
+    pub ema_price1_nad: u64,
+    pub directional_ema_price0_nad: u64,
+    pub directional_ema_price1_nad: u64,
+    pub rate0: u64,
+    pub rate1: u64,
+    /// Rate models driving `rate0` / `rate1`
+    pub rate_model0: Pubkey,
+    pub rate_model1: Pubkey,
+    pub utilization0_nad: u64,
+    pub utilization1_nad: u64,
+    pub reserve0: u64,
+    pub reserve1: u64,
+    pub cash_reserve0: u64,
+    pub cash_reserve1: u64,
+    pub total_debt0: u64,
+    pub total_debt1: u64,
+    pub total_debt0_shares: u128,
+    pub total_debt1_shares: u128,
+    pub total_collateral0: u64,
+    pub total_collateral1: u64,
+    pub total_supply: u64,
+    pub k: u128,
+    /// `None` unless the reserve vaults are passed as remaining accounts
+    pub claimable_protocol_fee0: Option<u64>,
+    pub claimable_protocol_fee1: Option<u64>,
+}
+
+impl PairSnapshot {
+    pub fn new(pair: &Pair, rate_model0: &RateModel, rate_model1: &RateModel, claimable_protocol_fees: Option<(u64, u64)>) -> Result<Self> {
+        let (rate0, rate1) = pair.get_rates(rate_model0, rate_model1)?;
+        let (utilization0_nad, utilization1_nad) = pair.utilizations_nad();
+
+        Ok(Self {
+            version: VIEW_RETURN_DATA_VERSION,
+            last_update: pair.last_update,
+            spot_price0_nad: pair.spot_price0_nad(),
+            spot_price1_nad: pair.spot_price1_nad(),
+            ema_price0_nad: pair.ema_price0_nad(),
+            ema_price1_nad: pair.ema_price1_nad(),
+            directional_ema_price0_nad: pair.directional_ema_price0_nad(),
+            directional_ema_price1_nad: pair.directional_ema_price1_nad(),
+            rate0,
+            rate1,
+            rate_model0: pair.rate_model0,
+            rate_model1: pair.rate_model1,
+            utilization0_nad,
+            utilization1_nad,
+            reserve0: pair.reserve0,
+            reserve1: pair.reserve1,
+            cash_reserve0: pair.cash_reserve0,
+            cash_reserve1: pair.cash_reserve1,
+            total_debt0: pair.total_debt0,
+            total_debt1: pair.total_debt1,
+            total_debt0_shares: pair.total_debt0_shares,
+            total_debt1_shares: pair.total_debt1_shares,
+            total_collateral0: pair.total_collateral0,
+            total_collateral1: pair.total_collateral1,
+            total_supply: pair.total_supply,
+            k: pair.k(),
+            claimable_protocol_fee0: claimable_protocol_fees.map(|(fee0, _)| fee0),
+            claimable_protocol_fee1: claimable_protocol_fees.map(|(_, fee1)| fee1),
+        })
+    }
+}
+
+/// Claimable protocol fees (vault balance beyond cash and insurance reserves, as in `claim_protocol_fees`),
+/// read from the optional `[reserve0_vault, reserve1_vault]` remaining accounts.
+fn claimable_protocol_fees(pair: &Pair, pair_key: &Pubkey, remaining_accounts: &[AccountInfo]) -> Result<Option<(u64, u64)>> {
+    let (vault0, vault1) = match remaining_accounts {
+        [vault0, vault1, ..] => (vault0, vault1),
+        _ => return Ok(None),
+    };
+
+    let mut vault_amounts = [0u64; 2];
+    for (index, (vault, mint, bump)) in [
+        (vault0, &pair.token0, pair.vault_bumps.reserve0),
+        (vault1, &pair.token1, pair.vault_bumps.reserve1),
+    ].into_iter().enumerate() {
+        let expected_vault = Pubkey::create_program_address(
+            &[RESERVE_VAULT_SEED_PREFIX, pair_key.as_ref(), mint.as_ref(), &[bump]],
+            &crate::ID,
+        ).map_err(|_| ErrorCode::InvalidVault)?;
+        require_keys_eq!(vault.key(), expected_vault, ErrorCode::InvalidVault);
+
+        let data = vault.try_borrow_data()?;
+        vault_amounts[index] = TokenAccount::try_deserialize(&mut &data[..])?.amount;
+    }
+
+    Ok(Some((
+        vault_amounts[0].saturating_sub(pair.cash_reserve0).saturating_sub(pair.insurance_reserve0),
+        vault_amounts[1].saturating_sub(pair.cash_reserve1).saturating_sub(pair.insurance_reserve1),
+    )))
+}
+
+/// Logs a struct-valued view result and publishes it as return data.
+fn emit_view_struct<T: AnchorSerialize + fmt::Debug>(getter: impl fmt::Display, value: T) -> Result<()> {
+    msg!("{}: {:?}", getter, value);
+
+    let mut data = Vec::new();
+    value.serialize(&mut data)?;
+    set_return_data(&data);
+    Ok(())
+}
+
+/// Health of one debt direction of a position (borrowing `debt` token against the other token).
+/// For a cross-margin position, `collateral` and `debt` are the net exposure of each token.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct PositionSideHealth {
+    pub collateral: u64,
+    /// Debt including accrued interest
+    pub debt: u64,
+    /// Max debt allowed by `borrow` / `remove_collateral` at the current collateral
+    pub borrow_limit: u64,
+    /// Debt at which the position becomes liquidatable
+    pub liquidation_limit: u64,
+    pub liquidation_cf_bps: u16,
+    /// liquidation_limit / debt in BPS; liquidatable at or below 10_000, u64::MAX without debt
+    pub health_factor_bps: u64,
+    /// NAD-scaled collateral price (in debt token) at which the position becomes liquidatable
+    pub liquidation_price_nad: u64,
+    pub max_withdrawable_collateral: u64,
+    /// Further debt allowed by the borrow limit, capped by the pair's cash reserve
+    pub max_additional_borrow: u64,
+}
+
+impl PositionSideHealth {
+    pub fn new(pair: &Pair, user_position: &UserPosition, debt_token: &Pubkey) -> Result<Self> {
+        let collateral_token = pair.get_collateral_token(debt_token);
+        let is_collateral_token0 = collateral_token == pair.token0;
+        let (mut collateral, mut debt, collateral_reserve, debt_reserve, collateral_ema_nad, debt_cash_reserve) = match is_collateral_token0 {
+            true => (
+                user_position.collateral0,
+                user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?,
+                pair.reserve0,
+                pair.reserve1,
+                pair.ema_price0_nad(),
+                pair.cash_reserve1,
+            ),
+            false => (
+                user_position.collateral1,
+                user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?,
+                pair.reserve1,
+                pair.reserve0,
+                pair.ema_price1_nad(),
+                pair.cash_reserve0,
+            ),
+        };
+
+        // Cross margin: the side is reported on the net exposure, as checked by `check_net_borrow_limit`
+        // and `liquidate`. Only the side whose debt token is in deficit carries debt, backed by the
+        // surplus of the other token; a surplus of the debt token itself adds to the borrow headroom.
+        let mut debt_token_surplus = 0;
+        if user_position.cross_margin {
+            let exposure = user_position.net_exposure(pair)?;
+            let (net_collateral_token, net_debt_token) = match is_collateral_token0 {
+                true => (exposure.net0, exposure.net1),
+                false => (exposure.net1, exposure.net0),
+            };
+            (collateral, debt) = match exposure.deficit(pair.ema_price0_nad()) {
+                Some((deficit_collateral_token0, net_collateral, net_debt)) if deficit_collateral_token0 == is_collateral_token0 => {
+                    (net_collateral, net_debt)
+                }
+                _ => (u64::try_from(net_collateral_token.max(0)).unwrap_or(u64::MAX), 0),
+            };
+            debt_token_surplus = u64::try_from(net_debt_token.max(0)).unwrap_or(u64::MAX);
+        }
+
+        let borrow_limit = pair.get_max_debt_and_cf_bps_for_collateral(pair, &collateral_token, collateral)?.0;
+
+        // Same valuation as `liquidate`: collateral sold at the EMA price with impact, times the locked CF
+        let liquidation_cf_bps = user_position.get_liquidation_cf_bps(pair, debt_token)?;
+        let liquidation_limit = match collateral == 0 || liquidation_cf_bps == 0 {
+            true => 0,
+            false => {
+                let (collateral_ema_reserve, debt_ema_reserve) = construct_virtual_reserves_at_pessimistic_price(
+                    collateral_reserve, debt_reserve, collateral_ema_nad, collateral_ema_nad
+                )?;
+                let collateral_value = CPCurve::calculate_amount_out(collateral_ema_reserve, debt_ema_reserve, collateral)?;
+                ((collateral_value as u128) * liquidation_cf_bps as u128 / BPS_DENOMINATOR as u128) as u64
+            }
+        };
+
+        let health_factor_bps = match debt {
+            0 => u64::MAX,
+            _ => ((liquidation_limit as u128) * BPS_DENOMINATOR as u128 / debt as u128).min(u64::MAX as u128) as u64,
+        };
+
+        // Largest withdrawal that still passes the `remove_collateral` borrow limit check
+        let max_withdrawable_collateral = match debt {
+            0 => collateral,
+            _ => {
+                let (mut low, mut high) = (0u64, collateral);
+                while low < high {
+                    let mid = low + (high - low).div_ceil(2);
+                    let remaining = collateral - mid;
+                    let limit = pair.get_max_debt_and_cf_bps_for_collateral(pair, &collateral_token, remaining)?.0;
+                    match limit >= debt {
+                        true => low = mid,
+                        false => high = mid - 1,
+                    }
+                }
+                low
+            }
+        };
+
+        Ok(Self {
+            collateral,
+            debt,
+            borrow_limit,
+            liquidation_limit,
+            liquidation_cf_bps,
+            health_factor_bps,
+            liquidation_price_nad: user_position.get_liquidation_price(pair, debt_token)?,
+            max_withdrawable_collateral,
+            max_additional_borrow: borrow_limit.saturating_add(debt_token_surplus).saturating_sub(debt).min(debt_cash_reserve),
+        })
+    }
+}
+
+/// Return data of `UserPositionViewKind::HealthReport`, Borsh-encoded via `set_return_data`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct HealthReport {
+    pub version: u8,
+    /// Borrowing token0 against token1 collateral
+    pub debt0: PositionSideHealth,
+    /// Borrowing token1 against token0 collateral
+    pub debt1: PositionSideHealth,
+}
+
+impl HealthReport {
+    pub fn new(pair: &Pair, user_position: &UserPosition) -> Result<Self> {
+        Ok(Self {
+            version: VIEW_RETURN_DATA_VERSION,
+            debt0: PositionSideHealth::new(pair, user_position, &pair.token0)?,
+            debt1: PositionSideHealth::new(pair, user_position, &pair.token1)?,
+        })
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct EmitValueArgs {
     pub amount: Option<u64>,
@@ -74,6 +351,13 @@ pub enum PairViewKind {
     /// Args: amount = collateral_amount, token_mint = collateral_token, debt_amount = debt to borrow.
     /// Returns NAD-scaled liquidation price of the collateral in debt token units.
     SimulateLiquidationPrice,
+    /// Every pair-level metric in one simulation, returned as a [`PairSnapshot`].
+    /// Optional remaining accounts: [reserve0_vault, reserve1_vault] to include claimable protocol fees.
+    FullSnapshot,
+    /// Caps on total debt0, total debt1 and utilization (bps) set by `set_pair_caps`, `None` if disabled.
+    DebtCaps,
+    /// Caps on total collateral0 and total collateral1 set by `set_pair_caps`, `None` if disabled.
+    CollateralCaps,
 }
 impl fmt::Display for PairViewKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -89,6 +373,9 @@ impl fmt::Display for PairViewKind {
             PairViewKind::CashReserves => write!(f, "CashReserves"),
             PairViewKind::SwapQuote => write!(f, "SwapQuote"),
             PairViewKind::SimulateLiquidationPrice => write!(f, "SimulateLiquidationPrice"),
+            PairViewKind::FullSnapshot => write!(f, "FullSnapshot"),
+            PairViewKind::DebtCaps => write!(f, "DebtCaps"),
+            PairViewKind::CollateralCaps => write!(f, "CollateralCaps"),
         }
     }
 }
@@ -104,6 +391,8 @@ pub enum UserPositionViewKind {
     UserIsLiquidatable,
     UserCollateralValueWithImpact,
     UserLiquidationBorrowLimit,
+    /// Every health metric for both debt directions in one simulation, returned as a [`HealthReport`].
+    HealthReport,
 }
 impl fmt::Display for UserPositionViewKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -117,6 +406,7 @@ impl fmt::Display for UserPositionViewKind {
             UserPositionViewKind::UserIsLiquidatable => write!(f, "UserIsLiquidatable"),
             UserPositionViewKind::UserCollateralValueWithImpact => write!(f, "UserCollateralValueWithImpact"),
             UserPositionViewKind::UserLiquidationBorrowLimit => write!(f, "UserLiquidationBorrowLimit"),
+            UserPositionViewKind::HealthReport => write!(f, "HealthReport"),
         }
     }
 }
@@ -127,9 +417,13 @@ impl fmt::Display for UserPositionViewKind {
 pub struct ViewPairData<'info> {
     pub pair: Account<'info, Pair>,
     #[account(
-        address = pair.rate_model @ ErrorCode::InvalidRateModel
+        address = pair.rate_model0 @ ErrorCode::InvalidRateModel
+    )]
+    pub rate_model0: Account<'info, RateModel>,
+    #[account(
+        address = pair.rate_model1 @ ErrorCode::InvalidRateModel
     )]
-    pub rate_model: Account<'info, RateModel>,
+    pub rate_model1: Account<'info, RateModel>,
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
         bump = futarchy_authority.bump
@@ -145,9 +439,13 @@ pub struct ViewUserPositionData<'info> {
     )]
     pub user_position: Account<'info, UserPosition>,
     #[account(
-        address = pair.rate_model @ ErrorCode::InvalidRateModel
+        address = pair.rate_model0 @ ErrorCode::InvalidRateModel
+    )]
+    pub rate_model0: Account<'info, RateModel>,
+    #[account(
+        address = pair.rate_model1 @ ErrorCode::InvalidRateModel
     )]
-    pub rate_model: Account<'info, RateModel>,
+    pub rate_model1: Account<'info, RateModel>,
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
         bump = futarchy_authority.bump
@@ -162,7 +460,7 @@ impl ViewPairData<'_> {
         let mut pair = ctx.accounts.pair.clone().into_inner();
         
         // update pair to get updated rates, interest, debt, etc.
-        pair.update(&ctx.accounts.rate_model, &ctx.accounts.futarchy_authority, pair_key, None)?;
+        pair.update(&ctx.accounts.rate_model0, &ctx.accounts.rate_model1, &ctx.accounts.futarchy_authority, pair_key, None)?;
 
         let empty = || OptionalUint::OptionalU64(None);
         let value: (OptionalUint, OptionalUint, OptionalUint) = match getter {
@@ -172,7 +470,7 @@ impl ViewPairData<'_> {
             PairViewKind::SpotPrice1Nad => (OptionalUint::from_u64(pair.spot_price1_nad()), empty(), empty()),
             PairViewKind::K => (OptionalUint::from_u128(pair.k()), empty(), empty()),
             PairViewKind::GetRates => {
-                let (rate0, rate1) = pair.get_rates(&ctx.accounts.rate_model).unwrap();
+                let (rate0, rate1) = pair.get_rates(&ctx.accounts.rate_model0, &ctx.accounts.rate_model1).unwrap();
                 (OptionalUint::from_u64(rate0), OptionalUint::from_u64(rate1), empty())
             },
             PairViewKind::GetBorrowLimitAndCfBpsForCollateral => {
@@ -237,8 +535,7 @@ impl ViewPairData<'_> {
                 if collateral_amount == 0 || cf_bps == 0 {
                     // Immediately unsafe
                     let value = (OptionalUint::from_u64(u64::MAX), empty(), empty());
-                    msg!("{}: {:?}", getter, value);
-                    return Ok(());
+                    return emit_view_value(getter, value);
                 }
 
                 // Determine decimal adjustments
@@ -277,11 +574,23 @@ impl ViewPairData<'_> {
 
                 (OptionalUint::from_u64(p_star_nad), OptionalUint::from_u16(liquidation_cf_bps), empty())
             },
+            PairViewKind::FullSnapshot => {
+                let claimable_protocol_fees = claimable_protocol_fees(&pair, &pair_key, ctx.remaining_accounts)?;
+                return emit_view_struct(getter, PairSnapshot::new(&pair, &ctx.accounts.rate_model0, &ctx.accounts.rate_model1, claimable_protocol_fees)?);
+            },
+            PairViewKind::DebtCaps => (
+                OptionalUint::from_optional_u64(pair.caps.max_debt(true)),
+                OptionalUint::from_optional_u64(pair.caps.max_debt(false)),
+                OptionalUint::from_optional_u16((pair.caps.max_utilization_bps > 0).then_some(pair.caps.max_utilization_bps)),
+            ),
+            PairViewKind::CollateralCaps => (
+                OptionalUint::from_optional_u64(pair.caps.max_collateral(true)),
+                OptionalUint::from_optional_u64(pair.caps.max_collateral(false)),
+                empty(),
+            ),
         };
 
-        msg!("{}: {:?}", getter, value);
-
-        Ok(())
+        emit_view_value(getter, value)
     }
 }
 
@@ -293,7 +602,7 @@ impl ViewUserPositionData<'_> {
         let user_position = &ctx.accounts.user_position;
 
         // update pair to get updated rates, interest, debt, etc.
-        pair.update(&ctx.accounts.rate_model, &ctx.accounts.futarchy_authority, pair_key, None)?;
+        pair.update(&ctx.accounts.rate_model0, &ctx.accounts.rate_model1, &ctx.accounts.futarchy_authority, pair_key, None)?;
 
         let empty = || OptionalUint::OptionalU64(None);
         let value: (OptionalUint, OptionalUint, OptionalUint) = match getter {
@@ -476,10 +785,109 @@ impl ViewUserPositionData<'_> {
                 };
                 (OptionalUint::from_u64(limit0), OptionalUint::from_u64(limit1), empty())
             },
+            UserPositionViewKind::HealthReport => {
+                return emit_view_struct(getter, HealthReport::new(&pair, user_position)?);
+            },
         };
 
-        msg!("{}: {:?}", getter, value);
+        emit_view_value(getter, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::lending::borrow::apply_borrow,
+        state::{DelegatePermission, RiskParams, VaultBumps},
+    };
+
+    const RESERVE: u64 = 1_000_000_000;
+    const COLLATERAL: u64 = 100_000_000;
+
+    fn test_pair() -> Pair {
+        let mut pair = Pair::initialize(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            6,
+            6,
+            Pubkey::new_unique(),
+            30,
+            60_000,
+            Some(8_000),
+            0,
+            [0; 32],
+            VERSION,
+            1,
+            VaultBumps::default(),
+            0,
+            RiskParams::default(),
+        );
+        pair.reserve0 = RESERVE;
+        pair.reserve1 = RESERVE;
+        pair.cash_reserve0 = RESERVE;
+        pair.cash_reserve1 = RESERVE;
+        pair.last_price0_ema.symmetric = pair.spot_price0_nad();
+        pair.last_price0_ema.directional = pair.spot_price0_nad();
+        pair.last_price1_ema.symmetric = pair.spot_price1_nad();
+        pair.last_price1_ema.directional = pair.spot_price1_nad();
+        pair
+    }
+
+    fn test_position(collateral0: u64, collateral1: u64) -> UserPosition {
+        UserPosition {
+            owner: Pubkey::new_unique(),
+            pair: Pubkey::new_unique(),
+            collateral0_liquidation_cf_bps: 0,
+            collateral1_liquidation_cf_bps: 0,
+            collateral0,
+            collateral1,
+            debt0_shares: 0,
+            debt1_shares: 0,
+            bump: 1,
+            cross_margin: true,
+            position_index: 0,
+            delegate: Pubkey::default(),
+            delegate_permission: DelegatePermission::None,
+            delegate_expiry_slot: 0,
+            open_orders: 0,
+        }
+    }
+
+    #[test]
+    fn cross_margin_health_nets_same_token_collateral() {
+        let mut pair = test_pair();
+        let mut user_position = test_position(COLLATERAL, COLLATERAL / 2);
+        pair.total_collateral0 = COLLATERAL;
+        pair.total_collateral1 = COLLATERAL / 2;
+        let token1 = pair.token1;
+        apply_borrow(&mut pair, &mut user_position, &token1, COLLATERAL).unwrap();
+
+        let report = HealthReport::new(&pair, &user_position).unwrap();
+        assert_eq!(report.debt1.collateral, COLLATERAL);
+        assert_eq!(report.debt1.debt, COLLATERAL / 2);
+        assert_eq!(report.debt0.debt, 0);
+        assert_eq!(report.debt0.health_factor_bps, u64::MAX);
+
+        // The same balances, isolated, carry the full debt against the token0 collateral
+        let isolated = PositionSideHealth::new(&pair, &UserPosition { cross_margin: false, ..user_position }, &token1).unwrap();
+        assert_eq!(isolated.debt, COLLATERAL);
+        assert!(report.debt1.health_factor_bps > isolated.health_factor_bps);
+        assert!(report.debt1.max_withdrawable_collateral > isolated.max_withdrawable_collateral);
+        assert!(report.debt1.max_additional_borrow > isolated.max_additional_borrow);
+    }
 
-        Ok(())
+    #[test]
+    fn cross_margin_borrow_headroom_includes_debt_token_surplus() {
+        let pair = test_pair();
+        let user_position = test_position(COLLATERAL, COLLATERAL);
+        let token1 = pair.token1;
+
+        let cross = PositionSideHealth::new(&pair, &user_position, &token1).unwrap();
+        let isolated = PositionSideHealth::new(&pair, &UserPosition { cross_margin: false, ..user_position }, &token1).unwrap();
+        assert_eq!(cross.borrow_limit, isolated.borrow_limit);
+        assert_eq!(isolated.max_additional_borrow, isolated.borrow_limit);
+        assert_eq!(cross.max_additional_borrow, cross.borrow_limit + COLLATERAL);
     }
 }
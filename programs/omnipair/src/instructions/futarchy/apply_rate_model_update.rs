@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use crate::state::futarchy_authority::FutarchyAuthority;
+use crate::state::pair::Pair;
+use crate::state::rate_model::RateModel;
+use crate::constants::FUTARCHY_AUTHORITY_SEED_PREFIX;
+use crate::errors::ErrorCode;
+
+/// Remaining accounts consumed by each affected pair, in order:
+/// `[pair (mut), rate_model0, rate_model1]`
+pub const APPLY_RATE_MODEL_UPDATE_ACCOUNTS_PER_PAIR: usize = 3;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApplyRateModelUpdate<'info> {
+    #[account(
+        address = futarchy_authority.authority @ ErrorCode::InvalidFutarchyAuthority
+    )]
+    pub authority_signer: Signer<'info>,
+
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+
+    #[account(mut)]
+    pub rate_model: Account<'info, RateModel>,
+}
+
+impl<'info> ApplyRateModelUpdate<'info> {
+    /// Applies the update queued by `update_rate_model` once its timelock has passed.
+    ///
+    /// Every pair using the rate model (on either side) must be passed in remaining accounts, once:
+    /// their interest is accrued up to the current slot with the old parameters first. The pair sides
+    /// passed must add up to `RateModel::pair_references`, so no pair can be left out.
+    pub fn handle_apply_rate_model_update(ctx: Context<'_, '_, 'info, 'info, Self>) -> Result<()> {
+        let rate_model_key = ctx.accounts.rate_model.key();
+        let remaining_accounts = ctx.remaining_accounts;
+        let pair_count = remaining_accounts.len() / APPLY_RATE_MODEL_UPDATE_ACCOUNTS_PER_PAIR;
+        require!(
+            pair_count * APPLY_RATE_MODEL_UPDATE_ACCOUNTS_PER_PAIR == remaining_accounts.len(),
+            ErrorCode::InvalidAffectedPairs
+        );
+
+        let mut pair_keys = Vec::with_capacity(pair_count);
+        let mut pair_references = 0u64;
+        for pair_accounts in remaining_accounts.chunks(APPLY_RATE_MODEL_UPDATE_ACCOUNTS_PER_PAIR) {
+            let mut pair = Box::new(Account::<Pair>::try_from(&pair_accounts[0])?);
+            let pair_key = pair.key();
+            require!(!pair_keys.contains(&pair_key), ErrorCode::InvalidAffectedPairs);
+            pair_keys.push(pair_key);
+            let sides = [pair.rate_model0, pair.rate_model1].iter().filter(|key| **key == rate_model_key).count();
+            require!(sides > 0, ErrorCode::InvalidAffectedPairs);
+            pair_references += sides as u64;
+            // Deserialized separately from `ctx.accounts.rate_model`, so still the old parameters
+            let rate_model0 = Account::<RateModel>::try_from(&pair_accounts[1])?;
+            require_keys_eq!(rate_model0.key(), pair.rate_model0, ErrorCode::InvalidRateModel);
+            let rate_model1 = Account::<RateModel>::try_from(&pair_accounts[2])?;
+            require_keys_eq!(rate_model1.key(), pair.rate_model1, ErrorCode::InvalidRateModel);
+
+            pair.update(
+                &rate_model0,
+                &rate_model1,
+                &ctx.accounts.futarchy_authority,
+                pair_key,
+                Some(ctx.accounts.event_authority.to_account_info()),
+            )?;
+            pair.exit(&crate::ID)?;
+        }
+
+        require_eq!(pair_references, ctx.accounts.rate_model.pair_references, ErrorCode::InvalidAffectedPairs);
+
+        let applied = ctx.accounts.rate_model.apply_pending_update(Clock::get()?.slot)?;
+
+        msg!(
+            "Rate model {} update applied ({} pairs accrued), next update delay {} slots",
+            rate_model_key,
+            pair_count,
+            applied.update_delay_slots
+        );
+
+        Ok(())
+    }
+}
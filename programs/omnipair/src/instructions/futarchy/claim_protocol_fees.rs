@@ -37,9 +37,15 @@ pub struct ClaimProtocolFees<'info> {
 
     #[account(
         mut,
-        address = pair.rate_model,
+        address = pair.rate_model0,
     )]
-    pub rate_model: Box<Account<'info, RateModel>>,
+    pub rate_model0: Box<Account<'info, RateModel>>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Box<Account<'info, RateModel>>,
 
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
@@ -150,7 +156,8 @@ impl<'info> ClaimProtocolFees<'info> {
     pub fn update(&mut self) -> Result<()> {
         let pair_key = self.pair.to_account_info().key();
         self.pair.update(
-            &self.rate_model,
+            &self.rate_model0,
+            &self.rate_model1,
             &self.futarchy_authority,
             pair_key,
             Some(self.event_authority.to_account_info()),
@@ -174,9 +181,13 @@ impl<'info> ClaimProtocolFees<'info> {
             ErrorCode::InvalidDistribution
         );
 
-        // Calculate claimable amounts (fees accumulated in vaults beyond cash reserves)
-        let claimable_amount0 = reserve0_vault.amount.saturating_sub(pair.cash_reserve0);
-        let claimable_amount1 = reserve1_vault.amount.saturating_sub(pair.cash_reserve1);
+        // Calculate claimable amounts (fees accumulated in vaults beyond cash and insurance reserves)
+        let claimable_amount0 = reserve0_vault.amount
+            .saturating_sub(pair.cash_reserve0)
+            .saturating_sub(pair.insurance_reserve0);
+        let claimable_amount1 = reserve1_vault.amount
+            .saturating_sub(pair.cash_reserve1)
+            .saturating_sub(pair.insurance_reserve1);
 
         // Calculate amounts for each recipient (token0)
         let buybacks_amount0 = (claimable_amount0 as u128)
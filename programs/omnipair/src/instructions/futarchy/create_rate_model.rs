@@ -13,6 +13,17 @@ pub struct CreateRateModelArgs {
     pub min_rate_bps: u64,
     pub max_rate_bps: u64,
     pub initial_rate_bps: u64,
+    /// Creates a kinked model instead of an exponential one.
+    /// Only `max_rate_bps` of the fields above applies to it, the others are ignored.
+    pub kinked: Option<KinkedRateArgs>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct KinkedRateArgs {
+    pub kink_util_bps: u64,
+    pub base_rate_bps: u64,
+    pub slope1_bps: u64,
+    pub slope2_bps: u64,
 }
 
 #[derive(Accounts)]
@@ -39,44 +50,95 @@ pub struct CreateRateModel<'info> {
     pub system_program: Program<'info, System>,
 }
 
-impl<'info> CreateRateModel<'info> {
-    pub fn validate(args: &CreateRateModelArgs) -> Result<()> {
+impl CreateRateModelArgs {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(kinked) = &self.kinked {
+            require!(
+                RateModel::validate_kinked_params(
+                    kinked.kink_util_bps,
+                    kinked.base_rate_bps,
+                    kinked.slope1_bps,
+                    kinked.slope2_bps,
+                    self.max_rate_bps
+                ),
+                ErrorCode::InvalidRateParams
+            );
+            return Ok(());
+        }
+
         require!(
-            RateModel::validate_util_bounds(args.target_util_start_bps, args.target_util_end_bps),
+            RateModel::validate_util_bounds(self.target_util_start_bps, self.target_util_end_bps),
             ErrorCode::InvalidUtilBounds
         );
         require!(
             RateModel::validate_rate_params(
-                args.half_life_ms,
-                args.min_rate_bps,
-                args.max_rate_bps,
-                args.initial_rate_bps
+                self.half_life_ms,
+                self.min_rate_bps,
+                self.max_rate_bps,
+                self.initial_rate_bps
             ),
             ErrorCode::InvalidRateParams
         );
         Ok(())
     }
 
-    pub fn handle_create_rate_model(ctx: Context<Self>, args: CreateRateModelArgs) -> Result<()> {
-        ctx.accounts.rate_model.set_inner(RateModel::new(
-            args.target_util_start_bps,
-            args.target_util_end_bps,
-            args.half_life_ms,
-            args.min_rate_bps,
-            args.max_rate_bps,
-            args.initial_rate_bps,
-        ));
+    /// The rate model described by these args, with the default update delay.
+    pub fn rate_model(&self) -> RateModel {
+        match &self.kinked {
+            Some(kinked) => RateModel::new_kinked(
+                kinked.kink_util_bps,
+                kinked.base_rate_bps,
+                kinked.slope1_bps,
+                kinked.slope2_bps,
+                self.max_rate_bps,
+            ),
+            None => RateModel::new(
+                self.target_util_start_bps,
+                self.target_util_end_bps,
+                self.half_life_ms,
+                self.min_rate_bps,
+                self.max_rate_bps,
+                self.initial_rate_bps,
+            ),
+        }
+    }
 
-        msg!(
-            "Rate model created: {} (util {}-{} bps, half_life {} ms, rates {}-{} bps, initial {} bps)",
-            ctx.accounts.rate_model.key(),
-            args.target_util_start_bps,
-            args.target_util_end_bps,
-            args.half_life_ms,
-            args.min_rate_bps,
-            args.max_rate_bps,
-            args.initial_rate_bps
-        );
+    /// Logs the parameters of the rate model `rate_model`, prefixed by `action`.
+    pub fn log(&self, action: &str, rate_model: Pubkey) {
+        match &self.kinked {
+            Some(kinked) => msg!(
+                "Kinked rate model {}: {} (kink {} bps, base {} bps, slopes {}/{} bps, max {} bps)",
+                action,
+                rate_model,
+                kinked.kink_util_bps,
+                kinked.base_rate_bps,
+                kinked.slope1_bps,
+                kinked.slope2_bps,
+                self.max_rate_bps
+            ),
+            None => msg!(
+                "Rate model {}: {} (util {}-{} bps, half_life {} ms, rates {}-{} bps, initial {} bps)",
+                action,
+                rate_model,
+                self.target_util_start_bps,
+                self.target_util_end_bps,
+                self.half_life_ms,
+                self.min_rate_bps,
+                self.max_rate_bps,
+                self.initial_rate_bps
+            ),
+        }
+    }
+}
+
+impl<'info> CreateRateModel<'info> {
+    pub fn validate(args: &CreateRateModelArgs) -> Result<()> {
+        args.validate()
+    }
+
+    pub fn handle_create_rate_model(ctx: Context<Self>, args: CreateRateModelArgs) -> Result<()> {
+        ctx.accounts.rate_model.set_inner(args.rate_model());
+        args.log("created", ctx.accounts.rate_model.key());
 
         Ok(())
     }
@@ -6,7 +6,13 @@ pub mod claim_protocol_fees;
 pub mod set_global_reduce_only;
 pub mod set_pair_reduce_only;
 pub mod set_pair_rate_model;
+pub mod set_pair_insurance_share;
+pub mod set_global_risk_params;
+pub mod set_pair_risk_params;
+pub mod set_pair_caps;
 pub mod create_rate_model;
+pub mod update_rate_model;
+pub mod apply_rate_model_update;
 
 pub use init_futarchy_authority::*;
 pub use update_futarchy_authority::*;
@@ -16,4 +22,10 @@ pub use claim_protocol_fees::*;
 pub use set_global_reduce_only::*;
 pub use set_pair_reduce_only::*;
 pub use set_pair_rate_model::*;
-pub use create_rate_model::*;
\ No newline at end of file
+pub use set_pair_insurance_share::*;
+pub use set_global_risk_params::*;
+pub use set_pair_risk_params::*;
+pub use set_pair_caps::*;
+pub use create_rate_model::*;
+pub use update_rate_model::*;
+pub use apply_rate_model_update::*;
\ No newline at end of file
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::state::futarchy_authority::FutarchyAuthority;
+use crate::state::risk_params::RiskParams;
+use crate::constants::FUTARCHY_AUTHORITY_SEED_PREFIX;
+use crate::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SetGlobalRiskParams<'info> {
+    #[account(
+        mut,
+        address = futarchy_authority.authority @ ErrorCode::InvalidFutarchyAuthority
+    )]
+    pub authority_signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+}
+
+impl<'info> SetGlobalRiskParams<'info> {
+    pub fn validate(risk_params: &RiskParams) -> Result<()> {
+        risk_params.validate()
+    }
+
+    /// Sets the default risk parameters. Pairs without an override pick them up on their next update.
+    pub fn handle_set_global_risk_params(ctx: Context<Self>, risk_params: RiskParams) -> Result<()> {
+        ctx.accounts.futarchy_authority.risk_params = risk_params;
+
+        msg!("Global risk params set to {:?}", risk_params);
+
+        Ok(())
+    }
+}
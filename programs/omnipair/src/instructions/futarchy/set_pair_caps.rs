@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::state::futarchy_authority::FutarchyAuthority;
+use crate::state::pair::Pair;
+use crate::state::pair_caps::PairCaps;
+use crate::constants::{FUTARCHY_AUTHORITY_SEED_PREFIX, PAIR_SEED_PREFIX};
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetPairCapsArgs {
+    /// Replaces all of the pair's caps, 0 disables a cap
+    pub caps: PairCaps,
+}
+
+#[derive(Accounts)]
+pub struct SetPairCaps<'info> {
+    #[account(
+        mut,
+        address = futarchy_authority.authority @ ErrorCode::InvalidFutarchyAuthority
+    )]
+    pub authority_signer: Signer<'info>,
+
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+
+    #[account(
+        mut,
+        seeds = [
+            PAIR_SEED_PREFIX,
+            pair.token0.as_ref(),
+            pair.token1.as_ref(),
+            pair.params_hash.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Account<'info, Pair>,
+}
+
+impl<'info> SetPairCaps<'info> {
+    pub fn validate(args: &SetPairCapsArgs) -> Result<()> {
+        args.caps.validate()
+    }
+
+    pub fn handle_set_pair_caps(ctx: Context<Self>, args: SetPairCapsArgs) -> Result<()> {
+        let pair = &mut ctx.accounts.pair;
+
+        pair.caps = args.caps;
+
+        msg!(
+            "Pair caps set to {:?} for pair with tokens ({}, {})",
+            pair.caps,
+            pair.token0,
+            pair.token1
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use crate::state::futarchy_authority::FutarchyAuthority;
+use crate::state::pair::Pair;
+use crate::state::rate_model::RateModel;
+use crate::constants::{BPS_DENOMINATOR, FUTARCHY_AUTHORITY_SEED_PREFIX, PAIR_SEED_PREFIX};
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetPairInsuranceShareArgs {
+    /// Share (BPS) of the LP part of liquidation penalties paid into the insurance reserves
+    pub penalty_share_bps: u16,
+    /// Share (BPS) of LP interest paid into the insurance reserves
+    pub interest_share_bps: u16,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPairInsuranceShare<'info> {
+    #[account(
+        mut,
+        address = futarchy_authority.authority @ ErrorCode::InvalidFutarchyAuthority
+    )]
+    pub authority_signer: Signer<'info>,
+
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+
+    #[account(
+        mut,
+        seeds = [
+            PAIR_SEED_PREFIX,
+            pair.token0.as_ref(),
+            pair.token1.as_ref(),
+            pair.params_hash.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Account<'info, Pair>,
+
+    #[account(address = pair.rate_model0 @ ErrorCode::InvalidRateModel)]
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(address = pair.rate_model1 @ ErrorCode::InvalidRateModel)]
+    pub rate_model1: Account<'info, RateModel>,
+}
+
+impl<'info> SetPairInsuranceShare<'info> {
+    pub fn validate(args: &SetPairInsuranceShareArgs) -> Result<()> {
+        require_gte!(BPS_DENOMINATOR, args.penalty_share_bps, ErrorCode::InvalidInsuranceShareBps);
+        require_gte!(BPS_DENOMINATOR, args.interest_share_bps, ErrorCode::InvalidInsuranceShareBps);
+        Ok(())
+    }
+
+    pub fn handle_set_pair_insurance_share(ctx: Context<Self>, args: SetPairInsuranceShareArgs) -> Result<()> {
+        let pair = &mut ctx.accounts.pair;
+        let pair_key = pair.key();
+
+        // Interest accrued so far is split with the old share
+        pair.update(
+            &ctx.accounts.rate_model0,
+            &ctx.accounts.rate_model1,
+            &ctx.accounts.futarchy_authority,
+            pair_key,
+            Some(ctx.accounts.event_authority.to_account_info()),
+        )?;
+
+        pair.insurance_penalty_share_bps = args.penalty_share_bps;
+        pair.insurance_interest_share_bps = args.interest_share_bps;
+
+        msg!(
+            "Pair insurance share set to {} bps of penalties, {} bps of interest for pair with tokens ({}, {})",
+            args.penalty_share_bps,
+            args.interest_share_bps,
+            pair.token0,
+            pair.token1
+        );
+
+        Ok(())
+    }
+}
@@ -31,28 +31,87 @@ pub struct SetPairRateModel<'info> {
     )]
     pub pair: Account<'info, Pair>,
 
-    /// The new rate model account to assign to this pair.
-    pub new_rate_model: Account<'info, RateModel>,
+    /// The current rate model for token0 borrowing
+    #[account(mut, address = pair.rate_model0 @ ErrorCode::InvalidRateModel)]
+    pub rate_model0: Box<Account<'info, RateModel>>,
+
+    /// The current rate model for token1 borrowing
+    #[account(mut, address = pair.rate_model1 @ ErrorCode::InvalidRateModel)]
+    pub rate_model1: Box<Account<'info, RateModel>>,
+
+    /// The new rate model for token0 borrowing, if it changes.
+    #[account(mut)]
+    pub new_rate_model0: Option<Box<Account<'info, RateModel>>>,
+
+    /// The new rate model for token1 borrowing, if it changes.
+    #[account(mut)]
+    pub new_rate_model1: Option<Box<Account<'info, RateModel>>>,
 
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> SetPairRateModel<'info> {
-    pub fn handle_set_pair_rate_model(ctx: Context<Self>) -> Result<()> {
-        let pair = &mut ctx.accounts.pair;
-        let old_rate_model = pair.rate_model;
-        let new_rate_model = ctx.accounts.new_rate_model.key();
-
-        pair.rate_model = new_rate_model;
-
-        msg!(
-            "Pair rate model updated from {} to {} for pair with tokens ({}, {})",
-            old_rate_model,
-            new_rate_model,
-            pair.token0,
-            pair.token1
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.new_rate_model0.is_some() || self.new_rate_model1.is_some(),
+            ErrorCode::MissingRateModel
         );
+        Ok(())
+    }
+
+    pub fn handle_set_pair_rate_model(ctx: Context<Self>) -> Result<()> {
+        let accounts = &mut *ctx.accounts;
+        let pair = &mut accounts.pair;
+        let changes = [
+            (pair.rate_model0, accounts.new_rate_model0.as_ref().map(|rate_model| rate_model.key())),
+            (pair.rate_model1, accounts.new_rate_model1.as_ref().map(|rate_model| rate_model.key())),
+        ];
+
+        // Pair sides moved off a rate model no longer have to be accrued by its updates
+        let mut rate_models = vec![&mut *accounts.rate_model0, &mut *accounts.rate_model1];
+        rate_models.extend(accounts.new_rate_model0.as_deref_mut());
+        rate_models.extend(accounts.new_rate_model1.as_deref_mut());
+        for (old_rate_model, new_rate_model) in changes {
+            if let Some(new_rate_model) = new_rate_model {
+                adjust_pair_references(&mut rate_models, old_rate_model, RateModel::remove_pair_reference)?;
+                adjust_pair_references(&mut rate_models, new_rate_model, RateModel::add_pair_reference)?;
+            }
+        }
+
+        if let Some(new_rate_model0) = &accounts.new_rate_model0 {
+            msg!(
+                "Pair token0 rate model updated from {} to {} for pair with tokens ({}, {})",
+                pair.rate_model0,
+                new_rate_model0.key(),
+                pair.token0,
+                pair.token1
+            );
+            pair.rate_model0 = new_rate_model0.key();
+        }
+        if let Some(new_rate_model1) = &accounts.new_rate_model1 {
+            msg!(
+                "Pair token1 rate model updated from {} to {} for pair with tokens ({}, {})",
+                pair.rate_model1,
+                new_rate_model1.key(),
+                pair.token0,
+                pair.token1
+            );
+            pair.rate_model1 = new_rate_model1.key();
+        }
 
         Ok(())
     }
 }
+
+/// Applies `adjust` to every passed account of the rate model `key`. A rate model can be passed more
+/// than once (e.g. on both sides of a pair), so each copy is kept in sync for the exit serialization.
+fn adjust_pair_references(
+    rate_models: &mut [&mut Account<'_, RateModel>],
+    key: Pubkey,
+    adjust: fn(&mut RateModel) -> Result<()>,
+) -> Result<()> {
+    for rate_model in rate_models.iter_mut().filter(|rate_model| rate_model.key() == key) {
+        adjust(rate_model)?;
+    }
+    Ok(())
+}
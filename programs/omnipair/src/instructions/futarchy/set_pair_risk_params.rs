@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::state::futarchy_authority::FutarchyAuthority;
+use crate::state::pair::Pair;
+use crate::state::risk_params::RiskParams;
+use crate::constants::{FUTARCHY_AUTHORITY_SEED_PREFIX, PAIR_SEED_PREFIX};
+use crate::errors::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetPairRiskParamsArgs {
+    /// Overrides all of the pair's risk parameters, or `None` to follow the global ones again
+    pub risk_params: Option<RiskParams>,
+}
+
+#[derive(Accounts)]
+pub struct SetPairRiskParams<'info> {
+    #[account(
+        mut,
+        address = futarchy_authority.authority @ ErrorCode::InvalidFutarchyAuthority
+    )]
+    pub authority_signer: Signer<'info>,
+
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+
+    #[account(
+        mut,
+        seeds = [
+            PAIR_SEED_PREFIX,
+            pair.token0.as_ref(),
+            pair.token1.as_ref(),
+            pair.params_hash.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Account<'info, Pair>,
+}
+
+impl<'info> SetPairRiskParams<'info> {
+    pub fn validate(args: &SetPairRiskParamsArgs) -> Result<()> {
+        if let Some(risk_params) = &args.risk_params {
+            risk_params.validate()?;
+        }
+        Ok(())
+    }
+
+    pub fn handle_set_pair_risk_params(ctx: Context<Self>, args: SetPairRiskParamsArgs) -> Result<()> {
+        let pair = &mut ctx.accounts.pair;
+
+        match args.risk_params {
+            Some(risk_params) => {
+                pair.risk_params = risk_params;
+                pair.risk_params_overridden = true;
+            }
+            None => {
+                pair.risk_params_overridden = false;
+                pair.sync_risk_params(&ctx.accounts.futarchy_authority.risk_params);
+            }
+        }
+
+        msg!(
+            "Pair risk params set to {:?} (overridden: {}) for pair with tokens ({}, {})",
+            pair.risk_params,
+            pair.risk_params_overridden,
+            pair.token0,
+            pair.token1
+        );
+
+        Ok(())
+    }
+}
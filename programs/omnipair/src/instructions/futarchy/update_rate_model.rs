@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::state::futarchy_authority::FutarchyAuthority;
+use crate::state::rate_model::RateModel;
+use crate::constants::FUTARCHY_AUTHORITY_SEED_PREFIX;
+use crate::errors::ErrorCode;
+use super::create_rate_model::CreateRateModelArgs;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateRateModelArgs {
+    /// New parameters, validated like `create_rate_model`'s
+    pub params: CreateRateModelArgs,
+    /// Timelock of the updates queued after this one is applied, in slots
+    pub update_delay_slots: u64,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRateModel<'info> {
+    #[account(
+        address = futarchy_authority.authority @ ErrorCode::InvalidFutarchyAuthority
+    )]
+    pub authority_signer: Signer<'info>,
+
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+
+    #[account(mut)]
+    pub rate_model: Account<'info, RateModel>,
+}
+
+impl<'info> UpdateRateModel<'info> {
+    pub fn validate(args: &UpdateRateModelArgs) -> Result<()> {
+        args.params.validate()
+    }
+
+    /// Queues new parameters behind the rate model's update delay, replacing any pending update.
+    /// They take effect with `apply_rate_model_update`.
+    pub fn handle_update_rate_model(ctx: Context<Self>, args: UpdateRateModelArgs) -> Result<()> {
+        let rate_model_key = ctx.accounts.rate_model.key();
+        let pending_update = ctx.accounts.rate_model.queue_update(
+            args.params.rate_model().params(),
+            args.update_delay_slots,
+            Clock::get()?.slot,
+        )?;
+
+        args.params.log("update queued", rate_model_key);
+        msg!(
+            "Applicable from slot {}, next update delay {} slots",
+            pending_update.eta_slot,
+            pending_update.update_delay_slots
+        );
+
+        Ok(())
+    }
+}
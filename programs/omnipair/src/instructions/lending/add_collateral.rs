@@ -14,6 +14,7 @@ use crate::{
 
 #[event_cpi]
 #[derive(Accounts)]
+#[instruction(args: AdjustCollateralArgs)]
 pub struct AddCollateral<'info> {
     #[account(
         mut,
@@ -29,9 +30,15 @@ pub struct AddCollateral<'info> {
 
     #[account(
         mut,
-        address = pair.rate_model,
+        address = pair.rate_model0,
     )]
-    pub rate_model: Account<'info, RateModel>,
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Account<'info, RateModel>,
 
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
@@ -43,12 +50,16 @@ pub struct AddCollateral<'info> {
         init_if_needed,
         payer = user,
         space = get_size_with_discriminator::<UserPosition>(),
-        constraint = user_position.owner == Pubkey::default() || user_position.owner == user.key(),
+        constraint = user_position.owner == Pubkey::default() || user_position.owner == position_owner.key(),
         constraint = user_position.pair == Pubkey::default() || user_position.pair == pair.key(),
+        // Only the owner opens a position; others can top up existing ones
+        constraint = user_position.is_initialized() || position_owner.key() == user.key()
+            @ ErrorCode::OnlyOwnerCanOpenPosition,
         seeds = [
             POSITION_SEED_PREFIX,
             pair.key().as_ref(),
-            user.key().as_ref()
+            position_owner.key().as_ref(),
+            &UserPosition::index_seed(args.position_index)
         ],
         bump
     )]
@@ -77,6 +88,11 @@ pub struct AddCollateral<'info> {
     )]
     pub collateral_token_mint: Box<Account<'info, Mint>>,
 
+    /// CHECK: Owner of the position, `user` itself unless topping up someone else's position.
+    pub position_owner: UncheckedAccount<'info>,
+
+    /// Pays the collateral: the owner or anyone else, as adding collateral only improves the
+    /// position's health. Only the owner can create the position, paying its rent
     #[account(mut)]
     pub user: Signer<'info>,
     pub token_program: Program<'info, Token>,
@@ -86,7 +102,7 @@ pub struct AddCollateral<'info> {
 
 impl<'info> AddCollateral<'info> {
     pub fn validate_add(&self, args: &AdjustCollateralArgs) -> Result<()> {
-        let AdjustCollateralArgs { amount } = args;
+        let AdjustCollateralArgs { amount, .. } = args;
         
         require!(*amount > 0, ErrorCode::AmountZero);
         
@@ -102,7 +118,8 @@ impl<'info> AddCollateral<'info> {
     pub fn update(&mut self) -> Result<()> {
         let pair_key = self.pair.to_account_info().key();
         self.pair.update(
-            &self.rate_model,
+            &self.rate_model0,
+            &self.rate_model1,
             &self.futarchy_authority,
             pair_key,
             Some(self.event_authority.to_account_info()),
@@ -125,20 +142,23 @@ impl<'info> AddCollateral<'info> {
             token_program,
             user_collateral_token_account,
             user_position,
+            position_owner,
             token_2022_program,
             ..
         } = ctx.accounts;
 
         if !user_position.is_initialized() {
             user_position.initialize(
-                user.key(),
+                position_owner.key(),
                 pair.key(),
+                args.position_index,
                 ctx.bumps.user_position,
             )?;
 
             emit_cpi!(UserPositionCreatedEvent {
                 metadata: EventMetadata::new(user.key(), pair.key()),
                 position: user_position.key(),
+                position_index: args.position_index,
             });
         }
 
@@ -168,6 +188,7 @@ impl<'info> AddCollateral<'info> {
                 user_position.collateral1 = user_position.collateral1.checked_add(args.amount).unwrap();
             }
         }
+        pair.check_collateral_cap(&user_collateral_token_account.mint)?;
 
         // Emit collateral adjustment event
         let (amount0, amount1) = if user_collateral_token_account.mint == pair.token0 {
@@ -186,6 +207,7 @@ impl<'info> AddCollateral<'info> {
         emit_cpi!(UserPositionUpdatedEvent {
             metadata: EventMetadata::new(user.key(), pair.key()),
             position: user_position.key(),
+            owner: user_position.owner,
             collateral0: user_position.collateral0,
             collateral1: user_position.collateral1,
             debt0_shares: user_position.debt0_shares,
@@ -13,7 +13,7 @@ use crate::{
     instructions::lending::common::AdjustDebtArgs,
     state::{
         futarchy_authority::FutarchyAuthority, pair::Pair, rate_model::RateModel,
-        user_position::UserPosition,
+        user_position::{DelegatePermission, UserPosition},
     },
     utils::{
         liquidity_delta_circuit_breaker::require_no_same_tx_liquidity_delta,
@@ -38,12 +38,14 @@ pub struct Borrow<'info> {
 
     #[account(
         mut,
-        constraint = user_position.owner == user.key(),
+        constraint = user_position.is_authorized(&user.key(), DelegatePermission::Manage, Clock::get()?.slot)
+            @ ErrorCode::PositionDelegateUnauthorized,
         constraint = user_position.pair == pair.key(),
         seeds = [
             POSITION_SEED_PREFIX,
             pair.key().as_ref(),
-            user.key().as_ref()
+            user_position.owner.as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
         ],
         bump = user_position.bump
     )]
@@ -51,9 +53,15 @@ pub struct Borrow<'info> {
 
     #[account(
         mut,
-        address = pair.rate_model,
+        address = pair.rate_model0,
     )]
-    pub rate_model: Account<'info, RateModel>,
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Account<'info, RateModel>,
 
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
@@ -75,7 +83,7 @@ pub struct Borrow<'info> {
     #[account(
         mut,
         constraint = user_reserve_token_account.mint == reserve_token_mint.key() @ ErrorCode::InvalidMint,
-        token::authority = user,
+        token::authority = user_position.owner,
     )]
     pub user_reserve_token_account: Box<Account<'info, TokenAccount>>,
 
@@ -106,6 +114,104 @@ fn resolve_borrow_amount(requested_amount: u64, borrow_limit: u64, user_debt: u6
     Ok(borrow_amount)
 }
 
+/// State transition of a `borrow`: checks the borrow limit and cash, then books the debt
+/// and locks in the liquidation CF. `u64::MAX` borrows the full remaining borrow limit.
+///
+/// Returns the amount borrowed.
+pub fn apply_borrow(pair: &mut Pair, user_position: &mut UserPosition, debt_token: &Pubkey, requested_amount: u64) -> Result<u64> {
+    if user_position.cross_margin {
+        return apply_cross_margin_borrow(pair, user_position, debt_token, requested_amount);
+    }
+    let is_token0 = *debt_token == pair.token0;
+
+    let user_debt = match is_token0 {
+        true => user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?,
+        false => user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?,
+    };
+
+    // If EMA lags behind a falling spot price, there will be a window where the collateral value may be artificially inflated.
+    // To prevent bad debt, we compute a pessimistic collateral factor:
+    // CF_pessimistic = min(CF_base, P_spot / P_EMA * CF_base)
+    // This ensures the solvency invariant: P_spot >= P_EMA * CF
+    let collateral_token = pair.get_collateral_token(debt_token);
+    let collateral_amount = match collateral_token == pair.token0 {
+        true => user_position.collateral0,
+        false => user_position.collateral1,
+    };
+    let (borrow_limit, _, liquidation_cf_bps) = pair.get_max_debt_and_cf_bps_for_collateral(
+        pair,
+        &collateral_token,
+        collateral_amount,
+    )?;
+    let borrow_amount = resolve_borrow_amount(requested_amount, borrow_limit, user_debt)?;
+
+    let new_debt = user_debt
+        .checked_add(borrow_amount)
+        .ok_or(ErrorCode::DebtMathOverflow)?;
+
+    require_gte!(borrow_limit, new_debt, ErrorCode::BorrowingPowerExceeded);
+
+    require_cash_reserve(pair, is_token0, borrow_amount)?;
+
+    user_position.increase_debt(pair, debt_token, borrow_amount)?;
+    user_position.set_liquidation_cf_for_debt_token(debt_token, pair, liquidation_cf_bps);
+    pair.check_borrow_caps(debt_token)?;
+
+    Ok(borrow_amount)
+}
+
+/// `apply_borrow` of a cross-margin position: the debt is backed by the collateral of the same token
+/// first, then by the net surplus of the other token. The post-borrow net exposure must stay
+/// within `UserPosition::check_net_borrow_limit`.
+fn apply_cross_margin_borrow(pair: &mut Pair, user_position: &mut UserPosition, debt_token: &Pubkey, requested_amount: u64) -> Result<u64> {
+    let is_token0 = *debt_token == pair.token0;
+    let exposure = user_position.net_exposure(pair)?;
+    let (net_debt_token, net_other_token) = match is_token0 {
+        true => (exposure.net0, exposure.net1),
+        false => (exposure.net1, exposure.net0),
+    };
+
+    // Headroom for `u64::MAX`: the same-token surplus plus the borrow limit of the other token's surplus
+    let other_surplus = u64::try_from(net_other_token.max(0)).map_err(|_| ErrorCode::DebtMathOverflow)?;
+    let (other_borrow_limit, _, _) = pair.get_max_debt_and_cf_bps_for_collateral(
+        pair,
+        &pair.get_collateral_token(debt_token),
+        other_surplus,
+    )?;
+    let same_token_surplus = u64::try_from(net_debt_token.max(0)).map_err(|_| ErrorCode::DebtMathOverflow)?;
+    let net_debt = u64::try_from(net_debt_token.min(0).unsigned_abs()).map_err(|_| ErrorCode::DebtMathOverflow)?;
+    let borrow_amount = resolve_borrow_amount(
+        requested_amount,
+        same_token_surplus.saturating_add(other_borrow_limit),
+        net_debt,
+    )?;
+
+    require_cash_reserve(pair, is_token0, borrow_amount)?;
+
+    user_position.increase_debt(pair, debt_token, borrow_amount)?;
+    user_position.check_net_borrow_limit(pair)?;
+    pair.check_borrow_caps(debt_token)?;
+
+    Ok(borrow_amount)
+}
+
+/// r_cash >= r_debt_out
+fn require_cash_reserve(pair: &Pair, is_token0: bool, borrow_amount: u64) -> Result<()> {
+    match is_token0 {
+        true => require_gte!(
+            pair.cash_reserve0,
+            borrow_amount,
+            ErrorCode::InsufficientCashReserve0
+        ),
+        false => require_gte!(
+            pair.cash_reserve1,
+            borrow_amount,
+            ErrorCode::InsufficientCashReserve1
+        ),
+    };
+    Ok(())
+}
+
 impl<'info> Borrow<'info> {
     pub fn validate_borrow(&self, args: &AdjustDebtArgs) -> Result<()> {
         let AdjustDebtArgs {
@@ -133,7 +239,8 @@ impl<'info> Borrow<'info> {
     pub fn update(&mut self) -> Result<()> {
         let pair_key = self.pair.to_account_info().key();
         self.pair.update(
-            &self.rate_model,
+            &self.rate_model0,
+            &self.rate_model1,
             &self.futarchy_authority,
             pair_key,
             Some(self.event_authority.to_account_info()),
@@ -172,46 +279,7 @@ impl<'info> Borrow<'info> {
         let debt_token = reserve_token_mint.key();
         let is_token0 = debt_token == pair.token0;
 
-        let user_debt = match is_token0 {
-            true => user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?,
-            false => user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?,
-        };
-
-        // If EMA lags behind a falling spot price, there will be a window where the collateral value may be artificially inflated.
-        // To prevent bad debt, we compute a pessimistic collateral factor:
-        // CF_pessimistic = min(CF_base, P_spot / P_EMA * CF_base)
-        // This ensures the solvency invariant: P_spot >= P_EMA * CF
-        let collateral_token = pair.get_collateral_token(&debt_token);
-        let collateral_amount = match collateral_token == pair.token0 {
-            true => user_position.collateral0,
-            false => user_position.collateral1,
-        };
-        let (borrow_limit, _, liquidation_cf_bps) = pair.get_max_debt_and_cf_bps_for_collateral(
-            &pair,
-            &collateral_token,
-            collateral_amount,
-        )?;
-        let borrow_amount = resolve_borrow_amount(args.amount, borrow_limit, user_debt)?;
-
-        let new_debt = user_debt
-            .checked_add(borrow_amount)
-            .ok_or(ErrorCode::DebtMathOverflow)?;
-
-        require_gte!(borrow_limit, new_debt, ErrorCode::BorrowingPowerExceeded);
-
-        // r_cash >= r_debt_out
-        match is_token0 {
-            true => require_gte!(
-                pair.cash_reserve0,
-                borrow_amount,
-                ErrorCode::InsufficientCashReserve0
-            ),
-            false => require_gte!(
-                pair.cash_reserve1,
-                borrow_amount,
-                ErrorCode::InsufficientCashReserve1
-            ),
-        };
+        let borrow_amount = apply_borrow(pair, user_position, &debt_token, args.amount)?;
 
         // Transfer tokens from vault to user
         transfer_from_vault_to_user(
@@ -228,9 +296,6 @@ impl<'info> Borrow<'info> {
             &[&generate_gamm_pair_seeds!(pair)[..]],
         )?;
 
-        user_position.increase_debt(pair, &debt_token, borrow_amount)?;
-        user_position.set_liquidation_cf_for_debt_token(&debt_token, &pair, liquidation_cf_bps);
-
         // Emit debt adjustment event
         let (amount0, amount1) = if is_token0 {
             (borrow_amount as i64, 0)
@@ -248,6 +313,7 @@ impl<'info> Borrow<'info> {
         emit_cpi!(UserPositionUpdatedEvent {
             metadata: EventMetadata::new(user.key(), pair.key()),
             position: user_position.key(),
+            owner: user_position.owner,
             collateral0: user_position.collateral0,
             collateral1: user_position.collateral1,
             debt0_shares: user_position.debt0_shares,
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    events::{EventMetadata, PositionOrderClosedEvent},
+    state::{position_order::PositionOrder, user_position::UserPosition},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelPositionOrder<'info> {
+    #[account(
+        mut,
+        close = user,
+        constraint = position_order.owner == user.key(),
+        seeds = [
+            POSITION_ORDER_SEED_PREFIX,
+            position_order.user_position.as_ref(),
+            &position_order.order_index.to_le_bytes()
+        ],
+        bump = position_order.bump
+    )]
+    pub position_order: Account<'info, PositionOrder>,
+
+    #[account(mut, address = position_order.user_position)]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// Owner of the order, receives its rent
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+impl<'info> CancelPositionOrder<'info> {
+    /// Cancels an order that has not been executed.
+    pub fn handle_cancel_position_order(ctx: Context<Self>) -> Result<()> {
+        let CancelPositionOrder { position_order, user_position, user, .. } = ctx.accounts;
+        user_position.open_orders = user_position.open_orders.saturating_sub(1);
+
+        emit_cpi!(PositionOrderClosedEvent {
+            metadata: EventMetadata::new(user.key(), position_order.pair),
+            order: position_order.key(),
+            position: position_order.user_position,
+            executed: false,
+            collateral_out: 0,
+            keeper_bounty: 0,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,355 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::{
+    token::{Mint, Token, TokenAccount},
+    token_interface::Token2022,
+};
+
+use crate::{
+    constants::*,
+    errors::ErrorCode,
+    events::{
+        AdjustCollateralEvent, AdjustDebtEvent, EventMetadata, SwapEvent,
+        UserPositionUpdatedEvent,
+    },
+    generate_gamm_pair_seeds,
+    instructions::spot::SwapAmounts,
+    state::{
+        futarchy_authority::FutarchyAuthority, pair::Pair, rate_model::RateModel,
+        user_position::{DebtDecreaseReason, UserPosition},
+    },
+    utils::{
+        liquidity_delta_circuit_breaker::require_no_same_tx_liquidity_delta,
+        token::{transfer_from_vault_to_user, transfer_from_vault_to_vault},
+    },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClosePositionArgs {
+    /// Slippage bound on the collateral returned to the owner after repaying the debt
+    pub min_collateral_out: u64,
+}
+
+/// Resolved amounts of closing one collateral side of a position: just enough of the collateral is
+/// sold through the pair's curve to buy back the whole debt it backs, the rest goes to the owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClosePositionAmounts {
+    /// Debt backed by the closed side, repaid in full
+    pub debt_repaid: u64,
+    /// Collateral of the closed side before the close
+    pub user_collateral: u64,
+    /// Collateral sold for exactly `debt_repaid` of the debt token
+    pub swap: SwapAmounts,
+    /// Collateral left after the sale, released from the position
+    pub collateral_out: u64,
+}
+
+impl ClosePositionAmounts {
+    /// Fails with `Undercollateralized` if the collateral can't buy back the debt.
+    pub fn new(pair: &Pair, user_position: &UserPosition, futarchy_swap_bps: u16, is_collateral_token0: bool) -> Result<Self> {
+        let debt_repaid = debt_for_collateral(pair, user_position, is_collateral_token0)?;
+        let user_collateral = match is_collateral_token0 {
+            true => user_position.collateral0,
+            false => user_position.collateral1,
+        };
+
+        // Collateral needed to buy back the debt, fees included
+        let swap = SwapAmounts::exact_out(pair, futarchy_swap_bps, is_collateral_token0, debt_repaid)?;
+        require_gte!(user_collateral, swap.amount_in, ErrorCode::Undercollateralized);
+
+        let collateral_out = user_collateral
+            .checked_sub(swap.amount_in)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(Self { debt_repaid, user_collateral, swap, collateral_out })
+    }
+
+    pub fn apply(&self, pair: &mut Pair, user_position: &mut UserPosition, is_collateral_token0: bool) -> Result<()> {
+        let debt_token = if is_collateral_token0 { pair.token1 } else { pair.token0 };
+
+        // Repay first so the repaid cash backs the swap output: ΔR_cash(debt) nets to zero
+        user_position.decrease_debt(pair, &debt_token, self.debt_repaid, DebtDecreaseReason::Repayment)?;
+        self.swap.apply(pair, is_collateral_token0)?;
+
+        match is_collateral_token0 {
+            true => {
+                pair.total_collateral0 = pair.total_collateral0.checked_sub(self.user_collateral).ok_or(ErrorCode::Overflow)?;
+                user_position.collateral0 = 0;
+            },
+            false => {
+                pair.total_collateral1 = pair.total_collateral1.checked_sub(self.user_collateral).ok_or(ErrorCode::Overflow)?;
+                user_position.collateral1 = 0;
+            }
+        }
+        // The closed side's collateral also backed the rest of a cross-margin position
+        if user_position.cross_margin {
+            user_position.check_net_borrow_limit(pair)?;
+        }
+        Ok(())
+    }
+}
+
+/// Outstanding debt backed by the collateral side being closed.
+pub fn debt_for_collateral(pair: &Pair, user_position: &UserPosition, is_collateral_token0: bool) -> Result<u64> {
+    match is_collateral_token0 {
+        true => user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares),
+        false => user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares),
+    }
+}
+
+/// Checks of `ClosePosition::validate_close` on the pair and position.
+pub fn validate_close_position(pair: &Pair, user_position: &UserPosition, is_collateral_token0: bool) -> Result<()> {
+    require_gt!(
+        debt_for_collateral(pair, user_position, is_collateral_token0)?,
+        0,
+        ErrorCode::ZeroDebtAmount
+    );
+    Ok(())
+}
+
+/// State transition of a close of the `is_collateral_token0` side, returning at least `min_collateral_out`.
+pub fn apply_close_position(
+    pair: &mut Pair,
+    user_position: &mut UserPosition,
+    futarchy_swap_bps: u16,
+    is_collateral_token0: bool,
+    min_collateral_out: u64,
+) -> Result<ClosePositionAmounts> {
+    let amounts = ClosePositionAmounts::new(pair, user_position, futarchy_swap_bps, is_collateral_token0)?;
+    require_gte!(amounts.collateral_out, min_collateral_out, ErrorCode::SlippageExceeded);
+    amounts.apply(pair, user_position, is_collateral_token0)?;
+    Ok(amounts)
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(
+        mut,
+        seeds = [
+            PAIR_SEED_PREFIX,
+            pair.token0.as_ref(),
+            pair.token1.as_ref(),
+            pair.params_hash.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Box<Account<'info, Pair>>,
+
+    #[account(
+        mut,
+        constraint = user_position.owner == user.key(),
+        constraint = user_position.pair == pair.key(),
+        seeds = [
+            POSITION_SEED_PREFIX,
+            pair.key().as_ref(),
+            user.key().as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
+        ],
+        bump = user_position.bump
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+
+    #[account(
+        mut,
+        address = pair.rate_model0,
+    )]
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Account<'info, RateModel>,
+
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+
+    #[account(
+        mut,
+        seeds = [
+            COLLATERAL_VAULT_SEED_PREFIX,
+            pair.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+        ],
+        bump = pair.get_collateral_vault_bump(&collateral_token_mint.key())
+    )]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Reserve vault of the collateral token, which receives the collateral sold to repay the debt
+    #[account(
+        mut,
+        seeds = [
+            RESERVE_VAULT_SEED_PREFIX,
+            pair.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+        ],
+        bump = pair.get_reserve_vault_bump(&collateral_token_mint.key())
+    )]
+    pub collateral_reserve_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_collateral_token_account.mint == collateral_token_mint.key() @ ErrorCode::InvalidMint,
+        token::authority = user,
+    )]
+    pub user_collateral_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = collateral_token_mint.key() == pair.token0 || collateral_token_mint.key() == pair.token1 @ ErrorCode::InvalidMint
+    )]
+    pub collateral_token_mint: Box<Account<'info, Mint>>,
+
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+
+    /// CHECK: Instructions sysvar used by the liquidity delta circuit breaker.
+    #[account(address = sysvar::instructions::ID @ ErrorCode::InvalidInstructionsSysvar)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+impl<'info> ClosePosition<'info> {
+    pub fn validate_close(&self, _args: &ClosePositionArgs) -> Result<()> {
+        require_no_same_tx_liquidity_delta(
+            &self.pair.key(),
+            &self.instructions_sysvar.to_account_info(),
+        )?;
+
+        let is_collateral_token0 = self.collateral_token_mint.key() == self.pair.token0;
+        validate_close_position(&self.pair, &self.user_position, is_collateral_token0)
+    }
+
+    pub fn update(&mut self) -> Result<()> {
+        let pair_key = self.pair.to_account_info().key();
+        self.pair.update(
+            &self.rate_model0,
+            &self.rate_model1,
+            &self.futarchy_authority,
+            pair_key,
+            Some(self.event_authority.to_account_info()),
+        )?;
+        Ok(())
+    }
+
+    pub fn update_and_validate_close(&mut self, args: &ClosePositionArgs) -> Result<()> {
+        self.update()?;
+        self.validate_close(args)?;
+        Ok(())
+    }
+
+    /// Unwinds one side of a position without outside funds:
+    /// sells just enough collateral through the pair's curve to buy back the full debt,
+    /// repays it, and returns the remaining collateral to the owner.
+    ///
+    /// The bought debt tokens never leave the debt reserve vault; only the sold collateral
+    /// moves from the collateral vault into the collateral reserve vault.
+    pub fn handle_close(ctx: Context<Self>, args: ClosePositionArgs) -> Result<()> {
+        let ClosePositionArgs { min_collateral_out } = args;
+        let is_collateral_token0 = ctx.accounts.collateral_token_mint.key() == ctx.accounts.pair.token0;
+
+        let ClosePosition {
+            pair,
+            futarchy_authority,
+            user_position,
+            collateral_vault,
+            collateral_reserve_vault,
+            user_collateral_token_account,
+            collateral_token_mint,
+            user,
+            token_program,
+            token_2022_program,
+            ..
+        } = ctx.accounts;
+
+        let ClosePositionAmounts { debt_repaid, user_collateral, swap: amounts, collateral_out } = apply_close_position(
+            pair,
+            user_position,
+            futarchy_authority.revenue_share.swap_bps,
+            is_collateral_token0,
+            min_collateral_out,
+        )?;
+
+        let token_program_info = match collateral_token_mint.to_account_info().owner == token_program.key {
+            true => token_program.to_account_info(),
+            false => token_2022_program.to_account_info(),
+        };
+
+        // Sold collateral: collateral vault -> collateral reserve vault
+        transfer_from_vault_to_vault(
+            pair.to_account_info(),
+            collateral_vault.to_account_info(),
+            collateral_reserve_vault.to_account_info(),
+            collateral_token_mint.to_account_info(),
+            token_program_info.clone(),
+            amounts.amount_in,
+            collateral_token_mint.decimals,
+            &[&generate_gamm_pair_seeds!(pair)[..]],
+        )?;
+
+        // Remaining collateral: collateral vault -> owner
+        if collateral_out > 0 {
+            transfer_from_vault_to_user(
+                pair.to_account_info(),
+                collateral_vault.to_account_info(),
+                user_collateral_token_account.to_account_info(),
+                collateral_token_mint.to_account_info(),
+                token_program_info,
+                collateral_out,
+                collateral_token_mint.decimals,
+                &[&generate_gamm_pair_seeds!(pair)[..]],
+            )?;
+        }
+
+        let (collateral0, collateral1) = match is_collateral_token0 {
+            true => (-(user_collateral as i64), 0),
+            false => (0, -(user_collateral as i64)),
+        };
+        emit_cpi!(AdjustCollateralEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            amount0: collateral0,
+            amount1: collateral1,
+        });
+
+        let (debt0, debt1) = match is_collateral_token0 {
+            true => (0, -(debt_repaid as i64)),
+            false => (-(debt_repaid as i64), 0),
+        };
+        emit_cpi!(AdjustDebtEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            amount0: debt0,
+            amount1: debt1,
+        });
+
+        emit_cpi!(SwapEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            reserve0: pair.reserve0,
+            reserve1: pair.reserve1,
+            is_token0_in: is_collateral_token0,
+            is_exact_out: true,
+            amount_in: amounts.amount_in,
+            amount_out: amounts.amount_out,
+            amount_in_after_fee: amounts.amount_in_after_fee,
+            lp_fee: amounts.lp_fee,
+            protocol_fee: amounts.protocol_fee,
+        });
+
+        emit_cpi!(UserPositionUpdatedEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            position: user_position.key(),
+            owner: user_position.owner,
+            collateral0: user_position.collateral0,
+            collateral1: user_position.collateral1,
+            debt0_shares: user_position.debt0_shares,
+            debt1_shares: user_position.debt1_shares,
+            collateral0_max_cf_bps: user_position.get_max_cf_bps_for_debt_token(pair, &pair.token1),
+            collateral1_max_cf_bps: user_position.get_max_cf_bps_for_debt_token(pair, &pair.token0),
+            collateral0_liquidation_cf_bps: user_position.collateral0_liquidation_cf_bps,
+            collateral1_liquidation_cf_bps: user_position.collateral1_liquidation_cf_bps,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    errors::ErrorCode,
+    events::{EventMetadata, UserPositionClosedEvent},
+    state::user_position::UserPosition,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePositionAccount<'info> {
+    #[account(
+        mut,
+        close = user,
+        constraint = user_position.owner == user.key(),
+        constraint = user_position.is_empty() @ ErrorCode::UserPositionNotEmpty,
+        constraint = user_position.open_orders == 0 @ ErrorCode::PositionHasOpenOrders,
+        seeds = [
+            POSITION_SEED_PREFIX,
+            user_position.pair.as_ref(),
+            user.key().as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
+        ],
+        bump = user_position.bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// Owner of the position, receives its rent
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+impl<'info> ClosePositionAccount<'info> {
+    /// Closes a position without collateral, debt or open orders. `add_collateral` or `open_leveraged`
+    /// recreate it at the same address; its margin mode and delegate are reset, and orders must be
+    /// cancelled first so they can't apply to the new position. Positions in an older layout must go
+    /// through `migrate_user_position` first.
+    pub fn handle_close_position_account(ctx: Context<Self>) -> Result<()> {
+        let ClosePositionAccount { user_position, user, .. } = ctx.accounts;
+
+        emit_cpi!(UserPositionClosedEvent {
+            metadata: EventMetadata::new(user.key(), user_position.pair),
+            position: user_position.key(),
+        });
+
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@ use anchor_spl::{
 use crate::{
     state::pair::Pair,
     state::rate_model::RateModel,
-    state::user_position::UserPosition,
+    state::user_position::{DelegatePermission, UserPosition},
     state::futarchy_authority::FutarchyAuthority,
     constants::*,
     errors::ErrorCode,
@@ -18,10 +18,13 @@ use crate::{
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct AdjustCollateralArgs {
     pub amount: u64,
+    /// Position of the user in the pair, 0 for the default one (see `UserPosition::position_index`)
+    pub position_index: u16,
 }
 
 #[event_cpi]
 #[derive(Accounts)]
+#[instruction(args: AdjustCollateralArgs)]
 pub struct CommonAdjustCollateral<'info> {
     #[account(
         mut,
@@ -37,12 +40,14 @@ pub struct CommonAdjustCollateral<'info> {
 
     #[account(
         mut,
-        constraint = user_position.owner == user.key(),
+        constraint = user_position.is_authorized(&user.key(), DelegatePermission::Manage, Clock::get()?.slot)
+            @ ErrorCode::PositionDelegateUnauthorized,
         constraint = user_position.pair == pair.key(),
         seeds = [
             POSITION_SEED_PREFIX,
             pair.key().as_ref(),
-            user.key().as_ref()
+            user_position.owner.as_ref(),
+            &UserPosition::index_seed(args.position_index)
         ],
         bump = user_position.bump
     )]
@@ -50,9 +55,15 @@ pub struct CommonAdjustCollateral<'info> {
 
     #[account(
         mut,
-        address = pair.rate_model,
+        address = pair.rate_model0,
     )]
-    pub rate_model: Account<'info, RateModel>,
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Account<'info, RateModel>,
 
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
@@ -74,7 +85,7 @@ pub struct CommonAdjustCollateral<'info> {
     #[account(
         mut,
         constraint = user_collateral_token_account.mint == pair.token0 || user_collateral_token_account.mint == pair.token1,
-        token::authority = user,
+        token::authority = user_position.owner,
     )]
     pub user_collateral_token_account: Box<Account<'info, TokenAccount>>,
 
@@ -98,7 +109,8 @@ impl<'info> CommonAdjustCollateral<'info> {
     pub fn update(&mut self) -> Result<()> {
         let pair_key = self.pair.to_account_info().key();
         self.pair.update(
-            &self.rate_model,
+            &self.rate_model0,
+            &self.rate_model1,
             &self.futarchy_authority,
             pair_key,
             Some(self.event_authority.to_account_info()),
@@ -129,12 +141,12 @@ pub struct CommonAdjustDebt<'info> {
 
     #[account(
         mut,
-        constraint = user_position.owner == user.key(),
         constraint = user_position.pair == pair.key(),
         seeds = [
             POSITION_SEED_PREFIX,
             pair.key().as_ref(),
-            user.key().as_ref()
+            user_position.owner.as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
         ],
         bump = user_position.bump
     )]
@@ -142,9 +154,15 @@ pub struct CommonAdjustDebt<'info> {
 
     #[account(
         mut,
-        address = pair.rate_model,
+        address = pair.rate_model0,
+    )]
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
     )]
-    pub rate_model: Account<'info, RateModel>,
+    pub rate_model1: Account<'info, RateModel>,
 
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
@@ -175,6 +193,7 @@ pub struct CommonAdjustDebt<'info> {
     )]
     pub reserve_token_mint: Box<Account<'info, Mint>>,
 
+    /// Pays the repayment: the owner or anyone else, as repaying only improves the position's health
     pub user: Signer<'info>,
     pub token_program: Program<'info, Token>,
     pub token_2022_program: Program<'info, Token2022>,
@@ -186,7 +205,8 @@ impl<'info> CommonAdjustDebt<'info> {
     pub fn update(&mut self) -> Result<()> {
         let pair_key = self.pair.to_account_info().key();
         self.pair.update(
-            &self.rate_model,
+            &self.rate_model0,
+            &self.rate_model1,
             &self.futarchy_authority,
             pair_key,
             Some(self.event_authority.to_account_info()),
@@ -0,0 +1,329 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::{
+    token::{Mint, Token, TokenAccount},
+    token_interface::Token2022,
+};
+
+use crate::{
+    constants::*,
+    errors::ErrorCode,
+    events::{
+        AdjustCollateralEvent, AdjustDebtEvent, EventMetadata, PositionOrderClosedEvent, SwapEvent,
+        UserPositionUpdatedEvent,
+    },
+    generate_gamm_pair_seeds,
+    instructions::lending::close_position::{apply_close_position, validate_close_position, ClosePositionAmounts},
+    state::{
+        futarchy_authority::FutarchyAuthority, pair::Pair, position_order::PositionOrder,
+        rate_model::RateModel, user_position::UserPosition,
+    },
+    utils::{
+        liquidity_delta_circuit_breaker::require_no_same_tx_liquidity_delta,
+        token::{transfer_from_vault_to_user, transfer_from_vault_to_vault},
+    },
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecutePositionOrder<'info> {
+    #[account(
+        mut,
+        seeds = [
+            PAIR_SEED_PREFIX,
+            pair.token0.as_ref(),
+            pair.token1.as_ref(),
+            pair.params_hash.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Box<Account<'info, Pair>>,
+
+    #[account(
+        mut,
+        address = position_order.user_position,
+        constraint = user_position.pair == pair.key(),
+        seeds = [
+            POSITION_SEED_PREFIX,
+            pair.key().as_ref(),
+            user_position.owner.as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
+        ],
+        bump = user_position.bump
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            POSITION_ORDER_SEED_PREFIX,
+            position_order.user_position.as_ref(),
+            &position_order.order_index.to_le_bytes()
+        ],
+        bump = position_order.bump
+    )]
+    pub position_order: Box<Account<'info, PositionOrder>>,
+
+    /// CHECK: Owner of the order and its position, receives the order's rent.
+    #[account(mut, address = position_order.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = pair.rate_model0,
+    )]
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Account<'info, RateModel>,
+
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+
+    #[account(
+        mut,
+        seeds = [
+            COLLATERAL_VAULT_SEED_PREFIX,
+            pair.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+        ],
+        bump = pair.get_collateral_vault_bump(&collateral_token_mint.key())
+    )]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Reserve vault of the collateral token, which receives the collateral sold to repay the debt
+    #[account(
+        mut,
+        seeds = [
+            RESERVE_VAULT_SEED_PREFIX,
+            pair.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+        ],
+        bump = pair.get_reserve_vault_bump(&collateral_token_mint.key())
+    )]
+    pub collateral_reserve_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = owner_collateral_token_account.mint == collateral_token_mint.key() @ ErrorCode::InvalidMint,
+        token::authority = owner,
+    )]
+    pub owner_collateral_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = keeper_collateral_token_account.mint == collateral_token_mint.key() @ ErrorCode::InvalidMint,
+    )]
+    pub keeper_collateral_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Collateral side of the order
+    #[account(
+        constraint = collateral_token_mint.key() == match position_order.is_collateral_token0 {
+            true => pair.token0,
+            false => pair.token1,
+        } @ ErrorCode::InvalidMint
+    )]
+    pub collateral_token_mint: Box<Account<'info, Mint>>,
+
+    pub keeper: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+
+    /// CHECK: Instructions sysvar used by the liquidity delta circuit breaker.
+    #[account(address = sysvar::instructions::ID @ ErrorCode::InvalidInstructionsSysvar)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+/// Checks of `ExecutePositionOrder::validate_execute` on the pair, position and order.
+pub fn validate_position_order(pair: &Pair, user_position: &UserPosition, position_order: &PositionOrder) -> Result<()> {
+    require!(position_order.trigger.is_met(pair), ErrorCode::PositionOrderNotTriggered);
+    validate_close_position(pair, user_position, position_order.is_collateral_token0)
+}
+
+/// State transition of `execute_position_order`, after `validate_position_order`: closes the ordered
+/// collateral side as `close_position` does and removes the order from the position's open orders.
+pub fn apply_position_order(
+    pair: &mut Pair,
+    user_position: &mut UserPosition,
+    position_order: &PositionOrder,
+    futarchy_swap_bps: u16,
+) -> Result<ClosePositionAmounts> {
+    let amounts = apply_close_position(
+        pair,
+        user_position,
+        futarchy_swap_bps,
+        position_order.is_collateral_token0,
+        position_order.min_close_collateral_out()?,
+    )?;
+    user_position.open_orders = user_position.open_orders.saturating_sub(1);
+    Ok(amounts)
+}
+
+impl<'info> ExecutePositionOrder<'info> {
+    pub fn validate_execute(&self) -> Result<()> {
+        require_no_same_tx_liquidity_delta(
+            &self.pair.key(),
+            &self.instructions_sysvar.to_account_info(),
+        )?;
+        validate_position_order(&self.pair, &self.user_position, &self.position_order)
+    }
+
+    pub fn update(&mut self) -> Result<()> {
+        let pair_key = self.pair.to_account_info().key();
+        self.pair.update(
+            &self.rate_model0,
+            &self.rate_model1,
+            &self.futarchy_authority,
+            pair_key,
+            Some(self.event_authority.to_account_info()),
+        )?;
+        Ok(())
+    }
+
+    pub fn update_and_validate_execute(&mut self) -> Result<()> {
+        self.update()?;
+        self.validate_execute()?;
+        Ok(())
+    }
+
+    /// Closes the ordered collateral side of a triggered order the same way `close_position` does,
+    /// pays `keeper_bounty` of the released collateral to the keeper and the rest to the owner,
+    /// and closes the order.
+    pub fn handle_execute_position_order(ctx: Context<Self>) -> Result<()> {
+        let ExecutePositionOrder {
+            pair,
+            futarchy_authority,
+            user_position,
+            position_order,
+            collateral_vault,
+            collateral_reserve_vault,
+            owner_collateral_token_account,
+            keeper_collateral_token_account,
+            collateral_token_mint,
+            keeper,
+            token_program,
+            token_2022_program,
+            ..
+        } = ctx.accounts;
+        let is_collateral_token0 = position_order.is_collateral_token0;
+
+        let ClosePositionAmounts { debt_repaid, user_collateral, swap: amounts, collateral_out } = apply_position_order(
+            pair,
+            user_position,
+            position_order,
+            futarchy_authority.revenue_share.swap_bps,
+        )?;
+        let keeper_bounty = position_order.keeper_bounty;
+        let owner_collateral_out = collateral_out.checked_sub(keeper_bounty).ok_or(ErrorCode::Overflow)?;
+
+        let token_program_info = match collateral_token_mint.to_account_info().owner == token_program.key {
+            true => token_program.to_account_info(),
+            false => token_2022_program.to_account_info(),
+        };
+
+        // Sold collateral: collateral vault -> collateral reserve vault
+        transfer_from_vault_to_vault(
+            pair.to_account_info(),
+            collateral_vault.to_account_info(),
+            collateral_reserve_vault.to_account_info(),
+            collateral_token_mint.to_account_info(),
+            token_program_info.clone(),
+            amounts.amount_in,
+            collateral_token_mint.decimals,
+            &[&generate_gamm_pair_seeds!(pair)[..]],
+        )?;
+
+        // Bounty: collateral vault -> keeper
+        if keeper_bounty > 0 {
+            transfer_from_vault_to_user(
+                pair.to_account_info(),
+                collateral_vault.to_account_info(),
+                keeper_collateral_token_account.to_account_info(),
+                collateral_token_mint.to_account_info(),
+                token_program_info.clone(),
+                keeper_bounty,
+                collateral_token_mint.decimals,
+                &[&generate_gamm_pair_seeds!(pair)[..]],
+            )?;
+        }
+
+        // Remaining collateral: collateral vault -> owner
+        if owner_collateral_out > 0 {
+            transfer_from_vault_to_user(
+                pair.to_account_info(),
+                collateral_vault.to_account_info(),
+                owner_collateral_token_account.to_account_info(),
+                collateral_token_mint.to_account_info(),
+                token_program_info,
+                owner_collateral_out,
+                collateral_token_mint.decimals,
+                &[&generate_gamm_pair_seeds!(pair)[..]],
+            )?;
+        }
+
+        let (collateral0, collateral1) = match is_collateral_token0 {
+            true => (-(user_collateral as i64), 0),
+            false => (0, -(user_collateral as i64)),
+        };
+        emit_cpi!(AdjustCollateralEvent {
+            metadata: EventMetadata::new(keeper.key(), pair.key()),
+            amount0: collateral0,
+            amount1: collateral1,
+        });
+
+        let (debt0, debt1) = match is_collateral_token0 {
+            true => (0, -(debt_repaid as i64)),
+            false => (-(debt_repaid as i64), 0),
+        };
+        emit_cpi!(AdjustDebtEvent {
+            metadata: EventMetadata::new(keeper.key(), pair.key()),
+            amount0: debt0,
+            amount1: debt1,
+        });
+
+        emit_cpi!(SwapEvent {
+            metadata: EventMetadata::new(keeper.key(), pair.key()),
+            reserve0: pair.reserve0,
+            reserve1: pair.reserve1,
+            is_token0_in: is_collateral_token0,
+            is_exact_out: true,
+            amount_in: amounts.amount_in,
+            amount_out: amounts.amount_out,
+            amount_in_after_fee: amounts.amount_in_after_fee,
+            lp_fee: amounts.lp_fee,
+            protocol_fee: amounts.protocol_fee,
+        });
+
+        emit_cpi!(UserPositionUpdatedEvent {
+            metadata: EventMetadata::new(keeper.key(), pair.key()),
+            position: user_position.key(),
+            owner: user_position.owner,
+            collateral0: user_position.collateral0,
+            collateral1: user_position.collateral1,
+            debt0_shares: user_position.debt0_shares,
+            debt1_shares: user_position.debt1_shares,
+            collateral0_max_cf_bps: user_position.get_max_cf_bps_for_debt_token(pair, &pair.token1),
+            collateral1_max_cf_bps: user_position.get_max_cf_bps_for_debt_token(pair, &pair.token0),
+            collateral0_liquidation_cf_bps: user_position.collateral0_liquidation_cf_bps,
+            collateral1_liquidation_cf_bps: user_position.collateral1_liquidation_cf_bps,
+        });
+
+        emit_cpi!(PositionOrderClosedEvent {
+            metadata: EventMetadata::new(keeper.key(), pair.key()),
+            order: position_order.key(),
+            position: user_position.key(),
+            executed: true,
+            collateral_out: owner_collateral_out,
+            keeper_bounty,
+        });
+
+        Ok(())
+    }
+}
@@ -46,9 +46,15 @@ pub struct Flashloan<'info> {
 
     #[account(
         mut,
-        address = pair.rate_model,
+        address = pair.rate_model0,
     )]
-    pub rate_model: Account<'info, RateModel>,
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Account<'info, RateModel>,
 
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
@@ -142,7 +148,8 @@ impl<'info> Flashloan<'info> {
     pub fn update(&mut self) -> Result<()> {
         let pair_key = self.pair.to_account_info().key();
         self.pair.update(
-            &self.rate_model,
+            &self.rate_model0,
+            &self.rate_model1,
             &self.futarchy_authority,
             pair_key,
             Some(self.event_authority.to_account_info()),
@@ -174,15 +181,15 @@ impl<'info> Flashloan<'info> {
 
         let FlashloanArgs { amount0, amount1, data } = args;
 
-        // Calculate fees (5 bps = 0.05%)
+        // Calculate fees (5 bps = 0.05% by default)
         let fee0 = ceil_div((amount0 as u128)
-            .checked_mul(FLASHLOAN_FEE_BPS as u128)
+            .checked_mul(pair.risk_params.flashloan_fee_bps as u128)
             .ok_or(ErrorCode::FeeMathOverflow)?,
             BPS_DENOMINATOR as u128,
         ).ok_or(ErrorCode::FeeMathOverflow)? as u64;
         
         let fee1 = ceil_div((amount1 as u128)
-            .checked_mul(FLASHLOAN_FEE_BPS as u128)
+            .checked_mul(pair.risk_params.flashloan_fee_bps as u128)
             .ok_or(ErrorCode::FeeMathOverflow)?,
             BPS_DENOMINATOR as u128,
         ).ok_or(ErrorCode::FeeMathOverflow)? as u64;
@@ -7,10 +7,11 @@ use anchor_spl::{
 use crate::{
     state::pair::Pair,
     state::rate_model::RateModel,
+    state::risk_params::RiskParams,
     state::futarchy_authority::FutarchyAuthority,
     constants::*,
     errors::ErrorCode,
-    events::{AdjustDebtEvent, EventMetadata, UserPositionLiquidatedEvent, UserPositionUpdatedEvent},
+    events::{AdjustDebtEvent, BadDebtEvent, EventMetadata, UserPositionLiquidatedEvent, UserPositionUpdatedEvent},
     state::user_position::{UserPosition, DebtDecreaseReason},
     utils::{
         token::{transfer_from_vault_to_user, transfer_from_vault_to_vault}, 
@@ -40,7 +41,8 @@ pub struct Liquidate<'info> {
         seeds = [
             POSITION_SEED_PREFIX,
             pair.key().as_ref(),
-            position_owner.key().as_ref()
+            position_owner.key().as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
         ],
         bump = user_position.bump
     )]
@@ -48,9 +50,15 @@ pub struct Liquidate<'info> {
 
     #[account(
         mut,
-        address = pair.rate_model,
+        address = pair.rate_model0,
     )]
-    pub rate_model: Account<'info, RateModel>,
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Account<'info, RateModel>,
 
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
@@ -100,114 +108,59 @@ pub struct Liquidate<'info> {
     pub system_program: Program<'info, System>,
 }
 
-impl<'info> Liquidate<'info> {
-    pub fn validate(&self) -> Result<()> {
-        let user_position = &self.user_position;
-
-        require!(user_position.is_initialized(), ErrorCode::UserPositionNotInitialized);
-        
-        // Check if user has enough debt
-        match self.collateral_token_mint.key() == self.pair.token0 {
-            true => require_gt!(
-                user_position.debt1_shares,
-                0,
-                ErrorCode::ZeroDebtAmount
-            ),
-            false => require_gt!(
-                user_position.debt0_shares,
-                0,
-                ErrorCode::ZeroDebtAmount
-            ),
-        }
-        
-        Ok(())
-    }
-
-    pub fn update(&mut self) -> Result<()> {
-        let pair_key = self.pair.to_account_info().key();
-        self.pair.update(
-            &self.rate_model,
-            &self.futarchy_authority,
-            pair_key,
-            Some(self.event_authority.to_account_info()),
-        )?;
-        Ok(())
-    }
-
-    pub fn update_and_validate_liquidate(&mut self) -> Result<()> {
-        self.update()?;
-        self.validate()?;
-        Ok(())
-    }
-
-    pub fn handle_liquidate(ctx: Context<Self>) -> Result<()> {
-        let Liquidate {
-            collateral_vault,
-            caller_token_account,
-            collateral_token_mint,
-            reserve_vault,
-            position_owner,
-            payer,
-            user_position,
-            token_program,
-            token_2022_program,
-            ..
-        } = ctx.accounts;
-        let pair = &mut ctx.accounts.pair;
-        
-        // Validate collateral vault and pool vault - already validated by Anchor seeds
-        require_keys_eq!(
-            collateral_vault.mint,
-            collateral_token_mint.key(),
-            ErrorCode::InvalidVault
-        );
-        require_keys_eq!(
-            reserve_vault.mint,
-            collateral_token_mint.key(),
-            ErrorCode::InvalidVault
-        );
-        require_keys_eq!(
-            reserve_vault.owner,
-            pair.key(),
-            ErrorCode::InvalidVault
-        );
+/// Resolved amounts of a liquidation of one debt side of a position, computed at the collateral EMA price.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LiquidationAmounts {
+    /// Debt of the position before the liquidation
+    pub user_debt: u64,
+    /// Debt coverable by selling all collateral at the EMA price, with price impact
+    pub collateral_value_with_impact: u64,
+    pub shares_to_writeoff: u128,
+    pub debt_to_writeoff: u64,
+    /// Collateral worth `debt_to_writeoff` at the EMA price, with price impact
+    pub collateral_base: u64,
+    /// Collateral taken from the position: base + penalty, clamped to the position's collateral
+    pub collateral_seized: u64,
+    /// Liquidator incentive rate, scaled by how far the debt exceeds the borrow limit
+    pub incentive_bps: u16,
+    /// Part of the seized collateral paid to the liquidator
+    pub caller_incentive: u64,
+    /// Part of the seized collateral added to the reserves
+    pub collateral_to_reserves: u64,
+    /// Part of the seized collateral added to the insurance reserve
+    pub collateral_to_insurance: u64,
+    /// Debt not covered by the collateral, 0 if solvent
+    pub bad_debt: u64,
+    /// Part of `bad_debt` covered by the insurance reserve, the rest is socialized to LPs
+    pub bad_debt_covered: u64,
+    /// Liquidation CF locked in for the remaining collateral
+    pub liquidation_cf_bps: u16,
+    /// Cross margin only: collateral repaid at par against debt of the same token before the liquidation
+    pub collateral_netted: u64,
+}
 
-        let collateral_token = collateral_token_mint.key();
-        let debt_token = if collateral_token == pair.token0 { pair.token1 } else { pair.token0 };
-        let is_collateral_token0 = collateral_token == pair.token0;
+impl LiquidationAmounts {
+    /// Fails with `NotUndercollateralized` if the position is healthy.
+    pub fn new(pair: &Pair, user_position: &UserPosition, is_collateral_token0: bool) -> Result<Self> {
+        let debt_token = if is_collateral_token0 { pair.token1 } else { pair.token0 };
         let liquidation_cf_bps = user_position.get_liquidation_cf_bps(pair, &debt_token)?;
-        let k0 = pair.k(); // k before liquidation
 
         // Compute debt
-        let (
-            user_debt, 
-            debt_reserve, 
-            user_collateral, 
-            collateral_reserve, 
-            collateral_ema_nad
-        )  = match is_collateral_token0 {
+        let (user_debt, user_collateral) = match is_collateral_token0 {
+            // collateral is token0, debt is token1
             true => (
-                // collateral is token0, debt is token1
                 user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?,
-                pair.reserve1, 
-                user_position.collateral0, 
-                pair.reserve0, 
-                pair.ema_price0_nad()
+                user_position.collateral0,
             ),
+            // collateral is token1, debt is token0
             false => (
-                // collateral is token1, debt is token0
                 user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?,
-                pair.reserve0,
-                user_position.collateral1, 
-                pair.reserve1, 
-                pair.ema_price1_nad()
+                user_position.collateral1,
             ),
         };
 
         // Construct virtual reserves at pessimistic price
-        let (collateral_ema_reserve, debt_ema_reserve) = construct_virtual_reserves_at_pessimistic_price(
-            collateral_reserve, debt_reserve, collateral_ema_nad, collateral_ema_nad
-        )?;
+        let (collateral_ema_reserve, debt_ema_reserve) = Self::pricing_reserves(pair, is_collateral_token0)?;
 
         // Collateral value with impact: debt coverable by selling all collateral
         let collateral_value_with_impact = CPCurve::calculate_amount_out(collateral_ema_reserve, debt_ema_reserve, user_collateral)?;
@@ -219,6 +172,7 @@ impl<'info> Liquidate<'info> {
 
         // Position is liquidatable if debt >= borrow_limit
         require_gte!(user_debt as u128, borrow_limit, ErrorCode::NotUndercollateralized);
+        let incentive_bps = Self::incentive_bps(&pair.risk_params, user_debt, borrow_limit)?;
  
         // Health Factor (HF) < 1: undercollateralized (liquidatable)
         // collateral_value > user_debt > borrow_limit: position can be liquidated partially
@@ -233,7 +187,7 @@ impl<'info> Liquidate<'info> {
         };
 
         // Calculate shares to writeoff first
-        // For partial liquidation: ceil(user_debt_shares * CLOSE_FACTOR_BPS / BPS_DENOMINATOR)
+        // For partial liquidation: ceil(user_debt_shares * close_factor_bps / BPS_DENOMINATOR)
         // For insolvent positions: all user debt shares
         let shares_to_writeoff: u128 = match is_insolvent {
             true => user_debt_shares,
@@ -241,7 +195,7 @@ impl<'info> Liquidate<'info> {
                 // ceiled division to avoid edge case where small shares never get fully written off
                 let partial_shares = ceil_div(
                     user_debt_shares
-                        .checked_mul(CLOSE_FACTOR_BPS as u128).ok_or(ErrorCode::DebtMathOverflow)?,
+                        .checked_mul(pair.risk_params.close_factor_bps as u128).ok_or(ErrorCode::DebtMathOverflow)?,
                     BPS_DENOMINATOR as u128
                 ).ok_or(ErrorCode::DebtMathOverflow)?;
                 min(user_debt_shares, partial_shares) // clamped to user's shares
@@ -265,47 +219,346 @@ impl<'info> Liquidate<'info> {
         let collateral_base = CPCurve::calculate_amount_in(collateral_ema_reserve, debt_ema_reserve, debt_to_writeoff)?;
 
         // Add liquidation penalty on top (paid by borrower, benefits LPs)
-        // total_seized = base * (1 + LIQUIDATION_PENALTY_BPS / BPS)
+        // total_seized = base * (1 + liquidation_penalty_bps / BPS)
         let collateral_with_penalty = ceil_div(
             (collateral_base as u128)
-                .checked_mul((BPS_DENOMINATOR + LIQUIDATION_PENALTY_BPS) as u128)
+                .checked_mul((BPS_DENOMINATOR + pair.risk_params.liquidation_penalty_bps) as u128)
                 .ok_or(ErrorCode::DebtMathOverflow)?,
             BPS_DENOMINATOR as u128
         ).ok_or(ErrorCode::DebtMathOverflow)?;
 
         // Clamp to what user actually has
-        let collateral_final: u64 = min(collateral_with_penalty, user_collateral as u128)
+        let collateral_seized: u64 = min(collateral_with_penalty, user_collateral as u128)
             .try_into().map_err(|_| ErrorCode::DebtMathOverflow)?;
 
-        let collateral_token = pair.get_collateral_token(&debt_token);
-        let collateral_amount_pre_liquidation = match collateral_token == pair.token0 {
-            true => user_position.collateral0,
-            false => user_position.collateral1,
-        };
-
-        let collateral_amount_post_liquidation = collateral_amount_pre_liquidation
-            .checked_sub(collateral_final)
+        let collateral_amount_post_liquidation = user_collateral
+            .checked_sub(collateral_seized)
             .ok_or(ErrorCode::DebtMathOverflow)?;
-        let (_, _, liquidation_cf_bps) = pair.get_max_debt_and_cf_bps_for_collateral(&pair, &collateral_token, collateral_amount_post_liquidation)?;
+        let collateral_token = pair.get_collateral_token(&debt_token);
+        let (_, _, liquidation_cf_bps) = pair.get_max_debt_and_cf_bps_for_collateral(pair, &collateral_token, collateral_amount_post_liquidation)?;
 
         // Liquidator incentive from base amount (not from penalty)
         let caller_incentive: u64 = min(
             (collateral_base as u128)
-                .checked_mul(LIQUIDATION_INCENTIVE_BPS as u128).ok_or(ErrorCode::DebtMathOverflow)?
+                .checked_mul(incentive_bps as u128).ok_or(ErrorCode::DebtMathOverflow)?
                 .checked_div(BPS_DENOMINATOR as u128).ok_or(ErrorCode::DebtMathOverflow)?
                 .try_into().map_err(|_| ErrorCode::DebtMathOverflow)?,
-            collateral_final
+            collateral_seized
         );
         
-        // Remaining collateral goes to reserves (LPs get base + penalty - incentive)
-        let collateral_to_reserves = collateral_final
+        // Remaining collateral goes to reserves (LPs get base + penalty - incentive),
+        // except the insurance share of the LPs' part of the penalty
+        let lp_penalty = collateral_seized
+            .saturating_sub(collateral_base)
+            .saturating_sub(caller_incentive);
+        let collateral_to_insurance = penalty_to_insurance(pair, lp_penalty);
+        let collateral_to_reserves = collateral_seized
             .checked_sub(caller_incentive)
+            .and_then(|amount| amount.checked_sub(collateral_to_insurance))
             .ok_or(ErrorCode::DebtMathOverflow)?;
 
+        // Insolvent positions leave bad debt, covered by the insurance reserve of the debt token first
+        let bad_debt = user_debt.saturating_sub(collateral_value_with_impact);
+        let bad_debt_covered = bad_debt.min(match is_collateral_token0 {
+            true => pair.insurance_reserve1,
+            false => pair.insurance_reserve0,
+        });
+
+        Ok(Self {
+            user_debt,
+            collateral_value_with_impact,
+            shares_to_writeoff,
+            debt_to_writeoff,
+            collateral_base,
+            collateral_seized,
+            incentive_bps,
+            caller_incentive,
+            collateral_to_reserves,
+            collateral_to_insurance,
+            bad_debt,
+            bad_debt_covered,
+            liquidation_cf_bps,
+            collateral_netted: 0,
+        })
+    }
+
+    /// Liquidator incentive for a debt of `user_debt` against a borrow limit of `borrow_limit`.
+    ///
+    /// Starts at `liquidation_incentive_bps` at the limit and grows by `liquidation_incentive_slope_bps`
+    /// of the relative excess, up to `max_liquidation_incentive_bps`. Deeper positions pay more,
+    /// so they stay worth liquidating when priority fees spike.
+    pub fn incentive_bps(risk_params: &RiskParams, user_debt: u64, borrow_limit: u128) -> Result<u16> {
+        if borrow_limit == 0 {
+            return Ok(risk_params.max_liquidation_incentive_bps);
+        }
+        let excess_bps = (user_debt as u128)
+            .saturating_sub(borrow_limit)
+            .checked_mul(BPS_DENOMINATOR as u128).ok_or(ErrorCode::DebtMathOverflow)?
+            / borrow_limit;
+        let incentive_bps = (risk_params.liquidation_incentive_bps as u128).saturating_add(
+            excess_bps.saturating_mul(risk_params.liquidation_incentive_slope_bps as u128) / BPS_DENOMINATOR as u128
+        );
+        Ok(min(incentive_bps, risk_params.max_liquidation_incentive_bps as u128) as u16)
+    }
+
+    /// Collateral and debt virtual reserves at the collateral EMA price, which price the seized collateral.
+    pub fn pricing_reserves(pair: &Pair, is_collateral_token0: bool) -> Result<(u64, u64)> {
+        let (collateral_reserve, debt_reserve, collateral_ema_nad) = match is_collateral_token0 {
+            true => (pair.reserve0, pair.reserve1, pair.ema_price0_nad()),
+            false => (pair.reserve1, pair.reserve0, pair.ema_price1_nad()),
+        };
+        construct_virtual_reserves_at_pessimistic_price(
+            collateral_reserve, debt_reserve, collateral_ema_nad, collateral_ema_nad
+        )
+    }
+
+    /// Debt not covered by the collateral, 0 if solvent.
+    pub fn shortfall(&self) -> u128 {
+        self.bad_debt as u128
+    }
+
+    /// Part of the bad debt lost by LPs.
+    pub fn socialized_debt(&self) -> u64 {
+        self.bad_debt - self.bad_debt_covered
+    }
+
+    pub fn apply(&self, pair: &mut Pair, user_position: &mut UserPosition, is_collateral_token0: bool) -> Result<()> {
+        let debt_token = if is_collateral_token0 { pair.token1 } else { pair.token0 };
 
         // Pass exact shares to writeoff to avoid edge cases where floor division leaves residual shares
-        user_position.decrease_debt(pair, &debt_token, debt_to_writeoff, DebtDecreaseReason::WriteOff(shares_to_writeoff))?;
-        user_position.set_liquidation_cf_for_debt_token(&debt_token, &pair, liquidation_cf_bps);
+        user_position.decrease_debt(pair, &debt_token, self.debt_to_writeoff, DebtDecreaseReason::WriteOff(self.shares_to_writeoff))?;
+        user_position.set_liquidation_cf_for_debt_token(&debt_token, pair, self.liquidation_cf_bps);
+        if self.bad_debt > 0 {
+            pair.cover_bad_debt(&debt_token, self.bad_debt);
+        }
+
+        seize_collateral(
+            pair,
+            user_position,
+            is_collateral_token0,
+            self.collateral_seized,
+            self.collateral_to_reserves,
+            self.collateral_to_insurance,
+        );
+        Ok(())
+    }
+}
+
+/// Checks of `Liquidate::validate` on the pair and position.
+///
+/// A cross-margin position is liquidated on its net exposure, one token at a time: with `collateral_token`
+/// its collateral of that token is first netted against its debt of that token, and the debt of the other
+/// token is only liquidated once the other token has no collateral left to net against it.
+pub fn validate_liquidation(pair: &Pair, user_position: &UserPosition, is_collateral_token0: bool) -> Result<()> {
+    require!(user_position.is_initialized(), ErrorCode::UserPositionNotInitialized);
+
+    if user_position.cross_margin {
+        require_net_exposure_liquidatable(pair, user_position)?;
+        let (collateral, debt_shares) = match is_collateral_token0 {
+            true => (user_position.collateral0, user_position.debt0_shares),
+            false => (user_position.collateral1, user_position.debt1_shares),
+        };
+        require!(
+            (collateral > 0 && debt_shares > 0) || liquidates_other_debt(user_position, is_collateral_token0),
+            ErrorCode::CrossMarginNettingRequired
+        );
+        return Ok(());
+    }
+
+    // Check if user has enough debt
+    match is_collateral_token0 {
+        true => require_gt!(
+            user_position.debt1_shares,
+            0,
+            ErrorCode::ZeroDebtAmount
+        ),
+        false => require_gt!(
+            user_position.debt0_shares,
+            0,
+            ErrorCode::ZeroDebtAmount
+        ),
+    }
+
+    Ok(())
+}
+
+/// Fails with `NotUndercollateralized` unless the debt of the token in deficit of a cross-margin position
+/// reaches the liquidation limit of the token in surplus, valued as in `LiquidationAmounts::new`.
+pub fn require_net_exposure_liquidatable(pair: &Pair, user_position: &UserPosition) -> Result<()> {
+    let Some((is_collateral_token0, collateral, debt)) = user_position.net_exposure(pair)?.deficit(pair.ema_price0_nad()) else {
+        return err!(ErrorCode::NotUndercollateralized);
+    };
+    let debt_token = if is_collateral_token0 { pair.token1 } else { pair.token0 };
+    let liquidation_cf_bps = user_position.get_liquidation_cf_bps(pair, &debt_token)?;
+
+    let (collateral_ema_reserve, debt_ema_reserve) = LiquidationAmounts::pricing_reserves(pair, is_collateral_token0)?;
+    let collateral_value_with_impact = CPCurve::calculate_amount_out(collateral_ema_reserve, debt_ema_reserve, collateral)?;
+    let liquidation_limit = (collateral_value_with_impact as u128)
+        .checked_mul(liquidation_cf_bps as u128).ok_or(ErrorCode::DebtMathOverflow)?
+        .checked_div(BPS_DENOMINATOR as u128).ok_or(ErrorCode::DebtMathOverflow)?;
+    require_gte!(debt as u128, liquidation_limit, ErrorCode::NotUndercollateralized);
+    Ok(())
+}
+
+/// Whether a liquidation with `is_collateral_token0` as collateral writes off debt of the other token.
+/// Always for isolated positions; for cross-margin ones once the other token has no collateral to net.
+pub fn liquidates_other_debt(user_position: &UserPosition, is_collateral_token0: bool) -> bool {
+    let (other_collateral, other_debt_shares) = match is_collateral_token0 {
+        true => (user_position.collateral1, user_position.debt1_shares),
+        false => (user_position.collateral0, user_position.debt0_shares),
+    };
+    !user_position.cross_margin || (other_collateral == 0 && other_debt_shares > 0)
+}
+
+/// Repays the debt of a cross-margin position with its collateral of the same token, at par.
+/// Returns the amount netted, which moves from the collateral vault to the reserve vault.
+pub fn net_collateral_against_debt(pair: &mut Pair, user_position: &mut UserPosition, is_token0: bool) -> Result<u64> {
+    let (token, collateral, debt) = match is_token0 {
+        true => (pair.token0, user_position.collateral0, user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?),
+        false => (pair.token1, user_position.collateral1, user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?),
+    };
+    let netted = min(collateral, debt);
+    if netted == 0 {
+        return Ok(0);
+    }
+
+    user_position.decrease_debt(pair, &token, netted, DebtDecreaseReason::Repayment)?;
+    match is_token0 {
+        true => {
+            user_position.collateral0 -= netted;
+            pair.total_collateral0 = pair.total_collateral0.checked_sub(netted).ok_or(ErrorCode::Overflow)?;
+        }
+        false => {
+            user_position.collateral1 -= netted;
+            pair.total_collateral1 = pair.total_collateral1.checked_sub(netted).ok_or(ErrorCode::Overflow)?;
+        }
+    }
+    Ok(netted)
+}
+
+/// State transition of a `liquidate`, after `validate_liquidation`: nets the collateral of a cross-margin
+/// position first, then liquidates the debt of the other token if `liquidates_other_debt`.
+pub fn apply_liquidation(pair: &mut Pair, user_position: &mut UserPosition, is_collateral_token0: bool) -> Result<LiquidationAmounts> {
+    let collateral_netted = match user_position.cross_margin {
+        true => net_collateral_against_debt(pair, user_position, is_collateral_token0)?,
+        false => 0,
+    };
+
+    let mut amounts = LiquidationAmounts::default();
+    if liquidates_other_debt(user_position, is_collateral_token0) {
+        amounts = LiquidationAmounts::new(pair, user_position, is_collateral_token0)?;
+        amounts.apply(pair, user_position, is_collateral_token0)?;
+    }
+    amounts.collateral_netted = collateral_netted;
+    Ok(amounts)
+}
+
+/// Insurance share of `lp_penalty`, the part of a liquidation penalty that would go to LPs.
+pub fn penalty_to_insurance(pair: &Pair, lp_penalty: u64) -> u64 {
+    ((lp_penalty as u128) * pair.insurance_penalty_share_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+/// Takes `collateral_seized` from the position; `collateral_to_reserves` of it goes to the reserves,
+/// `collateral_to_insurance` to the insurance reserve and the rest leaves the pair (to the liquidator).
+pub fn seize_collateral(
+    pair: &mut Pair,
+    user_position: &mut UserPosition,
+    is_collateral_token0: bool,
+    collateral_seized: u64,
+    collateral_to_reserves: u64,
+    collateral_to_insurance: u64,
+) {
+    // Update user position collateral and pair reserves
+    // Subtract the full seized amount from user position
+    match is_collateral_token0 {
+        true => {
+            user_position.collateral0 = user_position.collateral0.checked_sub(collateral_seized).unwrap();
+            pair.total_collateral0 = pair.total_collateral0.checked_sub(collateral_seized).unwrap();
+            // Add the collateral not paid to the liquidator to reserves
+            pair.reserve0 = pair.reserve0.checked_add(collateral_to_reserves).unwrap();
+            pair.cash_reserve0 = pair.cash_reserve0.saturating_add(collateral_to_reserves);
+        }
+        false => {
+            user_position.collateral1 = user_position.collateral1.checked_sub(collateral_seized).unwrap();
+            pair.total_collateral1 = pair.total_collateral1.checked_sub(collateral_seized).unwrap();
+            // Add the collateral not paid to the liquidator to reserves
+            pair.reserve1 = pair.reserve1.checked_add(collateral_to_reserves).unwrap();
+            pair.cash_reserve1 = pair.cash_reserve1.saturating_add(collateral_to_reserves);
+        }
+    }
+    pair.add_to_insurance(is_collateral_token0, collateral_to_insurance);
+}
+
+impl<'info> Liquidate<'info> {
+    pub fn validate(&self) -> Result<()> {
+        let is_collateral_token0 = self.collateral_token_mint.key() == self.pair.token0;
+        validate_liquidation(&self.pair, &self.user_position, is_collateral_token0)
+    }
+
+    pub fn update(&mut self) -> Result<()> {
+        let pair_key = self.pair.to_account_info().key();
+        self.pair.update(
+            &self.rate_model0,
+            &self.rate_model1,
+            &self.futarchy_authority,
+            pair_key,
+            Some(self.event_authority.to_account_info()),
+        )?;
+        Ok(())
+    }
+
+    pub fn update_and_validate_liquidate(&mut self) -> Result<()> {
+        self.update()?;
+        self.validate()?;
+        Ok(())
+    }
+
+    pub fn handle_liquidate(ctx: Context<Self>) -> Result<()> {
+        let Liquidate {
+            collateral_vault,
+            caller_token_account,
+            collateral_token_mint,
+            reserve_vault,
+            position_owner,
+            payer,
+            user_position,
+            token_program,
+            token_2022_program,
+            ..
+        } = ctx.accounts;
+        let pair = &mut ctx.accounts.pair;
+        
+        // Validate collateral vault and pool vault - already validated by Anchor seeds
+        require_keys_eq!(
+            collateral_vault.mint,
+            collateral_token_mint.key(),
+            ErrorCode::InvalidVault
+        );
+        require_keys_eq!(
+            reserve_vault.mint,
+            collateral_token_mint.key(),
+            ErrorCode::InvalidVault
+        );
+        require_keys_eq!(
+            reserve_vault.owner,
+            pair.key(),
+            ErrorCode::InvalidVault
+        );
+
+        let collateral_token = collateral_token_mint.key();
+        let is_collateral_token0 = collateral_token == pair.token0;
+        let k0 = pair.k(); // k before liquidation
+
+        let amounts = apply_liquidation(pair, user_position, is_collateral_token0)?;
+        let LiquidationAmounts {
+            debt_to_writeoff,
+            collateral_seized: collateral_final,
+            caller_incentive,
+            collateral_to_reserves,
+            collateral_to_insurance,
+            collateral_netted,
+            ..
+        } = amounts;
 
         // Transfer liquidation incentive to caller from collateral vault
         if caller_incentive > 0 {
@@ -324,7 +577,7 @@ impl<'info> Liquidate<'info> {
             )?;
         }
 
-        // Transfer remaining collateral from collateral vault to reserve vault
+        // Transfer remaining collateral (reserves, insurance and netted) from collateral vault to reserve vault
         transfer_from_vault_to_vault(
             pair.to_account_info(),
             collateral_vault.to_account_info(),
@@ -334,36 +587,18 @@ impl<'info> Liquidate<'info> {
                 true => token_program.to_account_info(),
                 false => token_2022_program.to_account_info(),
             },
-            collateral_to_reserves,
+            collateral_to_reserves + collateral_to_insurance + collateral_netted,
             collateral_token_mint.decimals,
             &[&generate_gamm_pair_seeds!(pair)[..]],
         )?;
 
-        // Update user position collateral and pair reserves
-        // Subtract the full seized amount from user position
-        match is_collateral_token0 {
-            true => {
-                user_position.collateral0 = user_position.collateral0.checked_sub(collateral_final).unwrap();
-                pair.total_collateral0 = pair.total_collateral0.checked_sub(collateral_final).unwrap();
-                // Add remaining collateral (after incentive) to reserves
-                pair.reserve0 = pair.reserve0.checked_add(collateral_to_reserves).unwrap();
-                pair.cash_reserve0 = pair.cash_reserve0.saturating_add(collateral_to_reserves);
-            }
-            false => {
-                user_position.collateral1 = user_position.collateral1.checked_sub(collateral_final).unwrap();
-                pair.total_collateral1 = pair.total_collateral1.checked_sub(collateral_final).unwrap();
-                // Add remaining collateral (after incentive) to reserves
-                pair.reserve1 = pair.reserve1.checked_add(collateral_to_reserves).unwrap();
-                pair.cash_reserve1 = pair.cash_reserve1.saturating_add(collateral_to_reserves);
-            }
-        }
-
-        // Emit debt adjustment event (debt written off)
-        let (amount0, amount1) = if is_collateral_token0 {
-            (0, -(debt_to_writeoff as i64))
+        // Emit debt adjustment event (debt written off and netted)
+        let (debt0_liquidated, debt1_liquidated) = if is_collateral_token0 {
+            (collateral_netted, debt_to_writeoff)
         } else {
-            (-(debt_to_writeoff as i64), 0)
+            (debt_to_writeoff, collateral_netted)
         };
+        let (amount0, amount1) = (-(debt0_liquidated as i64), -(debt1_liquidated as i64));
         emit_cpi!(AdjustDebtEvent {
             metadata: EventMetadata::new(position_owner.key(), pair.key()),
             amount0,
@@ -374,6 +609,7 @@ impl<'info> Liquidate<'info> {
         emit_cpi!(UserPositionUpdatedEvent {
             metadata: EventMetadata::new(position_owner.key(), pair.key()),
             position: user_position.key(),
+            owner: user_position.owner,
             collateral0: user_position.collateral0,
             collateral1: user_position.collateral1,
             debt0_shares: user_position.debt0_shares,
@@ -388,17 +624,50 @@ impl<'info> Liquidate<'info> {
             metadata: EventMetadata::new(position_owner.key(), pair.key()),
             position: user_position.key(),
             liquidator: payer.key(),
-            collateral0_liquidated: if is_collateral_token0 { collateral_final } else { 0 },
-            collateral1_liquidated: if is_collateral_token0 { 0 } else { collateral_final },
-            debt0_liquidated: if is_collateral_token0 { 0 } else { debt_to_writeoff },
-            debt1_liquidated: if is_collateral_token0 { debt_to_writeoff } else { 0 },
+            collateral0_liquidated: if is_collateral_token0 { collateral_final + collateral_netted } else { 0 },
+            collateral1_liquidated: if is_collateral_token0 { 0 } else { collateral_final + collateral_netted },
+            debt0_liquidated,
+            debt1_liquidated,
             collateral_price: if is_collateral_token0 { pair.ema_price0_nad() } else { pair.ema_price1_nad() },
-            shortfall: if is_insolvent { (user_debt as u128).saturating_sub(collateral_value_with_impact as u128) } else { 0 },
+            shortfall: amounts.shortfall(),
             liquidation_bonus_applied: caller_incentive,
+            liquidation_incentive_bps: amounts.incentive_bps,
+            debt_repaid_by_liquidator: 0,
             k0: k0,
             k1: pair.k(),
         });
 
+        if amounts.bad_debt > 0 {
+            emit_cpi!(BadDebtEvent {
+                metadata: EventMetadata::new(position_owner.key(), pair.key()),
+                position: user_position.key(),
+                is_token0_debt: !is_collateral_token0,
+                bad_debt: amounts.bad_debt,
+                covered_by_insurance: amounts.bad_debt_covered,
+                socialized: amounts.socialized_debt(),
+                insurance_reserve: if is_collateral_token0 { pair.insurance_reserve1 } else { pair.insurance_reserve0 },
+                total_bad_debt: if is_collateral_token0 { pair.total_bad_debt1 } else { pair.total_bad_debt0 },
+                total_socialized_debt: if is_collateral_token0 { pair.total_socialized_debt1 } else { pair.total_socialized_debt0 },
+            });
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incentive_scales_with_excess_debt_up_to_cap() {
+        let risk_params = RiskParams::default();
+        // At the borrow limit: base incentive
+        assert_eq!(LiquidationAmounts::incentive_bps(&risk_params, 1_000_000, 1_000_000).unwrap(), DEFAULT_LIQUIDATION_INCENTIVE_BPS);
+        // 5% over the limit: +1%
+        assert_eq!(LiquidationAmounts::incentive_bps(&risk_params, 1_050_000, 1_000_000).unwrap(), DEFAULT_LIQUIDATION_INCENTIVE_BPS + 100);
+        // Deep underwater and zero borrow limit: capped
+        assert_eq!(LiquidationAmounts::incentive_bps(&risk_params, 2_000_000, 1_000_000).unwrap(), DEFAULT_MAX_LIQUIDATION_INCENTIVE_BPS);
+        assert_eq!(LiquidationAmounts::incentive_bps(&risk_params, 1, 0).unwrap(), DEFAULT_MAX_LIQUIDATION_INCENTIVE_BPS);
+    }
+}
@@ -0,0 +1,647 @@
+use anchor_lang::prelude::*;
+use std::cmp::min;
+use anchor_spl::{
+    token::{Token, TokenAccount, Mint},
+    token_interface::{Token2022},
+};
+use crate::{
+    state::pair::Pair,
+    state::rate_model::RateModel,
+    state::futarchy_authority::FutarchyAuthority,
+    constants::*,
+    errors::ErrorCode,
+    events::{AdjustDebtEvent, BadDebtEvent, EventMetadata, UserPositionLiquidatedEvent, UserPositionUpdatedEvent},
+    state::user_position::{UserPosition, DebtDecreaseReason},
+    instructions::lending::liquidate::{
+        LiquidationAmounts, penalty_to_insurance, require_net_exposure_liquidatable, seize_collateral,
+    },
+    utils::{
+        token::{transfer_from_user_to_vault, transfer_from_vault_to_user, transfer_from_vault_to_vault},
+        math::ceil_div,
+        gamm_math::CPCurve,
+    },
+    generate_gamm_pair_seeds,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LiquidateWithRepayArgs {
+    /// Debt repaid by the liquidator, `u64::MAX` repays the most allowed by the close factor
+    pub repay_amount: u64,
+    /// Lower bound on the collateral paid to the liquidator
+    pub min_collateral_out: u64,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LiquidateWithRepay<'info> {
+    #[account(
+        mut,
+        seeds = [
+            PAIR_SEED_PREFIX,
+            pair.token0.as_ref(),
+            pair.token1.as_ref(),
+            pair.params_hash.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Box<Account<'info, Pair>>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED_PREFIX,
+            pair.key().as_ref(),
+            position_owner.key().as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
+        ],
+        bump = user_position.bump
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+
+    #[account(
+        mut,
+        address = pair.rate_model0,
+    )]
+    pub rate_model0: Box<Account<'info, RateModel>>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Box<Account<'info, RateModel>>,
+
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Box<Account<'info, FutarchyAuthority>>,
+
+    #[account(
+        mut,
+        seeds = [
+            COLLATERAL_VAULT_SEED_PREFIX,
+            pair.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+        ],
+        bump = pair.get_collateral_vault_bump(&collateral_token_mint.key())
+    )]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RESERVE_VAULT_SEED_PREFIX,
+            pair.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+        ],
+        bump = pair.get_reserve_vault_bump(&collateral_token_mint.key())
+    )]
+    pub collateral_reserve_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RESERVE_VAULT_SEED_PREFIX,
+            pair.key().as_ref(),
+            debt_token_mint.key().as_ref(),
+        ],
+        bump = pair.get_reserve_vault_bump(&debt_token_mint.key())
+    )]
+    pub debt_reserve_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Receives the seized collateral
+    #[account(
+        mut,
+        constraint = caller_collateral_token_account.mint == collateral_token_mint.key() @ ErrorCode::InvalidTokenAccount,
+    )]
+    pub caller_collateral_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Pays the repaid debt
+    #[account(
+        mut,
+        constraint = caller_debt_token_account.mint == debt_token_mint.key() @ ErrorCode::InvalidTokenAccount,
+        token::authority = payer,
+    )]
+    pub caller_debt_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = collateral_token_mint.key() == pair.token0 || collateral_token_mint.key() == pair.token1 @ ErrorCode::InvalidVault
+    )]
+    pub collateral_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        constraint = debt_token_mint.key() == pair.get_debt_token(&collateral_token_mint.key()) @ ErrorCode::InvalidVault
+    )]
+    pub debt_token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: This is the owner of the position being liquidated.
+    #[account(address = user_position.owner)]
+    pub position_owner: AccountInfo<'info>,
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Resolved amounts of a liquidation in which the liquidator repays part of one debt side of a position
+/// and receives the matching collateral at the EMA price plus a bonus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RepayLiquidationAmounts {
+    /// Debt of the position before the liquidation
+    pub user_debt: u64,
+    /// Most debt the liquidator may repay: the close factor of the debt, or all of it if insolvent
+    pub max_repay: u64,
+    pub debt_repaid: u64,
+    /// Collateral worth `debt_repaid` at the EMA price, with price impact
+    pub collateral_base: u64,
+    /// Collateral taken from the position: base + penalty, clamped to the position's collateral
+    pub collateral_seized: u64,
+    /// Liquidator incentive rate, same as in a write-off liquidation of the position
+    pub incentive_bps: u16,
+    /// Part of the seized collateral paid to the liquidator: base + incentive
+    pub caller_collateral: u64,
+    /// Part of the seized collateral added to the reserves
+    pub collateral_to_reserves: u64,
+    /// Part of the seized collateral added to the insurance reserve
+    pub collateral_to_insurance: u64,
+    /// Debt left once the position's collateral is all seized, written off as bad debt
+    pub bad_debt: u64,
+    /// Part of `bad_debt` covered by the insurance reserve of the debt token
+    pub bad_debt_covered: u64,
+    /// Liquidation CF locked in for the remaining collateral
+    pub liquidation_cf_bps: u16,
+}
+
+impl RepayLiquidationAmounts {
+    /// Fails with `NotUndercollateralized` if the position is healthy
+    /// and with `RepayExceedsCloseFactor` if `repay_amount` is above `max_repay`.
+    pub fn new(pair: &Pair, user_position: &UserPosition, is_collateral_token0: bool, repay_amount: u64) -> Result<Self> {
+        // The write-off liquidation of the same side bounds the repayment
+        let write_off = LiquidationAmounts::new(pair, user_position, is_collateral_token0)?;
+        let max_repay = write_off.debt_to_writeoff;
+        let debt_repaid = if repay_amount == u64::MAX { max_repay } else { repay_amount };
+        require!(debt_repaid > 0, ErrorCode::AmountZero);
+        require_gte!(max_repay, debt_repaid, ErrorCode::RepayExceedsCloseFactor);
+
+        let user_collateral = match is_collateral_token0 {
+            true => user_position.collateral0,
+            false => user_position.collateral1,
+        };
+
+        // Collateral bought by the repaid debt at the pessimistic EMA price: Δx = Δy * x / (y - Δy)
+        let (collateral_ema_reserve, debt_ema_reserve) = LiquidationAmounts::pricing_reserves(pair, is_collateral_token0)?;
+        let collateral_base = CPCurve::calculate_amount_in(collateral_ema_reserve, debt_ema_reserve, debt_repaid)?;
+
+        // The borrower pays the same penalty as in a write-off liquidation
+        let collateral_with_penalty = ceil_div(
+            (collateral_base as u128)
+                .checked_mul((BPS_DENOMINATOR + pair.risk_params.liquidation_penalty_bps) as u128)
+                .ok_or(ErrorCode::DebtMathOverflow)?,
+            BPS_DENOMINATOR as u128
+        ).ok_or(ErrorCode::DebtMathOverflow)?;
+        let collateral_seized: u64 = min(collateral_with_penalty, user_collateral as u128)
+            .try_into().map_err(|_| ErrorCode::DebtMathOverflow)?;
+
+        // The liquidator gets the collateral it paid for plus the incentive,
+        // LPs and the insurance reserve the rest of the penalty
+        let caller_collateral: u64 = min(
+            (collateral_base as u128)
+                .checked_mul((BPS_DENOMINATOR + write_off.incentive_bps) as u128).ok_or(ErrorCode::DebtMathOverflow)?
+                .checked_div(BPS_DENOMINATOR as u128).ok_or(ErrorCode::DebtMathOverflow)?
+                .try_into().map_err(|_| ErrorCode::DebtMathOverflow)?,
+            collateral_seized
+        );
+        let collateral_to_insurance = penalty_to_insurance(pair, collateral_seized.saturating_sub(caller_collateral));
+        let collateral_to_reserves = collateral_seized
+            .checked_sub(caller_collateral)
+            .and_then(|amount| amount.checked_sub(collateral_to_insurance))
+            .ok_or(ErrorCode::DebtMathOverflow)?;
+
+        let collateral_amount_post_liquidation = user_collateral
+            .checked_sub(collateral_seized)
+            .ok_or(ErrorCode::DebtMathOverflow)?;
+        let debt_token = if is_collateral_token0 { pair.token1 } else { pair.token0 };
+        let collateral_token = pair.get_collateral_token(&debt_token);
+        let (_, _, liquidation_cf_bps) = pair.get_max_debt_and_cf_bps_for_collateral(pair, &collateral_token, collateral_amount_post_liquidation)?;
+
+        // Debt left without collateral can't be repaid anymore: it is written off as bad debt,
+        // covered by the insurance reserve of the debt token first
+        let bad_debt = match collateral_amount_post_liquidation {
+            0 => write_off.user_debt.saturating_sub(debt_repaid),
+            _ => 0,
+        };
+        let bad_debt_covered = bad_debt.min(match is_collateral_token0 {
+            true => pair.insurance_reserve1,
+            false => pair.insurance_reserve0,
+        });
+
+        Ok(Self {
+            user_debt: write_off.user_debt,
+            max_repay,
+            debt_repaid,
+            collateral_base,
+            collateral_seized,
+            incentive_bps: write_off.incentive_bps,
+            caller_collateral,
+            collateral_to_reserves,
+            collateral_to_insurance,
+            bad_debt,
+            bad_debt_covered,
+            liquidation_cf_bps,
+        })
+    }
+
+    /// Collateral paid to the liquidator on top of what the repaid debt buys.
+    pub fn caller_bonus(&self) -> u64 {
+        self.caller_collateral.saturating_sub(self.collateral_base)
+    }
+
+    /// Debt not covered by the collateral, 0 if none is left unpaid.
+    pub fn shortfall(&self) -> u128 {
+        self.bad_debt as u128
+    }
+
+    /// Part of the bad debt lost by LPs.
+    pub fn socialized_debt(&self) -> u64 {
+        self.bad_debt - self.bad_debt_covered
+    }
+
+    pub fn apply(&self, pair: &mut Pair, user_position: &mut UserPosition, is_collateral_token0: bool) -> Result<()> {
+        let debt_token = if is_collateral_token0 { pair.token1 } else { pair.token0 };
+
+        // Repaid debt replenishes the cash reserve
+        user_position.decrease_debt(pair, &debt_token, self.debt_repaid, DebtDecreaseReason::Repayment)?;
+        if self.bad_debt > 0 {
+            let remaining_shares = match is_collateral_token0 {
+                true => user_position.debt1_shares,
+                false => user_position.debt0_shares,
+            };
+            user_position.decrease_debt(pair, &debt_token, self.bad_debt, DebtDecreaseReason::WriteOff(remaining_shares))?;
+            pair.cover_bad_debt(&debt_token, self.bad_debt);
+        }
+        user_position.set_liquidation_cf_for_debt_token(&debt_token, pair, self.liquidation_cf_bps);
+
+        seize_collateral(
+            pair,
+            user_position,
+            is_collateral_token0,
+            self.collateral_seized,
+            self.collateral_to_reserves,
+            self.collateral_to_insurance,
+        );
+        Ok(())
+    }
+}
+
+/// Checks of `LiquidateWithRepay::validate` on the pair and position. A cross-margin position must be
+/// netted by `liquidate` first: no collateral of the debt token, and none of the collateral token left
+/// alongside debt of that token.
+pub fn validate_repay_liquidation(pair: &Pair, user_position: &UserPosition, is_collateral_token0: bool) -> Result<()> {
+    require!(user_position.is_initialized(), ErrorCode::UserPositionNotInitialized);
+
+    // Check if user has enough debt
+    match is_collateral_token0 {
+        true => require_gt!(
+            user_position.debt1_shares,
+            0,
+            ErrorCode::ZeroDebtAmount
+        ),
+        false => require_gt!(
+            user_position.debt0_shares,
+            0,
+            ErrorCode::ZeroDebtAmount
+        ),
+    }
+
+    if user_position.cross_margin {
+        require_net_exposure_liquidatable(pair, user_position)?;
+        let (collateral, debt_shares, other_collateral) = match is_collateral_token0 {
+            true => (user_position.collateral0, user_position.debt0_shares, user_position.collateral1),
+            false => (user_position.collateral1, user_position.debt1_shares, user_position.collateral0),
+        };
+        require!(
+            (collateral == 0 || debt_shares == 0) && other_collateral == 0,
+            ErrorCode::CrossMarginNettingRequired
+        );
+    }
+
+    Ok(())
+}
+
+impl<'info> LiquidateWithRepay<'info> {
+    pub fn validate(&self, args: &LiquidateWithRepayArgs) -> Result<()> {
+        require!(args.repay_amount > 0, ErrorCode::AmountZero);
+        let is_collateral_token0 = self.collateral_token_mint.key() == self.pair.token0;
+        validate_repay_liquidation(&self.pair, &self.user_position, is_collateral_token0)
+    }
+
+    pub fn update(&mut self) -> Result<()> {
+        let pair_key = self.pair.to_account_info().key();
+        self.pair.update(
+            &self.rate_model0,
+            &self.rate_model1,
+            &self.futarchy_authority,
+            pair_key,
+            Some(self.event_authority.to_account_info()),
+        )?;
+        Ok(())
+    }
+
+    pub fn update_and_validate_liquidate_with_repay(&mut self, args: &LiquidateWithRepayArgs) -> Result<()> {
+        self.update()?;
+        self.validate(args)?;
+        Ok(())
+    }
+
+    pub fn handle_liquidate_with_repay(ctx: Context<Self>, args: LiquidateWithRepayArgs) -> Result<()> {
+        let LiquidateWithRepay {
+            collateral_vault,
+            collateral_reserve_vault,
+            debt_reserve_vault,
+            caller_collateral_token_account,
+            caller_debt_token_account,
+            collateral_token_mint,
+            debt_token_mint,
+            position_owner,
+            payer,
+            user_position,
+            token_program,
+            token_2022_program,
+            ..
+        } = ctx.accounts;
+        let pair = &mut ctx.accounts.pair;
+
+        let is_collateral_token0 = collateral_token_mint.key() == pair.token0;
+        let k0 = pair.k(); // k before liquidation
+
+        let amounts = RepayLiquidationAmounts::new(pair, user_position, is_collateral_token0, args.repay_amount)?;
+        require_gte!(amounts.caller_collateral, args.min_collateral_out, ErrorCode::SlippageExceeded);
+        amounts.apply(pair, user_position, is_collateral_token0)?;
+
+        let token_program_for = |mint: &AccountInfo<'info>| match mint.owner == token_program.key {
+            true => token_program.to_account_info(),
+            false => token_2022_program.to_account_info(),
+        };
+
+        // Repayment from the liquidator to the debt reserve vault
+        transfer_from_user_to_vault(
+            payer.to_account_info(),
+            caller_debt_token_account.to_account_info(),
+            debt_reserve_vault.to_account_info(),
+            debt_token_mint.to_account_info(),
+            token_program_for(&debt_token_mint.to_account_info()),
+            amounts.debt_repaid,
+            debt_token_mint.decimals,
+        )?;
+
+        // Seized collateral to the liquidator
+        if amounts.caller_collateral > 0 {
+            transfer_from_vault_to_user(
+                pair.to_account_info(),
+                collateral_vault.to_account_info(),
+                caller_collateral_token_account.to_account_info(),
+                collateral_token_mint.to_account_info(),
+                token_program_for(&collateral_token_mint.to_account_info()),
+                amounts.caller_collateral,
+                collateral_token_mint.decimals,
+                &[&generate_gamm_pair_seeds!(pair)[..]],
+            )?;
+        }
+
+        // Rest of the penalty (reserves and insurance) from the collateral vault to the reserve vault
+        transfer_from_vault_to_vault(
+            pair.to_account_info(),
+            collateral_vault.to_account_info(),
+            collateral_reserve_vault.to_account_info(),
+            collateral_token_mint.to_account_info(),
+            token_program_for(&collateral_token_mint.to_account_info()),
+            amounts.collateral_to_reserves + amounts.collateral_to_insurance,
+            collateral_token_mint.decimals,
+            &[&generate_gamm_pair_seeds!(pair)[..]],
+        )?;
+
+        // Emit debt adjustment event (debt repaid)
+        let (amount0, amount1) = if is_collateral_token0 {
+            (0, -(amounts.debt_repaid as i64))
+        } else {
+            (-(amounts.debt_repaid as i64), 0)
+        };
+        emit_cpi!(AdjustDebtEvent {
+            metadata: EventMetadata::new(position_owner.key(), pair.key()),
+            amount0,
+            amount1,
+        });
+
+        // Emit position updated event
+        emit_cpi!(UserPositionUpdatedEvent {
+            metadata: EventMetadata::new(position_owner.key(), pair.key()),
+            position: user_position.key(),
+            owner: user_position.owner,
+            collateral0: user_position.collateral0,
+            collateral1: user_position.collateral1,
+            debt0_shares: user_position.debt0_shares,
+            debt1_shares: user_position.debt1_shares,
+            collateral0_max_cf_bps: user_position.get_max_cf_bps_for_debt_token(pair, &pair.token1),
+            collateral1_max_cf_bps: user_position.get_max_cf_bps_for_debt_token(pair, &pair.token0),
+            collateral0_liquidation_cf_bps: user_position.collateral0_liquidation_cf_bps,
+            collateral1_liquidation_cf_bps: user_position.collateral1_liquidation_cf_bps,
+        });
+
+        emit_cpi!(UserPositionLiquidatedEvent {
+            metadata: EventMetadata::new(position_owner.key(), pair.key()),
+            position: user_position.key(),
+            liquidator: payer.key(),
+            collateral0_liquidated: if is_collateral_token0 { amounts.collateral_seized } else { 0 },
+            collateral1_liquidated: if is_collateral_token0 { 0 } else { amounts.collateral_seized },
+            debt0_liquidated: if is_collateral_token0 { 0 } else { amounts.debt_repaid },
+            debt1_liquidated: if is_collateral_token0 { amounts.debt_repaid } else { 0 },
+            collateral_price: if is_collateral_token0 { pair.ema_price0_nad() } else { pair.ema_price1_nad() },
+            shortfall: amounts.shortfall(),
+            liquidation_bonus_applied: amounts.caller_bonus(),
+            liquidation_incentive_bps: amounts.incentive_bps,
+            debt_repaid_by_liquidator: amounts.debt_repaid,
+            k0,
+            k1: pair.k(),
+        });
+
+        if amounts.bad_debt > 0 {
+            emit_cpi!(BadDebtEvent {
+                metadata: EventMetadata::new(position_owner.key(), pair.key()),
+                position: user_position.key(),
+                is_token0_debt: !is_collateral_token0,
+                bad_debt: amounts.bad_debt,
+                covered_by_insurance: amounts.bad_debt_covered,
+                socialized: amounts.socialized_debt(),
+                insurance_reserve: if is_collateral_token0 { pair.insurance_reserve1 } else { pair.insurance_reserve0 },
+                total_bad_debt: if is_collateral_token0 { pair.total_bad_debt1 } else { pair.total_bad_debt0 },
+                total_socialized_debt: if is_collateral_token0 { pair.total_socialized_debt1 } else { pair.total_socialized_debt0 },
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::{
+            lending::{borrow::apply_borrow, liquidate::apply_liquidation},
+            SwapAmounts,
+        },
+        state::{DelegatePermission, RateModel, RiskParams, VaultBumps},
+    };
+
+    const RESERVE: u64 = 1_000_000_000;
+    const COLLATERAL: u64 = 100_000_000;
+
+    fn test_pair() -> Pair {
+        let mut pair = Pair::initialize(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            6,
+            6,
+            Pubkey::new_unique(),
+            30,
+            60_000,
+            Some(8_000),
+            0,
+            [0; 32],
+            VERSION,
+            1,
+            VaultBumps::default(),
+            0,
+            RiskParams::default(),
+        );
+        pair.reserve0 = RESERVE;
+        pair.reserve1 = RESERVE;
+        pair.cash_reserve0 = RESERVE;
+        pair.cash_reserve1 = RESERVE;
+        pair.last_price0_ema.symmetric = pair.spot_price0_nad();
+        pair.last_price0_ema.directional = pair.spot_price0_nad();
+        pair.last_price1_ema.symmetric = pair.spot_price1_nad();
+        pair.last_price1_ema.directional = pair.spot_price1_nad();
+        pair
+    }
+
+    fn test_position() -> UserPosition {
+        UserPosition {
+            owner: Pubkey::new_unique(),
+            pair: Pubkey::new_unique(),
+            collateral0_liquidation_cf_bps: 0,
+            collateral1_liquidation_cf_bps: 0,
+            collateral0: COLLATERAL,
+            collateral1: 0,
+            debt0_shares: 0,
+            debt1_shares: 0,
+            bump: 1,
+            cross_margin: false,
+            position_index: 0,
+            delegate: Pubkey::default(),
+            delegate_permission: DelegatePermission::None,
+            delegate_expiry_slot: 0,
+            open_orders: 0,
+        }
+    }
+
+    /// A position borrowing all it can of token1 against token0 collateral, after the token0 EMA fell
+    /// to a tenth: insolvent.
+    fn insolvent_position() -> (Pair, UserPosition) {
+        let mut pair = test_pair();
+        let mut user_position = test_position();
+        pair.total_collateral0 = COLLATERAL;
+        let token1 = pair.token1;
+        apply_borrow(&mut pair, &mut user_position, &token1, u64::MAX).unwrap();
+        pair.last_price0_ema.symmetric /= 10;
+        (pair, user_position)
+    }
+
+    #[test]
+    fn debt_left_after_seizing_all_collateral_is_recorded_as_bad_debt() {
+        let (mut pair, mut user_position) = insolvent_position();
+        let write_off = LiquidationAmounts::new(&pair, &user_position, true).unwrap();
+        assert!(write_off.bad_debt > 0);
+        let insurance_reserve1 = write_off.bad_debt / 2;
+        pair.insurance_reserve1 = insurance_reserve1;
+        let (reserve1, cash_reserve1) = (pair.reserve1, pair.cash_reserve1);
+
+        // Repaying what the collateral is worth takes all of it
+        let repay_amount = write_off.collateral_value_with_impact;
+        let amounts = RepayLiquidationAmounts::new(&pair, &user_position, true, repay_amount).unwrap();
+        assert_eq!(amounts.collateral_seized, COLLATERAL);
+        assert_eq!(amounts.bad_debt, write_off.user_debt - repay_amount);
+        assert_eq!(amounts.bad_debt_covered, insurance_reserve1);
+        assert_eq!(amounts.shortfall(), amounts.bad_debt as u128);
+        amounts.apply(&mut pair, &mut user_position, true).unwrap();
+
+        assert_eq!((user_position.collateral0, user_position.debt1_shares), (0, 0));
+        assert_eq!((pair.total_debt1, pair.total_debt1_shares), (0, 0));
+        assert_eq!(pair.total_bad_debt1, amounts.bad_debt);
+        assert_eq!(pair.total_socialized_debt1, amounts.socialized_debt());
+        assert_eq!(pair.insurance_reserve1, 0);
+        // The repayment and the insured part of the bad debt return to the cash reserve,
+        // the virtual reserve loses the socialized part
+        assert_eq!(pair.cash_reserve1, cash_reserve1 + repay_amount + insurance_reserve1);
+        assert_eq!(pair.reserve1, reserve1 - amounts.socialized_debt());
+    }
+
+    #[test]
+    fn partial_repayment_leaving_collateral_records_no_bad_debt() {
+        let (mut pair, mut user_position) = insolvent_position();
+        let write_off = LiquidationAmounts::new(&pair, &user_position, true).unwrap();
+
+        let amounts = RepayLiquidationAmounts::new(&pair, &user_position, true, write_off.collateral_value_with_impact / 2).unwrap();
+        assert!(amounts.collateral_seized < COLLATERAL);
+        assert_eq!((amounts.bad_debt, amounts.shortfall()), (0, 0));
+        amounts.apply(&mut pair, &mut user_position, true).unwrap();
+
+        assert!(user_position.debt1_shares > 0);
+        assert_eq!((pair.total_bad_debt1, pair.total_socialized_debt1), (0, 0));
+    }
+
+    #[test]
+    fn repay_liquidation_replenishes_cash_instead_of_writing_off() {
+        let rate_model = RateModel::new(
+            TARGET_UTIL_START_BPS,
+            TARGET_UTIL_END_BPS,
+            DEFAULT_RATE_HALF_LIFE_MS,
+            DEFAULT_MIN_RATE_BPS,
+            0,
+            DEFAULT_INITIAL_RATE_BPS,
+        );
+        let mut pair = test_pair();
+        let mut user_position = test_position();
+        pair.total_collateral0 = COLLATERAL;
+        let token1 = pair.token1;
+        apply_borrow(&mut pair, &mut user_position, &token1, u64::MAX).unwrap();
+        // Crash token0 and let the EMA catch up
+        SwapAmounts::exact_in(&pair, 0, true, RESERVE).unwrap().apply(&mut pair, true).unwrap();
+        pair.update_at(&rate_model, &rate_model, 0, pair.last_update + 100_000).unwrap();
+        validate_repay_liquidation(&pair, &user_position, true).unwrap();
+
+        let (mut written_off, mut written_off_position) = (pair.clone(), user_position.clone());
+        let write_off = apply_liquidation(&mut written_off, &mut written_off_position, true).unwrap();
+        let (mut repaid, mut repaid_position) = (pair.clone(), user_position.clone());
+        let amounts = RepayLiquidationAmounts::new(&repaid, &repaid_position, true, u64::MAX).unwrap();
+        amounts.apply(&mut repaid, &mut repaid_position, true).unwrap();
+
+        // Same close factor and borrower penalty as the write-off
+        assert_eq!(amounts.debt_repaid, write_off.debt_to_writeoff);
+        assert_eq!(amounts.collateral_seized, write_off.collateral_seized);
+        assert_eq!(repaid_position.collateral0, written_off_position.collateral0);
+        // The liquidator takes the collateral it paid for, LPs get fresh debt tokens
+        assert!(amounts.caller_collateral > write_off.caller_incentive);
+        assert_eq!(
+            amounts.collateral_to_reserves + amounts.collateral_to_insurance,
+            amounts.collateral_seized - amounts.caller_collateral
+        );
+        assert_eq!(repaid.cash_reserve1, pair.cash_reserve1 + amounts.debt_repaid);
+        assert_eq!(repaid.reserve1, pair.reserve1);
+        assert!(written_off.reserve1 < pair.reserve1);
+
+        let err = RepayLiquidationAmounts::new(&pair, &user_position, true, amounts.max_repay + 1).unwrap_err();
+        assert_eq!(err, error!(ErrorCode::RepayExceedsCloseFactor));
+    }
+}
@@ -4,7 +4,17 @@ pub mod remove_collateral;
 pub mod borrow;
 pub mod repay;
 pub mod liquidate;
+pub mod liquidate_with_repay;
 pub mod flashloan;
+pub mod open_leveraged;
+pub mod close_position;
+pub mod set_margin_mode;
+pub mod set_position_delegate;
+pub mod transfer_position;
+pub mod close_position_account;
+pub mod place_position_order;
+pub mod cancel_position_order;
+pub mod execute_position_order;
 
 pub use common::*;
 pub use liquidate::*;
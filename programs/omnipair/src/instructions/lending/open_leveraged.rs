@@ -0,0 +1,514 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::{
+    token::{Mint, Token, TokenAccount},
+    token_interface::Token2022,
+};
+
+use crate::{
+    constants::*,
+    errors::ErrorCode,
+    events::{
+        AdjustCollateralEvent, AdjustDebtEvent, EventMetadata, SwapEvent,
+        UserPositionCreatedEvent, UserPositionUpdatedEvent,
+    },
+    generate_gamm_pair_seeds,
+    instructions::spot::SwapAmounts,
+    state::{
+        futarchy_authority::FutarchyAuthority, pair::Pair, rate_model::RateModel,
+        user_position::UserPosition,
+    },
+    utils::{
+        account::get_size_with_discriminator,
+        liquidity_delta_circuit_breaker::require_no_same_tx_liquidity_delta,
+        token::{transfer_from_user_to_vault, transfer_from_vault_to_vault},
+    },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OpenLeveragedArgs {
+    /// Collateral supplied by the user
+    pub collateral_amount: u64,
+    /// Target leverage as final collateral / supplied collateral (e.g. 30_000 = 3x)
+    pub leverage_bps: u32,
+    /// Slippage bound on the debt taken to buy the extra collateral
+    pub max_borrow_amount: u64,
+    /// Position of the user in the pair, 0 for the default one (see `UserPosition::position_index`)
+    pub position_index: u16,
+}
+
+/// Resolved amounts of an `open_leveraged`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenLeveragedAmounts {
+    /// Collateral bought with the debt, on top of the supplied collateral
+    pub extra_collateral: u64,
+    /// Debt taken, spent in full on the extra collateral
+    pub borrow_amount: u64,
+    /// Sale of the debt for exactly `extra_collateral`
+    pub swap: SwapAmounts,
+}
+
+/// State transition of an `open_leveraged` of `collateral_amount` on the `is_collateral_token0` side:
+/// borrows at most `max_borrow_amount` of the other token and sells it to the pair for the extra
+/// collateral, then checks the final position against the borrow limit of `borrow`.
+pub fn apply_open_leveraged(
+    pair: &mut Pair,
+    user_position: &mut UserPosition,
+    futarchy_swap_bps: u16,
+    is_collateral_token0: bool,
+    collateral_amount: u64,
+    leverage_bps: u32,
+    max_borrow_amount: u64,
+) -> Result<OpenLeveragedAmounts> {
+    let (collateral_token, debt_token) = match is_collateral_token0 {
+        true => (pair.token0, pair.token1),
+        false => (pair.token1, pair.token0),
+    };
+    let is_debt_token0 = !is_collateral_token0;
+
+    // extra_collateral = collateral_amount * (leverage - 1)
+    let extra_collateral: u64 = (collateral_amount as u128)
+        .checked_mul(leverage_bps.checked_sub(BPS_DENOMINATOR as u32).ok_or(ErrorCode::InvalidArgument)? as u128)
+        .ok_or(ErrorCode::Overflow)?
+        .checked_div(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::Overflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::Overflow)?;
+    require!(extra_collateral > 0, ErrorCode::AmountZero);
+
+    // Debt needed to buy the extra collateral, fees included
+    let swap = SwapAmounts::exact_out(pair, futarchy_swap_bps, is_debt_token0, extra_collateral)?;
+    let borrow_amount = swap.amount_in;
+    require_gte!(max_borrow_amount, borrow_amount, ErrorCode::SlippageExceeded);
+
+    // r_cash >= r_debt_out
+    match is_debt_token0 {
+        true => require_gte!(pair.cash_reserve0, borrow_amount, ErrorCode::InsufficientCashReserve0),
+        false => require_gte!(pair.cash_reserve1, borrow_amount, ErrorCode::InsufficientCashReserve1),
+    };
+
+    // Borrow, then sell the borrowed tokens back into the pair
+    user_position.increase_debt(pair, &debt_token, borrow_amount)?;
+    swap.apply(pair, is_debt_token0)?;
+
+    let total_collateral_added = collateral_amount
+        .checked_add(extra_collateral)
+        .ok_or(ErrorCode::Overflow)?;
+    match is_collateral_token0 {
+        true => {
+            pair.total_collateral0 = pair.total_collateral0.checked_add(total_collateral_added).ok_or(ErrorCode::Overflow)?;
+            user_position.collateral0 = user_position.collateral0.checked_add(total_collateral_added).ok_or(ErrorCode::Overflow)?;
+        },
+        false => {
+            pair.total_collateral1 = pair.total_collateral1.checked_add(total_collateral_added).ok_or(ErrorCode::Overflow)?;
+            user_position.collateral1 = user_position.collateral1.checked_add(total_collateral_added).ok_or(ErrorCode::Overflow)?;
+        }
+    }
+    pair.check_collateral_cap(&collateral_token)?;
+    pair.check_borrow_caps(&debt_token)?;
+
+    // Final state must pass the same borrow limit check as `borrow`
+    if user_position.cross_margin {
+        user_position.check_net_borrow_limit(pair)?;
+    } else {
+        let (collateral_amount_total, user_debt) = match is_collateral_token0 {
+            true => (user_position.collateral0, user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?),
+            false => (user_position.collateral1, user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?),
+        };
+        let (borrow_limit, _, liquidation_cf_bps) = pair.get_max_debt_and_cf_bps_for_collateral(
+            pair,
+            &collateral_token,
+            collateral_amount_total,
+        )?;
+        require_gte!(borrow_limit, user_debt, ErrorCode::BorrowingPowerExceeded);
+        user_position.set_liquidation_cf_for_debt_token(&debt_token, pair, liquidation_cf_bps);
+    }
+
+    Ok(OpenLeveragedAmounts { extra_collateral, borrow_amount, swap })
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(args: OpenLeveragedArgs)]
+pub struct OpenLeveraged<'info> {
+    #[account(
+        mut,
+        seeds = [
+            PAIR_SEED_PREFIX,
+            pair.token0.as_ref(),
+            pair.token1.as_ref(),
+            pair.params_hash.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Box<Account<'info, Pair>>,
+
+    #[account(
+        mut,
+        address = pair.rate_model0,
+    )]
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Account<'info, RateModel>,
+
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = get_size_with_discriminator::<UserPosition>(),
+        constraint = user_position.owner == Pubkey::default() || user_position.owner == user.key(),
+        constraint = user_position.pair == Pubkey::default() || user_position.pair == pair.key(),
+        seeds = [
+            POSITION_SEED_PREFIX,
+            pair.key().as_ref(),
+            user.key().as_ref(),
+            &UserPosition::index_seed(args.position_index)
+        ],
+        bump
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+
+    #[account(
+        mut,
+        seeds = [
+            COLLATERAL_VAULT_SEED_PREFIX,
+            pair.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+        ],
+        bump = pair.get_collateral_vault_bump(&collateral_token_mint.key())
+    )]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Reserve vault of the collateral token, which pays out the swapped collateral
+    #[account(
+        mut,
+        seeds = [
+            RESERVE_VAULT_SEED_PREFIX,
+            pair.key().as_ref(),
+            collateral_token_mint.key().as_ref(),
+        ],
+        bump = pair.get_reserve_vault_bump(&collateral_token_mint.key())
+    )]
+    pub collateral_reserve_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_collateral_token_account.mint == collateral_token_mint.key() @ ErrorCode::InvalidMint,
+        token::authority = user,
+    )]
+    pub user_collateral_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = collateral_token_mint.key() == pair.token0 || collateral_token_mint.key() == pair.token1 @ ErrorCode::InvalidMint
+    )]
+    pub collateral_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Instructions sysvar used by the liquidity delta circuit breaker.
+    #[account(address = sysvar::instructions::ID @ ErrorCode::InvalidInstructionsSysvar)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+impl<'info> OpenLeveraged<'info> {
+    pub fn validate_open_leveraged(&self, args: &OpenLeveragedArgs) -> Result<()> {
+        let OpenLeveragedArgs { collateral_amount, leverage_bps, .. } = args;
+
+        require_no_same_tx_liquidity_delta(
+            &self.pair.key(),
+            &self.instructions_sysvar.to_account_info(),
+        )?;
+
+        // Check reduce-only mode (global or per-pair)
+        require!(
+            !self
+                .futarchy_authority
+                .is_reduce_only(self.pair.reduce_only),
+            ErrorCode::ReduceOnlyMode
+        );
+
+        require!(*collateral_amount > 0, ErrorCode::AmountZero);
+        require_gte!(
+            self.user_collateral_token_account.amount,
+            *collateral_amount,
+            ErrorCode::InsufficientBalanceForCollateral
+        );
+        // Leverage must be strictly above 1x, otherwise there is nothing to borrow
+        require_gt!(*leverage_bps, BPS_DENOMINATOR as u32, ErrorCode::InvalidArgument);
+
+        Ok(())
+    }
+
+    pub fn update(&mut self) -> Result<()> {
+        let pair_key = self.pair.to_account_info().key();
+        self.pair.update(
+            &self.rate_model0,
+            &self.rate_model1,
+            &self.futarchy_authority,
+            pair_key,
+            Some(self.event_authority.to_account_info()),
+        )?;
+        Ok(())
+    }
+
+    pub fn update_and_validate_open_leveraged(&mut self, args: &OpenLeveragedArgs) -> Result<()> {
+        self.update()?;
+        self.validate_open_leveraged(args)?;
+        Ok(())
+    }
+
+    /// Opens (or grows) a leveraged position in one step:
+    /// deposits `collateral_amount`, borrows the debt token from the pair and sells it
+    /// along the pair's own curve for exactly the extra collateral implied by `leverage_bps`.
+    ///
+    /// The borrowed tokens never leave the pair: they are swapped straight back into the
+    /// debt reserve, and only the bought collateral moves from the reserve vault to the
+    /// collateral vault. The final position must satisfy the same borrow limit as [`Borrow`](crate::Borrow).
+    pub fn handle_open_leveraged(ctx: Context<Self>, args: OpenLeveragedArgs) -> Result<()> {
+        let OpenLeveragedArgs { collateral_amount, leverage_bps, max_borrow_amount, position_index } = args;
+        let OpenLeveraged {
+            pair,
+            futarchy_authority,
+            user_position,
+            collateral_vault,
+            collateral_reserve_vault,
+            user_collateral_token_account,
+            collateral_token_mint,
+            user,
+            token_program,
+            token_2022_program,
+            ..
+        } = ctx.accounts;
+
+        if !user_position.is_initialized() {
+            user_position.initialize(
+                user.key(),
+                pair.key(),
+                position_index,
+                ctx.bumps.user_position,
+            )?;
+
+            emit_cpi!(UserPositionCreatedEvent {
+                metadata: EventMetadata::new(user.key(), pair.key()),
+                position: user_position.key(),
+                position_index,
+            });
+        }
+
+        let collateral_token = collateral_token_mint.key();
+        let is_collateral_token0 = collateral_token == pair.token0;
+        let is_debt_token0 = !is_collateral_token0;
+        let OpenLeveragedAmounts { extra_collateral, borrow_amount, swap: amounts } = apply_open_leveraged(
+            pair,
+            user_position,
+            futarchy_authority.revenue_share.swap_bps,
+            is_collateral_token0,
+            collateral_amount,
+            leverage_bps,
+            max_borrow_amount,
+        )?;
+
+        let token_program_info = match collateral_token_mint.to_account_info().owner == token_program.key {
+            true => token_program.to_account_info(),
+            false => token_2022_program.to_account_info(),
+        };
+
+        transfer_from_user_to_vault(
+            user.to_account_info(),
+            user_collateral_token_account.to_account_info(),
+            collateral_vault.to_account_info(),
+            collateral_token_mint.to_account_info(),
+            token_program_info.clone(),
+            collateral_amount,
+            collateral_token_mint.decimals,
+        )?;
+
+        transfer_from_vault_to_vault(
+            pair.to_account_info(),
+            collateral_reserve_vault.to_account_info(),
+            collateral_vault.to_account_info(),
+            collateral_token_mint.to_account_info(),
+            token_program_info,
+            extra_collateral,
+            collateral_token_mint.decimals,
+            &[&generate_gamm_pair_seeds!(pair)[..]],
+        )?;
+
+        let total_collateral_added = collateral_amount + extra_collateral;
+        let (collateral0, collateral1) = match is_collateral_token0 {
+            true => (total_collateral_added as i64, 0),
+            false => (0, total_collateral_added as i64),
+        };
+        emit_cpi!(AdjustCollateralEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            amount0: collateral0,
+            amount1: collateral1,
+        });
+
+        let (debt0, debt1) = match is_debt_token0 {
+            true => (borrow_amount as i64, 0),
+            false => (0, borrow_amount as i64),
+        };
+        emit_cpi!(AdjustDebtEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            amount0: debt0,
+            amount1: debt1,
+        });
+
+        emit_cpi!(SwapEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            reserve0: pair.reserve0,
+            reserve1: pair.reserve1,
+            is_token0_in: is_debt_token0,
+            is_exact_out: true,
+            amount_in: amounts.amount_in,
+            amount_out: amounts.amount_out,
+            amount_in_after_fee: amounts.amount_in_after_fee,
+            lp_fee: amounts.lp_fee,
+            protocol_fee: amounts.protocol_fee,
+        });
+
+        emit_cpi!(UserPositionUpdatedEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            position: user_position.key(),
+            owner: user_position.owner,
+            collateral0: user_position.collateral0,
+            collateral1: user_position.collateral1,
+            debt0_shares: user_position.debt0_shares,
+            debt1_shares: user_position.debt1_shares,
+            collateral0_max_cf_bps: user_position.get_max_cf_bps_for_debt_token(pair, &pair.token1),
+            collateral1_max_cf_bps: user_position.get_max_cf_bps_for_debt_token(pair, &pair.token0),
+            collateral0_liquidation_cf_bps: user_position.collateral0_liquidation_cf_bps,
+            collateral1_liquidation_cf_bps: user_position.collateral1_liquidation_cf_bps,
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{DelegatePermission, RiskParams, VaultBumps};
+
+    const RESERVE: u64 = 1_000_000_000;
+    const COLLATERAL: u64 = 10_000_000;
+    const SWAP_BPS: u16 = 0;
+
+    fn test_pair() -> Pair {
+        let mut pair = Pair::initialize(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            6,
+            6,
+            Pubkey::new_unique(),
+            30,
+            60_000,
+            Some(8_000),
+            0,
+            [0; 32],
+            VERSION,
+            1,
+            VaultBumps::default(),
+            0,
+            RiskParams::default(),
+        );
+        pair.reserve0 = RESERVE;
+        pair.reserve1 = RESERVE;
+        pair.cash_reserve0 = RESERVE;
+        pair.cash_reserve1 = RESERVE;
+        pair.last_price0_ema.symmetric = pair.spot_price0_nad();
+        pair.last_price0_ema.directional = pair.spot_price0_nad();
+        pair.last_price1_ema.symmetric = pair.spot_price1_nad();
+        pair.last_price1_ema.directional = pair.spot_price1_nad();
+        pair
+    }
+
+    fn test_position(cross_margin: bool) -> UserPosition {
+        UserPosition {
+            owner: Pubkey::new_unique(),
+            pair: Pubkey::new_unique(),
+            collateral0_liquidation_cf_bps: 0,
+            collateral1_liquidation_cf_bps: 0,
+            collateral0: 0,
+            collateral1: 0,
+            debt0_shares: 0,
+            debt1_shares: 0,
+            bump: 1,
+            cross_margin,
+            position_index: 0,
+            delegate: Pubkey::default(),
+            delegate_permission: DelegatePermission::None,
+            delegate_expiry_slot: 0,
+            open_orders: 0,
+        }
+    }
+
+    #[test]
+    fn borrows_exactly_the_price_of_the_extra_collateral() {
+        let mut pair = test_pair();
+        let mut user_position = test_position(false);
+        let quote = SwapAmounts::exact_out(&pair, SWAP_BPS, false, COLLATERAL / 2).unwrap();
+
+        // 1.5x: half the supplied collateral again, bought with token1 debt
+        let amounts = apply_open_leveraged(&mut pair, &mut user_position, SWAP_BPS, true, COLLATERAL, 15_000, u64::MAX).unwrap();
+        assert_eq!(amounts.extra_collateral, COLLATERAL / 2);
+        assert_eq!(amounts.swap, quote);
+        assert_eq!(amounts.borrow_amount, quote.amount_in);
+        assert!(amounts.borrow_amount > amounts.extra_collateral);
+
+        assert_eq!(user_position.collateral0, COLLATERAL + COLLATERAL / 2);
+        assert_eq!(pair.total_collateral0, COLLATERAL + COLLATERAL / 2);
+        assert_eq!(user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares).unwrap(), amounts.borrow_amount);
+        assert_eq!(pair.reserve0, RESERVE - amounts.extra_collateral);
+        assert_ne!(user_position.collateral0_liquidation_cf_bps, 0);
+    }
+
+    #[test]
+    fn borrow_above_max_borrow_amount_fails() {
+        let pair = test_pair();
+        let quote = SwapAmounts::exact_out(&pair, SWAP_BPS, false, COLLATERAL / 2).unwrap();
+
+        let err = apply_open_leveraged(
+            &mut pair.clone(), &mut test_position(false), SWAP_BPS, true, COLLATERAL, 15_000, quote.amount_in - 1,
+        ).unwrap_err();
+        assert_eq!(err, error!(ErrorCode::SlippageExceeded));
+        apply_open_leveraged(&mut pair.clone(), &mut test_position(false), SWAP_BPS, true, COLLATERAL, 15_000, quote.amount_in).unwrap();
+    }
+
+    #[test]
+    fn leverage_beyond_the_borrow_limit_is_rejected() {
+        let mut pair = test_pair();
+        let err = apply_open_leveraged(&mut pair, &mut test_position(false), SWAP_BPS, true, COLLATERAL, 100_000, u64::MAX).unwrap_err();
+        assert_eq!(err, error!(ErrorCode::BorrowingPowerExceeded));
+    }
+
+    #[test]
+    fn cross_margin_nets_the_debt_against_same_token_collateral() {
+        // Token1 collateral covering most of the debt: only the netted debt counts towards the borrow limit
+        let with_collateral1 = |cross_margin| {
+            let mut pair = test_pair();
+            let mut user_position = test_position(cross_margin);
+            user_position.collateral1 = 10 * COLLATERAL;
+            pair.total_collateral1 = 10 * COLLATERAL;
+            apply_open_leveraged(&mut pair, &mut user_position, SWAP_BPS, true, COLLATERAL, 100_000, u64::MAX)
+        };
+
+        assert_eq!(with_collateral1(false).unwrap_err(), error!(ErrorCode::BorrowingPowerExceeded));
+        assert_eq!(with_collateral1(true).unwrap().extra_collateral, 9 * COLLATERAL);
+    }
+}
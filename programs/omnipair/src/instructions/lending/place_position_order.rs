@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    errors::ErrorCode,
+    events::{EventMetadata, PositionOrderPlacedEvent},
+    state::{
+        position_order::{OrderTrigger, PositionOrder},
+        user_position::UserPosition,
+    },
+    utils::account::get_size_with_discriminator,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PlacePositionOrderArgs {
+    /// Distinguishes the orders of the position, part of the order's address
+    pub order_index: u16,
+    /// Collateral side to close once triggered
+    pub is_collateral_token0: bool,
+    pub trigger: OrderTrigger,
+    /// Slippage bound on the collateral returned to the owner, after the keeper bounty
+    pub min_collateral_out: u64,
+    /// Collateral paid to the keeper executing the order
+    pub keeper_bounty: u64,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(args: PlacePositionOrderArgs)]
+pub struct PlacePositionOrder<'info> {
+    #[account(
+        mut,
+        constraint = user_position.owner == user.key(),
+        seeds = [
+            POSITION_SEED_PREFIX,
+            user_position.pair.as_ref(),
+            user.key().as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
+        ],
+        bump = user_position.bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        init,
+        payer = user,
+        space = get_size_with_discriminator::<PositionOrder>(),
+        seeds = [
+            POSITION_ORDER_SEED_PREFIX,
+            user_position.key().as_ref(),
+            &args.order_index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub position_order: Account<'info, PositionOrder>,
+
+    /// Owner of the position, pays the rent of the order
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PlacePositionOrder<'info> {
+    /// Places a stop-loss or take-profit order on the position. The order stays open until it is
+    /// executed or cancelled; the side it closes may change in size in the meantime.
+    pub fn handle_place_position_order(ctx: Context<Self>, args: PlacePositionOrderArgs) -> Result<()> {
+        let PlacePositionOrder { user_position, position_order, user, .. } = ctx.accounts;
+        require_gt!(args.trigger.price_nad, 0, ErrorCode::InvalidArgument);
+
+        position_order.set_inner(PositionOrder {
+            user_position: user_position.key(),
+            owner: user.key(),
+            pair: user_position.pair,
+            order_index: args.order_index,
+            is_collateral_token0: args.is_collateral_token0,
+            trigger: args.trigger,
+            min_collateral_out: args.min_collateral_out,
+            keeper_bounty: args.keeper_bounty,
+            bump: ctx.bumps.position_order,
+        });
+        position_order.min_close_collateral_out()?;
+        user_position.open_orders = user_position.open_orders.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        emit_cpi!(PositionOrderPlacedEvent {
+            metadata: EventMetadata::new(user.key(), user_position.pair),
+            order: position_order.key(),
+            position: user_position.key(),
+            order_index: args.order_index,
+            is_collateral_token0: args.is_collateral_token0,
+            trigger: args.trigger,
+            min_collateral_out: args.min_collateral_out,
+            keeper_bounty: args.keeper_bounty,
+        });
+
+        Ok(())
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
 
 impl<'info> CommonAdjustCollateral<'info> {
     pub fn validate_remove(&self, args: &AdjustCollateralArgs) -> Result<()> {
-        let AdjustCollateralArgs { amount } = args;
+        let AdjustCollateralArgs { amount, .. } = args;
         
         require_no_same_tx_liquidity_delta(
             &self.pair.key(),
@@ -32,13 +32,18 @@ impl<'info> CommonAdjustCollateral<'info> {
             true => self.user_position.calculate_debt1(self.pair.total_debt1, self.pair.total_debt1_shares)?,
             false => self.user_position.calculate_debt0(self.pair.total_debt0, self.pair.total_debt0_shares)?,
         };
+        // Cross-margin collateral backs the debt of both tokens
+        let has_debt = match self.user_position.cross_margin {
+            true => self.user_position.debt0_shares > 0 || self.user_position.debt1_shares > 0,
+            false => debt > 0,
+        };
 
         // Check reduce-only mode: if active, user must have zero debt to remove collateral
         if self.futarchy_authority.is_reduce_only(self.pair.reduce_only) {
-            require!(debt == 0, ErrorCode::ReduceOnlyHasDebt);
+            require!(!has_debt, ErrorCode::ReduceOnlyHasDebt);
         }
 
-        let withdraw_amount = if *amount == u64::MAX && debt == 0 {
+        let withdraw_amount = if *amount == u64::MAX && !has_debt {
             user_collateral
         } else {
             *amount
@@ -52,7 +57,8 @@ impl<'info> CommonAdjustCollateral<'info> {
         );
 
         // If the user has debt, validate the exact post-withdraw position.
-        if debt > 0 {
+        // Cross-margin positions are checked on their post-withdraw net exposure by the handler.
+        if debt > 0 && !self.user_position.cross_margin {
             let remaining_collateral = user_collateral
                 .checked_sub(withdraw_amount)
                 .ok_or(ErrorCode::Overflow)?;
@@ -101,7 +107,11 @@ impl<'info> CommonAdjustCollateral<'info> {
             true => user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?,
             false => user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?,
         };
-        let withdraw_amount = if args.amount == u64::MAX && debt == 0 {
+        let has_debt = match user_position.cross_margin {
+            true => user_position.debt0_shares > 0 || user_position.debt1_shares > 0,
+            false => debt > 0,
+        };
+        let withdraw_amount = if args.amount == u64::MAX && !has_debt {
             user_collateral
         } else {
             args.amount
@@ -139,15 +149,19 @@ impl<'info> CommonAdjustCollateral<'info> {
             }
         }
 
-        let collateral_token = if is_token0 { pair.token0 } else { pair.token1 };
-        let debt_token = if is_token0 { pair.token1 } else { pair.token0 };
-        let collateral_amount = if is_token0 {
-            user_position.collateral0
+        if user_position.cross_margin {
+            user_position.check_net_borrow_limit(pair)?;
         } else {
-            user_position.collateral1
-        };
-        let (_, _, liquidation_cf_bps) = pair.get_max_debt_and_cf_bps_for_collateral(&pair, &collateral_token, collateral_amount)?;
-        user_position.set_liquidation_cf_for_debt_token(&debt_token, &pair, liquidation_cf_bps);
+            let collateral_token = if is_token0 { pair.token0 } else { pair.token1 };
+            let debt_token = if is_token0 { pair.token1 } else { pair.token0 };
+            let collateral_amount = if is_token0 {
+                user_position.collateral0
+            } else {
+                user_position.collateral1
+            };
+            let (_, _, liquidation_cf_bps) = pair.get_max_debt_and_cf_bps_for_collateral(&pair, &collateral_token, collateral_amount)?;
+            user_position.set_liquidation_cf_for_debt_token(&debt_token, &pair, liquidation_cf_bps);
+        }
 
         // Emit collateral adjustment event
         let (amount0, amount1) = match is_token0 {
@@ -165,6 +179,7 @@ impl<'info> CommonAdjustCollateral<'info> {
         emit_cpi!(UserPositionUpdatedEvent {
             metadata: EventMetadata::new(user.key(), pair.key()),
             position: user_position.key(),
+            owner: user_position.owner,
             collateral0: user_position.collateral0,
             collateral1: user_position.collateral1,
             debt0_shares: user_position.debt0_shares,
@@ -182,8 +197,9 @@ impl<'info> CommonAdjustCollateral<'info> {
 #[cfg(test)]
 mod tests {
     use crate::constants::*;
+    use crate::state::RiskParams;
     use crate::utils::gamm_math::{
-        construct_virtual_reserves_at_pessimistic_price, pessimistic_max_debt, CPCurve,
+        construct_virtual_reserves_at_pessimistic_price, pessimistic_max_debt, CPCurve, CfParams,
     };
     use crate::utils::math::ceil_div;
 
@@ -211,7 +227,7 @@ mod tests {
             collateral_reserve,
             debt_reserve,
             total_debt,
-            None,
+            CfParams::new(None, &RiskParams::default()),
         )
         .unwrap();
         let min_collateral_value = ceil_div(
@@ -245,7 +261,7 @@ mod tests {
             collateral_reserve,
             debt_reserve,
             total_debt,
-            None,
+            CfParams::new(None, &RiskParams::default()),
         )
         .unwrap();
         let min_collateral_value = ceil_div(
@@ -286,7 +302,7 @@ mod tests {
             collateral_reserve,
             debt_reserve,
             total_debt,
-            None,
+            CfParams::new(None, &RiskParams::default()),
         )
         .unwrap();
         borrow_limit
@@ -333,7 +349,7 @@ mod tests {
             collateral_reserve,
             debt_reserve,
             total_debt,
-            None,
+            CfParams::new(None, &RiskParams::default()),
         )
         .unwrap();
 
@@ -375,7 +391,7 @@ mod tests {
                 collateral_reserve,
                 debt_reserve,
                 0,
-                None,
+                CfParams::new(None, &RiskParams::default()),
             )
             .unwrap();
             let initial_liquidation_limit = liquidation_limit_with_cf(
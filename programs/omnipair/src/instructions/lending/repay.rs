@@ -4,22 +4,44 @@ use crate::{
     events::{AdjustDebtEvent, UserPositionUpdatedEvent, EventMetadata},
     utils::token::transfer_from_user_to_vault,
     instructions::lending::common::{CommonAdjustDebt, AdjustDebtArgs},
-    state::user_position::DebtDecreaseReason,
+    state::{pair::Pair, user_position::{DebtDecreaseReason, UserPosition}},
 };
 
+/// Debt repaid by a `repay` of `amount` (`u64::MAX` repays the whole debt), checked against the position's debt.
+pub fn resolve_repay_amount(pair: &Pair, user_position: &UserPosition, debt_token: &Pubkey, amount: u64) -> Result<u64> {
+    require!(amount > 0, ErrorCode::AmountZero);
+
+    let user_total_debt = match *debt_token == pair.token0 {
+        true => user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?,
+        false => user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?,
+    };
+    let debt_to_repay = if amount == u64::MAX { user_total_debt } else { amount };
+
+    // Check user debt >= debt to repay
+    require_gte!(
+        user_total_debt,
+        debt_to_repay,
+        ErrorCode::InsufficientDebt
+    );
+    
+    // debt cannot be zero
+    require_gt!(
+        user_total_debt,
+        0,
+        ErrorCode::ZeroDebtAmount
+    );
+
+    Ok(debt_to_repay)
+}
+
 impl<'info> CommonAdjustDebt<'info> {
     pub fn validate_repay(&self, args: &AdjustDebtArgs) -> Result<()> {
-        let AdjustDebtArgs { amount } = args;
-        
-        require!(*amount > 0, ErrorCode::AmountZero);
-
-        let is_repay_all = *amount == u64::MAX;
-        let is_token0 = self.user_reserve_token_account.mint == self.pair.token0;
-        let user_total_debt = match is_token0 {
-            true => self.user_position.calculate_debt0(self.pair.total_debt0, self.pair.total_debt0_shares)?,
-            false => self.user_position.calculate_debt1(self.pair.total_debt1, self.pair.total_debt1_shares)?,
-        };
-        let debt_to_repay = if is_repay_all { user_total_debt } else { *amount };
+        let debt_to_repay = resolve_repay_amount(
+            &self.pair,
+            &self.user_position,
+            &self.user_reserve_token_account.mint,
+            args.amount,
+        )?;
         
         // Check user token balance >= debt to repay
         require_gte!(
@@ -27,20 +49,6 @@ impl<'info> CommonAdjustDebt<'info> {
             debt_to_repay,
             ErrorCode::InsufficientBalance
         );
-
-        // Check user debt >= debt to repay
-        require_gte!(
-            user_total_debt,
-            debt_to_repay,
-            ErrorCode::InsufficientDebt
-        );
-        
-        // debt cannot be zero
-        require_gt!(
-            user_total_debt,
-            0,
-            ErrorCode::ZeroDebtAmount
-        );
         
         Ok(())
     }
@@ -64,16 +72,7 @@ impl<'info> CommonAdjustDebt<'info> {
             ..
         } = ctx.accounts;
 
-        let is_repay_all = args.amount == u64::MAX;
-        let is_token0 = user_reserve_token_account.mint == pair.token0;
-        let debt_to_repay = if is_repay_all { 
-            match is_token0 {
-                true => user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?,
-                false => user_position.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?,
-            }
-        } else {
-            args.amount
-        };
+        let debt_to_repay = resolve_repay_amount(pair, user_position, &user_reserve_token_account.mint, args.amount)?;
 
         // Transfer tokens from user to vault
         transfer_from_user_to_vault(
@@ -109,6 +108,7 @@ impl<'info> CommonAdjustDebt<'info> {
         emit_cpi!(UserPositionUpdatedEvent {
             metadata: EventMetadata::new(user.key(), pair.key()),
             position: user_position.key(),
+            owner: user_position.owner,
             collateral0: user_position.collateral0,
             collateral1: user_position.collateral1,
             debt0_shares: user_position.debt0_shares,
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    errors::ErrorCode,
+    events::{EventMetadata, UserPositionMarginModeEvent, UserPositionUpdatedEvent},
+    state::{
+        futarchy_authority::FutarchyAuthority, pair::Pair, rate_model::RateModel,
+        user_position::UserPosition,
+    },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetMarginModeArgs {
+    /// Cross margin (true) or isolated (false)
+    pub cross_margin: bool,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetMarginMode<'info> {
+    #[account(
+        mut,
+        seeds = [
+            PAIR_SEED_PREFIX,
+            pair.token0.as_ref(),
+            pair.token1.as_ref(),
+            pair.params_hash.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Account<'info, Pair>,
+
+    #[account(
+        mut,
+        constraint = user_position.owner == user.key(),
+        constraint = user_position.pair == pair.key(),
+        seeds = [
+            POSITION_SEED_PREFIX,
+            pair.key().as_ref(),
+            user.key().as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
+        ],
+        bump = user_position.bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        address = pair.rate_model0,
+    )]
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Account<'info, RateModel>,
+
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+
+    pub user: Signer<'info>,
+}
+
+/// State transition of a `set_margin_mode`: the position must be within its borrow limit
+/// in the new mode, whose liquidation CFs are locked in.
+pub fn apply_margin_mode(pair: &Pair, user_position: &mut UserPosition, cross_margin: bool) -> Result<()> {
+    require!(user_position.cross_margin != cross_margin, ErrorCode::InvalidArgument);
+    user_position.cross_margin = cross_margin;
+    match cross_margin {
+        true => user_position.check_net_borrow_limit(pair),
+        false => user_position.check_isolated_borrow_limits(pair),
+    }
+}
+
+impl<'info> SetMarginMode<'info> {
+    pub fn update(&mut self) -> Result<()> {
+        let pair_key = self.pair.to_account_info().key();
+        self.pair.update(
+            &self.rate_model0,
+            &self.rate_model1,
+            &self.futarchy_authority,
+            pair_key,
+            Some(self.event_authority.to_account_info()),
+        )?;
+        Ok(())
+    }
+
+    /// Switches the position between isolated margin, where each collateral token only backs
+    /// the debt of the other token, and cross margin, where both tokens are netted.
+    pub fn handle_set_margin_mode(ctx: Context<Self>, args: SetMarginModeArgs) -> Result<()> {
+        let SetMarginMode { pair, user_position, user, .. } = ctx.accounts;
+
+        apply_margin_mode(pair, user_position, args.cross_margin)?;
+
+        emit_cpi!(UserPositionMarginModeEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            position: user_position.key(),
+            cross_margin: user_position.cross_margin,
+        });
+
+        emit_cpi!(UserPositionUpdatedEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            position: user_position.key(),
+            owner: user_position.owner,
+            collateral0: user_position.collateral0,
+            collateral1: user_position.collateral1,
+            debt0_shares: user_position.debt0_shares,
+            debt1_shares: user_position.debt1_shares,
+            collateral0_max_cf_bps: user_position.get_max_cf_bps_for_debt_token(pair, &pair.token1),
+            collateral1_max_cf_bps: user_position.get_max_cf_bps_for_debt_token(pair, &pair.token0),
+            collateral0_liquidation_cf_bps: user_position.collateral0_liquidation_cf_bps,
+            collateral1_liquidation_cf_bps: user_position.collateral1_liquidation_cf_bps,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    errors::ErrorCode,
+    events::{EventMetadata, UserPositionDelegateEvent},
+    state::user_position::{DelegatePermission, UserPosition},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetPositionDelegateArgs {
+    /// Ignored when revoking
+    pub delegate: Pubkey,
+    /// `None` revokes the current delegate
+    pub permission: DelegatePermission,
+    /// Last slot in which the delegation is valid, ignored when revoking
+    pub expiry_slot: u64,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPositionDelegate<'info> {
+    #[account(
+        mut,
+        constraint = user_position.owner == user.key(),
+        seeds = [
+            POSITION_SEED_PREFIX,
+            user_position.pair.as_ref(),
+            user.key().as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
+        ],
+        bump = user_position.bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    pub user: Signer<'info>,
+}
+
+/// State transition of a `set_position_delegate` at `slot`.
+pub fn apply_position_delegate(user_position: &mut UserPosition, args: &SetPositionDelegateArgs, slot: u64) -> Result<()> {
+    match args.permission {
+        DelegatePermission::None => {
+            user_position.delegate = Pubkey::default();
+            user_position.delegate_expiry_slot = 0;
+        },
+        _ => {
+            require!(
+                args.delegate != Pubkey::default() && args.delegate != user_position.owner,
+                ErrorCode::InvalidArgument
+            );
+            require_gte!(args.expiry_slot, slot, ErrorCode::InvalidArgument);
+            user_position.delegate = args.delegate;
+            user_position.delegate_expiry_slot = args.expiry_slot;
+        },
+    }
+    user_position.delegate_permission = args.permission;
+    Ok(())
+}
+
+impl<'info> SetPositionDelegate<'info> {
+    /// Sets, replaces or revokes the delegate of the position. A delegate with `Manage` may borrow
+    /// and remove collateral in the owner's place; repaying and adding collateral is open to anyone.
+    pub fn handle_set_position_delegate(ctx: Context<Self>, args: SetPositionDelegateArgs) -> Result<()> {
+        let SetPositionDelegate { user_position, user, .. } = ctx.accounts;
+
+        apply_position_delegate(user_position, &args, Clock::get()?.slot)?;
+
+        emit_cpi!(UserPositionDelegateEvent {
+            metadata: EventMetadata::new(user.key(), user_position.pair),
+            position: user_position.key(),
+            delegate: user_position.delegate,
+            permission: user_position.delegate_permission,
+            expiry_slot: user_position.delegate_expiry_slot,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::*,
+    errors::ErrorCode,
+    events::{EventMetadata, UserPositionCreatedEvent, UserPositionTransferredEvent},
+    state::{pair::Pair, user_position::UserPosition},
+    utils::account::get_size_with_discriminator,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TransferPositionArgs {
+    /// Position of the new owner receiving the balances, 0 for their default one
+    pub new_position_index: u16,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(args: TransferPositionArgs)]
+pub struct TransferPosition<'info> {
+    #[account(
+        seeds = [
+            PAIR_SEED_PREFIX,
+            pair.token0.as_ref(),
+            pair.token1.as_ref(),
+            pair.params_hash.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Account<'info, Pair>,
+
+    #[account(
+        mut,
+        constraint = user_position.owner == user.key(),
+        constraint = user_position.pair == pair.key(),
+        constraint = user_position.open_orders == 0 @ ErrorCode::PositionHasOpenOrders,
+        seeds = [
+            POSITION_SEED_PREFIX,
+            pair.key().as_ref(),
+            user.key().as_ref(),
+            &UserPosition::index_seed(user_position.position_index)
+        ],
+        bump = user_position.bump
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+
+    /// Created if needed, must be empty otherwise
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = get_size_with_discriminator::<UserPosition>(),
+        constraint = new_user_position.key() != user_position.key() @ ErrorCode::InvalidArgument,
+        constraint = new_user_position.owner == Pubkey::default() || new_user_position.owner == new_owner.key(),
+        constraint = new_user_position.pair == Pubkey::default() || new_user_position.pair == pair.key(),
+        seeds = [
+            POSITION_SEED_PREFIX,
+            pair.key().as_ref(),
+            new_owner.key().as_ref(),
+            &UserPosition::index_seed(args.new_position_index)
+        ],
+        bump
+    )]
+    pub new_user_position: Box<Account<'info, UserPosition>>,
+
+    /// Accepts the position and its debt
+    pub new_owner: Signer<'info>,
+
+    /// Owner of `user_position`, pays the rent of `new_user_position` if it is created
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TransferPosition<'info> {
+    /// Hands the position over to `new_owner` by moving its balances into their position at
+    /// `new_position_index`. Both owners sign, and the position's orders must be cancelled first as
+    /// they stay with its address. The pair's totals and the position's health are unchanged.
+    pub fn handle_transfer_position(ctx: Context<Self>, args: TransferPositionArgs) -> Result<()> {
+        let TransferPosition { pair, user_position, new_user_position, new_owner, user, .. } = ctx.accounts;
+
+        if !new_user_position.is_initialized() {
+            new_user_position.initialize(
+                new_owner.key(),
+                pair.key(),
+                args.new_position_index,
+                ctx.bumps.new_user_position,
+            )?;
+
+            emit_cpi!(UserPositionCreatedEvent {
+                metadata: EventMetadata::new(user.key(), pair.key()),
+                position: new_user_position.key(),
+                position_index: args.new_position_index,
+            });
+        }
+
+        user_position.transfer_to(new_user_position)?;
+
+        emit_cpi!(UserPositionTransferredEvent {
+            metadata: EventMetadata::new(user.key(), pair.key()),
+            from_position: user_position.key(),
+            to_position: new_user_position.key(),
+            new_owner: new_owner.key(),
+            collateral0: new_user_position.collateral0,
+            collateral1: new_user_position.collateral1,
+            debt0_shares: new_user_position.debt0_shares,
+            debt1_shares: new_user_position.debt1_shares,
+        });
+
+        Ok(())
+    }
+}
@@ -7,6 +7,74 @@ use crate::utils::liquidity_delta_circuit_breaker::{require_top_level_liquidity_
 use crate::generate_gamm_pair_seeds;
 use crate::liquidity::common::{AdjustLiquidity, AddLiquidityArgs};
 use crate::events::{MintEvent, UserLiquidityPositionUpdatedEvent, EventMetadata};
+use crate::state::Pair;
+
+/// Resolved amounts of an `add_liquidity`: the LP tokens minted for the offered amounts
+/// and the (rounded up) token amounts actually pulled from the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AddLiquidityAmounts {
+    pub liquidity: u64,
+    pub amount0: u64,
+    pub amount1: u64,
+}
+
+impl AddLiquidityAmounts {
+    pub fn new(pair: &Pair, args: &AddLiquidityArgs) -> Result<Self> {
+        // Calculate liquidity based on input amounts
+        let total_supply = pair.total_supply; // total supply is set to MIN_LIQUIDITY in initialize
+        let liquidity: u64 = {
+                let liquidity0 = (args.amount0_in as u128)
+                    .checked_mul(total_supply as u128).ok_or(ErrorCode::LiquidityMathOverflow)?
+                    .checked_div(pair.reserve0 as u128).ok_or(ErrorCode::LiquidityMathOverflow)?;
+                let liquidity1 = (args.amount1_in as u128)
+                    .checked_mul(total_supply as u128).ok_or(ErrorCode::LiquidityMathOverflow)?
+                    .checked_div(pair.reserve1 as u128).ok_or(ErrorCode::LiquidityMathOverflow)?;
+                liquidity0.min(liquidity1).try_into().map_err(|_| ErrorCode::LiquidityConversionOverflow)?
+            };
+
+        // Calculate exact amounts to transfer based on liquidity minted
+        // amount_used = ceil(liquidity * reserve / total_supply) - round up to favor protocol
+        let amount0: u64 = ceil_div(
+            (liquidity as u128).checked_mul(pair.reserve0 as u128).ok_or(ErrorCode::LiquidityMathOverflow)?,
+            total_supply as u128
+        ).ok_or(ErrorCode::LiquidityMathOverflow)?
+            .try_into()
+            .map_err(|_| ErrorCode::LiquidityConversionOverflow)?;
+        
+        let amount1: u64 = ceil_div(
+            (liquidity as u128).checked_mul(pair.reserve1 as u128).ok_or(ErrorCode::LiquidityMathOverflow)?,
+            total_supply as u128
+        ).ok_or(ErrorCode::LiquidityMathOverflow)?
+            .try_into()
+            .map_err(|_| ErrorCode::LiquidityConversionOverflow)?;
+
+        Ok(Self { liquidity, amount0, amount1 })
+    }
+
+    pub fn apply(&self, pair: &mut Pair) -> Result<()> {
+        // liqudity additions equally increase both virtual and cash reserves
+        // r_virtual + (amount) = r_cash + (amount) + r_debt
+        // Update reserves
+        pair.reserve0 = pair.reserve0
+            .checked_add(self.amount0)
+            .ok_or(ErrorCode::ReserveOverflow)?;
+        pair.reserve1 = pair.reserve1
+            .checked_add(self.amount1)
+            .ok_or(ErrorCode::ReserveOverflow)?;
+        pair.total_supply = pair.total_supply
+            .checked_add(self.liquidity)
+            .ok_or(ErrorCode::SupplyOverflow)?;
+
+        // Update cash reserves
+        pair.cash_reserve0 = pair.cash_reserve0
+            .checked_add(self.amount0)
+            .ok_or(ErrorCode::ReserveOverflow)?;
+        pair.cash_reserve1 = pair.cash_reserve1
+            .checked_add(self.amount1)
+            .ok_or(ErrorCode::ReserveOverflow)?;
+        Ok(())
+    }
+}
 
 impl<'info> AdjustLiquidity<'info> {
     fn validate_add(&self, args: &AddLiquidityArgs) -> Result<()> {
@@ -67,40 +135,15 @@ impl<'info> AdjustLiquidity<'info> {
             ..
         } = ctx.accounts;
 
-        // Calculate liquidity based on input amounts
-        let total_supply = pair.total_supply; // total supply is set to MIN_LIQUIDITY in initialize
-        let liquidity: u64 = {
-                let liquidity0 = (args.amount0_in as u128)
-                    .checked_mul(total_supply as u128).ok_or(ErrorCode::LiquidityMathOverflow)?
-                    .checked_div(pair.reserve0 as u128).ok_or(ErrorCode::LiquidityMathOverflow)?;
-                let liquidity1 = (args.amount1_in as u128)
-                    .checked_mul(total_supply as u128).ok_or(ErrorCode::LiquidityMathOverflow)?
-                    .checked_div(pair.reserve1 as u128).ok_or(ErrorCode::LiquidityMathOverflow)?;
-                liquidity0.min(liquidity1).try_into().map_err(|_| ErrorCode::LiquidityConversionOverflow)?
-            };
-        
+        let amounts = AddLiquidityAmounts::new(pair, &args)?;
+        let AddLiquidityAmounts { liquidity, amount0: amount0_used, amount1: amount1_used } = amounts;
+
         // Check if liquidity meets minimum (slippage protection)
         require!(
             liquidity >= args.min_liquidity_out,
             ErrorCode::SlippageExceeded
         );
 
-        // Calculate exact amounts to transfer based on liquidity minted
-        // amount_used = ceil(liquidity * reserve / total_supply) - round up to favor protocol
-        let amount0_used: u64 = ceil_div(
-            (liquidity as u128).checked_mul(pair.reserve0 as u128).ok_or(ErrorCode::LiquidityMathOverflow)?,
-            total_supply as u128
-        ).ok_or(ErrorCode::LiquidityMathOverflow)?
-            .try_into()
-            .map_err(|_| ErrorCode::LiquidityConversionOverflow)?;
-        
-        let amount1_used: u64 = ceil_div(
-            (liquidity as u128).checked_mul(pair.reserve1 as u128).ok_or(ErrorCode::LiquidityMathOverflow)?,
-            total_supply as u128
-        ).ok_or(ErrorCode::LiquidityMathOverflow)?
-            .try_into()
-            .map_err(|_| ErrorCode::LiquidityConversionOverflow)?;
-
         // Transfer only the exact amounts needed
         transfer_from_user_to_vault(
             user.to_account_info(),
@@ -137,26 +180,7 @@ impl<'info> AdjustLiquidity<'info> {
             &[&generate_gamm_pair_seeds!(pair)[..]],
         )?;
         
-        // liqudity additions equally increase both virtual and cash reserves
-        // r_virtual + (amount) = r_cash + (amount) + r_debt
-        // Update reserves
-        pair.reserve0 = pair.reserve0
-            .checked_add(amount0_used)
-            .ok_or(ErrorCode::ReserveOverflow)?;
-        pair.reserve1 = pair.reserve1
-            .checked_add(amount1_used)
-            .ok_or(ErrorCode::ReserveOverflow)?;
-        pair.total_supply = pair.total_supply
-            .checked_add(liquidity)
-            .ok_or(ErrorCode::SupplyOverflow)?;
-
-        // Update cash reserves
-        pair.cash_reserve0 = pair.cash_reserve0
-            .checked_add(amount0_used)
-            .ok_or(ErrorCode::ReserveOverflow)?;
-        pair.cash_reserve1 = pair.cash_reserve1
-            .checked_add(amount1_used)
-            .ok_or(ErrorCode::ReserveOverflow)?;
+        amounts.apply(pair)?;
         
         user_lp_token_account.reload()?;
         let user_lp_balance = user_lp_token_account.amount;
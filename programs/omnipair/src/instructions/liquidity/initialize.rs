@@ -225,6 +225,26 @@ pub struct InitializeAndBootstrap<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+impl InitializeAndBootstrapArgs {
+    /// SHA256(VERSION || swap_fee_bps || half_life || fixed_cf_bps || target_util_start_bps || target_util_end_bps
+    ///        || rate_half_life_ms || min_rate_bps || max_rate_bps)
+    ///
+    /// Unset optional parameters hash as zero.
+    pub fn compute_params_hash(&self) -> [u8; 32] {
+        let mut hash_data = Vec::new();
+        hash_data.extend_from_slice(&VERSION.to_le_bytes());
+        hash_data.extend_from_slice(&self.swap_fee_bps.to_le_bytes());
+        hash_data.extend_from_slice(&self.half_life.to_le_bytes());
+        hash_data.extend_from_slice(&self.fixed_cf_bps.unwrap_or(0).to_le_bytes());
+        hash_data.extend_from_slice(&self.target_util_start_bps.unwrap_or(0).to_le_bytes());
+        hash_data.extend_from_slice(&self.target_util_end_bps.unwrap_or(0).to_le_bytes());
+        hash_data.extend_from_slice(&self.rate_half_life_ms.unwrap_or(0).to_le_bytes());
+        hash_data.extend_from_slice(&self.min_rate_bps.unwrap_or(0).to_le_bytes());
+        hash_data.extend_from_slice(&self.max_rate_bps.unwrap_or(0).to_le_bytes());
+        hash(&hash_data).to_bytes()
+    }
+}
+
 impl<'info> InitializeAndBootstrap<'info> {
     pub fn validate(&self, args: &InitializeAndBootstrapArgs) -> Result<()> {
         let InitializeAndBootstrapArgs { 
@@ -279,19 +299,7 @@ impl<'info> InitializeAndBootstrap<'info> {
         );
 
         // Verify params_hash matches the computed hash
-        // SHA256(VERSION || swap_fee_bps || half_life || fixed_cf_bps || target_util_start_bps || target_util_end_bps 
-        //        || rate_half_life_ms || min_rate_bps || max_rate_bps)
-        let mut hash_data = Vec::new();
-        hash_data.extend_from_slice(&VERSION.to_le_bytes());
-        hash_data.extend_from_slice(&swap_fee_bps.to_le_bytes());
-        hash_data.extend_from_slice(&half_life.to_le_bytes());
-        hash_data.extend_from_slice(&fixed_cf_bps.unwrap_or(0).to_le_bytes());
-        hash_data.extend_from_slice(&target_util_start_bps.unwrap_or(0).to_le_bytes());
-        hash_data.extend_from_slice(&target_util_end_bps.unwrap_or(0).to_le_bytes());
-        hash_data.extend_from_slice(&rate_half_life_ms.unwrap_or(0).to_le_bytes());
-        hash_data.extend_from_slice(&min_rate_bps.unwrap_or(0).to_le_bytes());
-        hash_data.extend_from_slice(&max_rate_bps.unwrap_or(0).to_le_bytes());
-        let computed_hash = hash(&hash_data).to_bytes();
+        let computed_hash = args.compute_params_hash();
         let hashes_match = computed_hash.iter().zip(params_hash.iter()).all(|(a, b)| a == b);
         require!(hashes_match, ErrorCode::InvalidParamsHash);
 
@@ -392,14 +400,18 @@ impl<'info> InitializeAndBootstrap<'info> {
         let max_rate = max_rate_bps.unwrap_or(DEFAULT_MAX_RATE_BPS);
         let init_rate = initial_rate_bps.unwrap_or(DEFAULT_INITIAL_RATE_BPS);
         
-        ctx.accounts.rate_model.set_inner(RateModel::new(
-            util_start,
-            util_end,
-            rate_hl,
-            min_rate,
-            max_rate,
-            init_rate,
-        ));
+        ctx.accounts.rate_model.set_inner(RateModel {
+            // Used by both sides of the new pair
+            pair_references: 2,
+            ..RateModel::new(
+                util_start,
+                util_end,
+                rate_hl,
+                min_rate,
+                max_rate,
+                init_rate,
+            )
+        });
 
         // Initialize pair (before LP mint is initialized, but we store the key)
         let vault_bumps = VaultBumps {
@@ -425,6 +437,7 @@ impl<'info> InitializeAndBootstrap<'info> {
             ctx.bumps.pair,
             vault_bumps,
             ctx.accounts.rate_model.initial_rate, // Use rate model's configured initial rate (NAD-scaled)
+            ctx.accounts.futarchy_authority.risk_params,
         ));
 
         // Transfer tokens from deployer to vaults
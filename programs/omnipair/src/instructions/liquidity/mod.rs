@@ -4,5 +4,6 @@ pub mod remove_liquidity;
 pub mod initialize;
 
 pub use common::*;
+pub use add_liquidity::*;
 pub use remove_liquidity::*;
 pub use initialize::*; 
\ No newline at end of file
@@ -43,9 +43,15 @@ pub struct RemoveLiquidity<'info> {
 
     #[account(
         mut,
-        address = pair.rate_model,
+        address = pair.rate_model0,
     )]
-    pub rate_model: Account<'info, RateModel>,
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
+    )]
+    pub rate_model1: Account<'info, RateModel>,
 
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
@@ -126,6 +132,109 @@ pub struct RemoveLiquidity<'info> {
     pub instructions_sysvar: UncheckedAccount<'info>,
 }
 
+/// Resolved amounts of a `remove_liquidity`. The withdrawal fee stays in the reserves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RemoveLiquidityAmounts {
+    pub amount0_out: u64,
+    pub amount1_out: u64,
+    pub fee0: u64,
+    pub fee1: u64,
+}
+
+impl RemoveLiquidityAmounts {
+    pub fn new(pair: &Pair, liquidity_in: u64) -> Result<Self> {
+        // Calculate amounts to remove (before fee)
+        let total_supply = pair.total_supply;
+        let amount0_gross: u64 = (liquidity_in as u128)
+            .checked_mul(pair.reserve0 as u128)
+            .ok_or(ErrorCode::LiquidityMathOverflow)?
+            .checked_div(total_supply as u128)
+            .ok_or(ErrorCode::LiquidityMathOverflow)?
+            .try_into()
+            .map_err(|_| ErrorCode::LiquidityConversionOverflow)?;
+        let amount1_gross: u64 = (liquidity_in as u128)
+            .checked_mul(pair.reserve1 as u128)
+            .ok_or(ErrorCode::LiquidityMathOverflow)?
+            .checked_div(total_supply as u128)
+            .ok_or(ErrorCode::LiquidityMathOverflow)?
+            .try_into()
+            .map_err(|_| ErrorCode::LiquidityConversionOverflow)?;
+
+        // Apply withdrawal fee (1% by default) - fee remains in reserves for remaining LPs
+        let fee0 = ceil_div(
+            (amount0_gross as u128)
+                .checked_mul(pair.risk_params.liquidity_withdrawal_fee_bps as u128)
+                .ok_or(ErrorCode::FeeMathOverflow)?,
+            BPS_DENOMINATOR as u128,
+        )
+        .ok_or(ErrorCode::FeeMathOverflow)? as u64;
+        let fee1 = ceil_div(
+            (amount1_gross as u128)
+                .checked_mul(pair.risk_params.liquidity_withdrawal_fee_bps as u128)
+                .ok_or(ErrorCode::FeeMathOverflow)?,
+            BPS_DENOMINATOR as u128,
+        )
+        .ok_or(ErrorCode::FeeMathOverflow)? as u64;
+
+        let amount0_out = amount0_gross
+            .checked_sub(fee0)
+            .ok_or(ErrorCode::LiquidityMathOverflow)?;
+        let amount1_out = amount1_gross
+            .checked_sub(fee1)
+            .ok_or(ErrorCode::LiquidityMathOverflow)?;
+
+        Ok(Self { amount0_out, amount1_out, fee0, fee1 })
+    }
+
+    /// Checks that cash covers the withdrawal and outstanding debt stays covered, then burns `liquidity_in`.
+    pub fn apply(&self, pair: &mut Pair, liquidity_in: u64) -> Result<()> {
+        // Ensure sufficient cash reserves: (internally accounted instead of relying on token account balance for deciding liquidity availability)
+        // - Token account balances may include protocol fees and external donation, allowing them
+        //   to be higher than the virtual reserves (r_virtual).
+        // - If the invariant r_cash + r_debt = r_virtual is broken, the pool's solvency
+        //   assumption (r_virtual >= r_debt) may also be violated.
+        require_gte!(
+            pair.cash_reserve0,
+            self.amount0_out,
+            ErrorCode::InsufficientCashReserve0
+        );
+        require_gte!(
+            pair.cash_reserve1,
+            self.amount1_out,
+            ErrorCode::InsufficientCashReserve1
+        );
+
+        let post_reserve0 = pair
+            .reserve0
+            .checked_sub(self.amount0_out)
+            .ok_or(ErrorCode::ReserveUnderflow)?;
+        let post_reserve1 = pair
+            .reserve1
+            .checked_sub(self.amount1_out)
+            .ok_or(ErrorCode::ReserveUnderflow)?;
+        validate_post_withdraw_debt_coverage(pair, post_reserve0, post_reserve1)?;
+
+        // Update reserves
+        pair.reserve0 = post_reserve0;
+        pair.reserve1 = post_reserve1;
+        pair.total_supply = pair
+            .total_supply
+            .checked_sub(liquidity_in)
+            .ok_or(ErrorCode::SupplyUnderflow)?;
+
+        // Update cash reserves
+        pair.cash_reserve0 = pair
+            .cash_reserve0
+            .checked_sub(self.amount0_out)
+            .ok_or(ErrorCode::CashReserveUnderflow)?;
+        pair.cash_reserve1 = pair
+            .cash_reserve1
+            .checked_sub(self.amount1_out)
+            .ok_or(ErrorCode::CashReserveUnderflow)?;
+        Ok(())
+    }
+}
+
 impl<'info> RemoveLiquidity<'info> {
     fn validate_remove(&self, args: &RemoveLiquidityArgs) -> Result<()> {
         require_top_level_liquidity_delta_ix(
@@ -154,7 +263,8 @@ impl<'info> RemoveLiquidity<'info> {
     pub fn update(&mut self) -> Result<()> {
         let pair_key = self.pair.to_account_info().key();
         self.pair.update(
-            &self.rate_model,
+            &self.rate_model0,
+            &self.rate_model1,
             &self.futarchy_authority,
             pair_key,
             Some(self.event_authority.to_account_info()),
@@ -184,45 +294,8 @@ impl<'info> RemoveLiquidity<'info> {
             ..
         } = ctx.accounts;
 
-        // Calculate amounts to remove (before fee)
-        let total_supply = pair.total_supply;
-        let amount0_gross: u64 = (args.liquidity_in as u128)
-            .checked_mul(pair.reserve0 as u128)
-            .ok_or(ErrorCode::LiquidityMathOverflow)?
-            .checked_div(total_supply as u128)
-            .ok_or(ErrorCode::LiquidityMathOverflow)?
-            .try_into()
-            .map_err(|_| ErrorCode::LiquidityConversionOverflow)?;
-        let amount1_gross: u64 = (args.liquidity_in as u128)
-            .checked_mul(pair.reserve1 as u128)
-            .ok_or(ErrorCode::LiquidityMathOverflow)?
-            .checked_div(total_supply as u128)
-            .ok_or(ErrorCode::LiquidityMathOverflow)?
-            .try_into()
-            .map_err(|_| ErrorCode::LiquidityConversionOverflow)?;
-
-        // Apply withdrawal fee (1%) - fee remains in reserves for remaining LPs
-        let fee0 = ceil_div(
-            (amount0_gross as u128)
-                .checked_mul(LIQUIDITY_WITHDRAWAL_FEE_BPS as u128)
-                .ok_or(ErrorCode::FeeMathOverflow)?,
-            BPS_DENOMINATOR as u128,
-        )
-        .ok_or(ErrorCode::FeeMathOverflow)? as u64;
-        let fee1 = ceil_div(
-            (amount1_gross as u128)
-                .checked_mul(LIQUIDITY_WITHDRAWAL_FEE_BPS as u128)
-                .ok_or(ErrorCode::FeeMathOverflow)?,
-            BPS_DENOMINATOR as u128,
-        )
-        .ok_or(ErrorCode::FeeMathOverflow)? as u64;
-
-        let amount0_out = amount0_gross
-            .checked_sub(fee0)
-            .ok_or(ErrorCode::LiquidityMathOverflow)?;
-        let amount1_out = amount1_gross
-            .checked_sub(fee1)
-            .ok_or(ErrorCode::LiquidityMathOverflow)?;
+        let amounts = RemoveLiquidityAmounts::new(pair, args.liquidity_in)?;
+        let RemoveLiquidityAmounts { amount0_out, amount1_out, .. } = amounts;
 
         // Check if amounts meet minimum (slippage protection)
         require!(
@@ -234,31 +307,7 @@ impl<'info> RemoveLiquidity<'info> {
             ErrorCode::SlippageExceeded
         );
 
-        // Ensure sufficient cash reserves: (internally accounted instead of relying on token account balance for deciding liquidity availability)
-        // - Token account balances may include protocol fees and external donation, allowing them
-        //   to be higher than the virtual reserves (r_virtual).
-        // - If the invariant r_cash + r_debt = r_virtual is broken, the pool's solvency
-        //   assumption (r_virtual >= r_debt) may also be violated.
-        require_gte!(
-            pair.cash_reserve0,
-            amount0_out,
-            ErrorCode::InsufficientCashReserve0
-        );
-        require_gte!(
-            pair.cash_reserve1,
-            amount1_out,
-            ErrorCode::InsufficientCashReserve1
-        );
-
-        let post_reserve0 = pair
-            .reserve0
-            .checked_sub(amount0_out)
-            .ok_or(ErrorCode::ReserveUnderflow)?;
-        let post_reserve1 = pair
-            .reserve1
-            .checked_sub(amount1_out)
-            .ok_or(ErrorCode::ReserveUnderflow)?;
-        validate_post_withdraw_debt_coverage(pair, post_reserve0, post_reserve1)?;
+        amounts.apply(pair, args.liquidity_in)?;
 
         // Transfer tokens from pool to user
         transfer_from_vault_to_user(
@@ -303,24 +352,6 @@ impl<'info> RemoveLiquidity<'info> {
             &[&generate_gamm_pair_seeds!(pair)[..]],
         )?;
 
-        // Update reserves
-        pair.reserve0 = post_reserve0;
-        pair.reserve1 = post_reserve1;
-        pair.total_supply = pair
-            .total_supply
-            .checked_sub(args.liquidity_in)
-            .ok_or(ErrorCode::SupplyUnderflow)?;
-
-        // Update cash reserves
-        pair.cash_reserve0 = pair
-            .cash_reserve0
-            .checked_sub(amount0_out)
-            .ok_or(ErrorCode::CashReserveUnderflow)?;
-        pair.cash_reserve1 = pair
-            .cash_reserve1
-            .checked_sub(amount1_out)
-            .ok_or(ErrorCode::CashReserveUnderflow)?;
-
         // Reload LP token account to get updated balance after burn
         user_lp_token_account.reload()?;
         let user_lp_balance = user_lp_token_account.amount;
@@ -379,6 +410,7 @@ fn validate_post_withdraw_debt_coverage(
         pair.directional_ema_price0_nad(),
         pair.ema_price1_nad(),
         pair.directional_ema_price1_nad(),
+        pair.risk_params.post_withdraw_debt_coverage_bps,
     )
 }
 
@@ -391,6 +423,7 @@ fn validate_post_withdraw_debt_coverage_with_prices(
     token0_directional_ema_price_nad: u64,
     token1_ema_price_nad: u64,
     token1_directional_ema_price_nad: u64,
+    coverage_bps: u16,
 ) -> Result<()> {
     let required_token1_for_debt0 = required_collateral_with_impact(
         total_debt0,
@@ -408,11 +441,11 @@ fn validate_post_withdraw_debt_coverage_with_prices(
     )?;
 
     require!(
-        (post_reserve1 as u128) >= with_debt_coverage_buffer(required_token1_for_debt0)?,
+        (post_reserve1 as u128) >= with_debt_coverage_buffer(required_token1_for_debt0, coverage_bps)?,
         ErrorCode::InsufficientPostWithdrawDebtCoverage
     );
     require!(
-        (post_reserve0 as u128) >= with_debt_coverage_buffer(required_token0_for_debt1)?,
+        (post_reserve0 as u128) >= with_debt_coverage_buffer(required_token0_for_debt1, coverage_bps)?,
         ErrorCode::InsufficientPostWithdrawDebtCoverage
     );
 
@@ -451,10 +484,10 @@ fn required_collateral_with_impact(
     CPCurve::calculate_amount_in(collateral_ema_reserve, debt_ema_reserve, debt_amount)
 }
 
-fn with_debt_coverage_buffer(amount: u64) -> Result<u128> {
+fn with_debt_coverage_buffer(amount: u64, coverage_bps: u16) -> Result<u128> {
     ceil_div(
         (amount as u128)
-            .checked_mul(POST_WITHDRAW_DEBT_COVERAGE_BPS as u128)
+            .checked_mul(coverage_bps as u128)
             .ok_or(ErrorCode::DebtMathOverflow)?,
         BPS_DENOMINATOR as u128,
     )
@@ -468,7 +501,7 @@ mod tests {
     #[test]
     fn liquidity_delta_withdrawal_solvency_passes_with_coverage_buffer() {
         validate_post_withdraw_debt_coverage_with_prices(
-            1_000, 1_000, 100, 100, NAD, NAD, NAD, NAD,
+            1_000, 1_000, 100, 100, NAD, NAD, NAD, NAD, DEFAULT_POST_WITHDRAW_DEBT_COVERAGE_BPS,
         )
         .unwrap();
     }
@@ -476,7 +509,7 @@ mod tests {
     #[test]
     fn liquidity_delta_withdrawal_solvency_fails_without_coverage_buffer() {
         let err = validate_post_withdraw_debt_coverage_with_prices(
-            1_000, 1_000, 900, 0, NAD, NAD, NAD, NAD,
+            1_000, 1_000, 900, 0, NAD, NAD, NAD, NAD, DEFAULT_POST_WITHDRAW_DEBT_COVERAGE_BPS,
         )
         .unwrap_err();
 
@@ -486,7 +519,7 @@ mod tests {
     #[test]
     fn liquidity_delta_withdrawal_solvency_fails_with_zero_pessimistic_price() {
         let err = validate_post_withdraw_debt_coverage_with_prices(
-            1_000, 1_000, 100, 0, NAD, NAD, NAD, 0,
+            1_000, 1_000, 100, 0, NAD, NAD, NAD, 0, DEFAULT_POST_WITHDRAW_DEBT_COVERAGE_BPS,
         )
         .unwrap_err();
 
@@ -497,7 +530,7 @@ mod tests {
     fn liquidity_delta_withdrawal_solvency_accounts_for_fee_remaining_in_reserves() {
         let gross = 100_u64;
         let fee = ceil_div(
-            (gross as u128) * (LIQUIDITY_WITHDRAWAL_FEE_BPS as u128),
+            (gross as u128) * (DEFAULT_LIQUIDITY_WITHDRAWAL_FEE_BPS as u128),
             BPS_DENOMINATOR as u128,
         )
         .unwrap() as u64;
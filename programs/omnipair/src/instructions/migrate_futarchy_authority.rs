@@ -0,0 +1,61 @@
+use anchor_lang::{prelude::*, Discriminator};
+use crate::{
+    constants::FUTARCHY_AUTHORITY_SEED_PREFIX,
+    errors::ErrorCode,
+    state::{futarchy_authority::FutarchyAuthority, risk_params::RiskParams},
+    utils::account::{get_size_with_discriminator, grow_account},
+};
+
+#[derive(Accounts)]
+pub struct MigrateFutarchyAuthority<'info> {
+    /// CHECK: A futarchy authority created before the current layout does not deserialize as
+    /// `FutarchyAuthority`. The address is checked here, the discriminator and size in the handler.
+    #[account(mut, seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX], bump)]
+    pub futarchy_authority: UncheckedAccount<'info>,
+
+    /// Pays the rent of the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateFutarchyAuthority<'info> {
+    /// Grows the futarchy authority account to the current `FutarchyAuthority` layout. Permissionless.
+    ///
+    /// New fields are appended at the end of `FutarchyAuthority`, so the old data deserializes unchanged
+    /// and the zeroed tail reads as the defaults, fixed up below:
+    /// - `risk_params`: `RiskParams::default()`, the former constants, so pairs are unchanged
+    pub fn handle_migrate_futarchy_authority(ctx: Context<Self>) -> Result<()> {
+        let futarchy_authority_info = ctx.accounts.futarchy_authority.to_account_info();
+        let new_len = get_size_with_discriminator::<FutarchyAuthority>();
+        {
+            let data = futarchy_authority_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == *FutarchyAuthority::DISCRIMINATOR,
+                anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+            );
+            require_gt!(new_len, data.len(), ErrorCode::FutarchyAuthorityAlreadyMigrated);
+        }
+
+        grow_account(
+            &futarchy_authority_info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_len,
+        )?;
+
+        let mut data = futarchy_authority_info.try_borrow_mut_data()?;
+        let mut futarchy_authority = FutarchyAuthority::try_deserialize(&mut &data[..])?;
+        futarchy_authority.risk_params = RiskParams::default();
+        futarchy_authority.version = FutarchyAuthority::CURRENT_VERSION;
+        futarchy_authority.try_serialize(&mut &mut data[..])?;
+
+        msg!(
+            "Futarchy authority migrated to version {}, default risk params set",
+            futarchy_authority.version
+        );
+
+        Ok(())
+    }
+}
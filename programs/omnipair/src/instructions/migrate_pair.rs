@@ -0,0 +1,73 @@
+use anchor_lang::{prelude::*, Discriminator};
+use crate::{
+    errors::ErrorCode,
+    state::{pair::Pair, rate_model::RateModel},
+    utils::account::{get_size_with_discriminator, grow_account},
+};
+
+#[derive(Accounts)]
+pub struct MigratePair<'info> {
+    /// CHECK: A pair created before the current layout does not deserialize as `Pair`.
+    /// Ownership is checked here, the discriminator and size in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub pair: UncheckedAccount<'info>,
+
+    /// The pair's `rate_model0`, migrated first
+    #[account(mut)]
+    pub rate_model: Account<'info, RateModel>,
+
+    /// Pays the rent of the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigratePair<'info> {
+    /// Grows a pair account to the current `Pair` layout. Permissionless.
+    ///
+    /// New fields are appended at the end of `Pair`, so the old data deserializes unchanged
+    /// and the zeroed tail reads as the defaults, fixed up below:
+    /// - `rate_model1`: the pair's single rate model (`rate_model0`), so rates are unchanged.
+    ///   Only set when still zeroed, so pairs grown by an earlier migration keep theirs. Both sides
+    ///   are then counted in the rate model's `pair_references`, as `initialize` does for new pairs
+    /// - insurance shares: left at 0, the insurance reserves stay off until `set_pair_insurance_share`
+    /// - caps: left at 0, the pair is uncapped until `set_pair_caps`
+    pub fn handle_migrate_pair(ctx: Context<Self>) -> Result<()> {
+        let pair_info = ctx.accounts.pair.to_account_info();
+        let new_len = get_size_with_discriminator::<Pair>();
+        {
+            let data = pair_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == *Pair::DISCRIMINATOR,
+                anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+            );
+            require_gt!(new_len, data.len(), ErrorCode::PairAlreadyMigrated);
+        }
+
+        grow_account(
+            &pair_info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_len,
+        )?;
+
+        let mut data = pair_info.try_borrow_mut_data()?;
+        let mut pair = Pair::try_deserialize(&mut &data[..])?;
+        require_keys_eq!(ctx.accounts.rate_model.key(), pair.rate_model0, ErrorCode::InvalidRateModel);
+        if pair.rate_model1 == Pubkey::default() {
+            pair.rate_model1 = pair.rate_model0;
+            ctx.accounts.rate_model.add_pair_reference()?;
+            ctx.accounts.rate_model.add_pair_reference()?;
+        }
+        pair.try_serialize(&mut &mut data[..])?;
+
+        msg!(
+            "Pair {} migrated, token1 rate model set to {}",
+            pair_info.key(),
+            pair.rate_model1
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use anchor_lang::{prelude::*, Discriminator};
+use crate::{
+    constants::DEFAULT_RATE_MODEL_UPDATE_DELAY_SLOTS,
+    errors::ErrorCode,
+    state::rate_model::RateModel,
+    utils::account::{get_size_with_discriminator, grow_account},
+};
+
+#[derive(Accounts)]
+pub struct MigrateRateModel<'info> {
+    /// CHECK: A rate model created before the current layout does not deserialize as `RateModel`.
+    /// Ownership is checked here, the discriminator and size in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub rate_model: UncheckedAccount<'info>,
+
+    /// Pays the rent of the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateRateModel<'info> {
+    /// Grows a rate model account to the current `RateModel` layout. Permissionless.
+    ///
+    /// New fields are appended at the end of `RateModel`, so the old data deserializes unchanged
+    /// and the zeroed tail reads as the defaults, fixed up below:
+    /// - `kind`: `Exponential`, so rates are unchanged
+    /// - `update_delay_slots`: `DEFAULT_RATE_MODEL_UPDATE_DELAY_SLOTS`
+    /// - `pending_update`: none
+    /// - `pair_references`: 0, its pairs are counted as they are migrated by `migrate_pair`
+    pub fn handle_migrate_rate_model(ctx: Context<Self>) -> Result<()> {
+        let rate_model_info = ctx.accounts.rate_model.to_account_info();
+        let new_len = get_size_with_discriminator::<RateModel>();
+        {
+            let data = rate_model_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == *RateModel::DISCRIMINATOR,
+                anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+            );
+            require_gt!(new_len, data.len(), ErrorCode::RateModelAlreadyMigrated);
+        }
+
+        grow_account(
+            &rate_model_info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_len,
+        )?;
+
+        let mut data = rate_model_info.try_borrow_mut_data()?;
+        let mut rate_model = RateModel::try_deserialize(&mut &data[..])?;
+        rate_model.update_delay_slots = DEFAULT_RATE_MODEL_UPDATE_DELAY_SLOTS;
+        rate_model.try_serialize(&mut &mut data[..])?;
+
+        msg!(
+            "Rate model {} migrated, update delay set to {} slots",
+            rate_model_info.key(),
+            rate_model.update_delay_slots
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use anchor_lang::{prelude::*, Discriminator};
+use crate::{
+    errors::ErrorCode,
+    state::user_position::UserPosition,
+    utils::account::{get_size_with_discriminator, grow_account},
+};
+
+#[derive(Accounts)]
+pub struct MigrateUserPosition<'info> {
+    /// CHECK: A position created before the current layout does not deserialize as `UserPosition`.
+    /// Ownership is checked here, the discriminator and size in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub user_position: UncheckedAccount<'info>,
+
+    /// Pays the rent of the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateUserPosition<'info> {
+    /// Grows a user position account to the current `UserPosition` layout. Permissionless.
+    ///
+    /// New fields are appended at the end of `UserPosition`, so the old data deserializes unchanged
+    /// and the zeroed tail reads as the defaults:
+    /// - `cross_margin`: false, the position stays isolated
+    /// - `position_index`: 0, positions created before sub-accounts are the default position
+    /// - `delegate_permission`: `None`, no delegate
+    /// - `open_orders`: 0, orders did not exist before
+    pub fn handle_migrate_user_position(ctx: Context<Self>) -> Result<()> {
+        let user_position_info = ctx.accounts.user_position.to_account_info();
+        let new_len = get_size_with_discriminator::<UserPosition>();
+        {
+            let data = user_position_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == *UserPosition::DISCRIMINATOR,
+                anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+            );
+            require_gt!(new_len, data.len(), ErrorCode::UserPositionAlreadyMigrated);
+        }
+
+        grow_account(
+            &user_position_info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_len,
+        )?;
+
+        msg!("User position {} migrated", user_position_info.key());
+
+        Ok(())
+    }
+}
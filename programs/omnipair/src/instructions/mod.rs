@@ -3,6 +3,10 @@ pub mod liquidity;
 pub mod lending;
 pub mod futarchy;
 pub mod emit_value;
+pub mod migrate_pair;
+pub mod migrate_rate_model;
+pub mod migrate_futarchy_authority;
+pub mod migrate_user_position;
 
 pub use spot::*;
 pub use liquidity::*;
@@ -10,6 +14,20 @@ pub use lending::common::*;
 pub use lending::add_collateral::*;
 pub use lending::borrow::*;
 pub use lending::liquidate::*;
+pub use lending::liquidate_with_repay::*;
 pub use lending::flashloan::*;
+pub use lending::open_leveraged::*;
+pub use lending::close_position::*;
+pub use lending::set_margin_mode::*;
+pub use lending::set_position_delegate::*;
+pub use lending::transfer_position::*;
+pub use lending::close_position_account::*;
+pub use lending::place_position_order::*;
+pub use lending::cancel_position_order::*;
+pub use lending::execute_position_order::*;
 pub use futarchy::*;
 pub use emit_value::*;
+pub use migrate_pair::*;
+pub use migrate_rate_model::*;
+pub use migrate_futarchy_authority::*;
+pub use migrate_user_position::*;
@@ -1,3 +1,5 @@
 pub mod swap;
+pub mod swap_route;
 
-pub use swap::*;
\ No newline at end of file
+pub use swap::*;
+pub use swap_route::*;
@@ -21,6 +21,138 @@ pub struct SwapArgs {
     pub min_amount_out: u64,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SwapExactOutArgs {
+    pub amount_out: u64,
+    pub max_amount_in: u64,
+}
+
+/// Resolved amounts of a single swap against the pair's virtual reserves.
+/// The swap fee is charged on the input token and split into an LP portion
+/// (kept in reserves) and a futarchy portion (kept out of reserves).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapAmounts {
+    pub amount_in: u64,
+    pub amount_in_after_fee: u64,
+    pub amount_out: u64,
+    pub lp_fee: u64,
+    pub protocol_fee: u64,
+}
+
+impl SwapAmounts {
+    fn swap_fee(amount_in: u64, swap_fee_bps: u16) -> Result<u64> {
+        Ok(ceil_div((amount_in as u128)
+            .checked_mul(swap_fee_bps as u128)
+            .ok_or(ErrorCode::FeeMathOverflow)?,
+            BPS_DENOMINATOR as u128,
+        ).ok_or(ErrorCode::FeeMathOverflow)? as u64)
+    }
+
+    fn from_amount_in(pair: &Pair, futarchy_swap_bps: u16, amount_in: u64, amount_out: u64) -> Result<Self> {
+        // Swap fee = LP fee + Futarchy fee
+        let swap_fee = Self::swap_fee(amount_in, pair.swap_fee_bps)?;
+
+        // Calculate futarchy fee portion of the swap fee
+        let protocol_fee = ceil_div((swap_fee as u128)
+            .checked_mul(futarchy_swap_bps as u128)
+            .ok_or(ErrorCode::FeeMathOverflow)?,
+            BPS_DENOMINATOR as u128,
+        ).ok_or(ErrorCode::FeeMathOverflow)? as u64;
+
+        Ok(Self {
+            amount_in,
+            amount_in_after_fee: amount_in.checked_sub(swap_fee).ok_or(ErrorCode::FeeMathOverflow)?,
+            amount_out,
+            lp_fee: swap_fee.saturating_sub(protocol_fee),
+            protocol_fee,
+        })
+    }
+
+    /// Amounts for selling exactly `amount_in` of the input token.
+    pub fn exact_in(pair: &Pair, futarchy_swap_bps: u16, is_token0_in: bool, amount_in: u64) -> Result<Self> {
+        let mut amounts = Self::from_amount_in(pair, futarchy_swap_bps, amount_in, 0)?;
+        let (reserve_in, reserve_out) = Self::reserves(pair, is_token0_in);
+
+        // Δy = (Δx * y) / (x + Δx)
+        amounts.amount_out = CPCurve::calculate_amount_out(reserve_in, reserve_out, amounts.amount_in_after_fee)?;
+        Ok(amounts)
+    }
+
+    /// Amounts for buying exactly `amount_out` of the output token.
+    /// The input is rounded up so the pool never receives less than the curve requires.
+    pub fn exact_out(pair: &Pair, futarchy_swap_bps: u16, is_token0_in: bool, amount_out: u64) -> Result<Self> {
+        let (reserve_in, reserve_out) = Self::reserves(pair, is_token0_in);
+
+        // Δx = ceil(Δy * x / (y - Δy))
+        let required_after_fee = CPCurve::calculate_amount_in(reserve_in, reserve_out, amount_out)?;
+
+        // Gross up for the swap fee: amount_in = ceil(Δx * BPS / (BPS - fee_bps))
+        let fee_complement = BPS_DENOMINATOR
+            .checked_sub(pair.swap_fee_bps)
+            .filter(|bps| *bps > 0)
+            .ok_or(ErrorCode::FeeMathOverflow)?;
+        let mut amount_in: u64 = ceil_div((required_after_fee as u128)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::FeeMathOverflow)?,
+            fee_complement as u128,
+        ).ok_or(ErrorCode::FeeMathOverflow)?
+            .try_into()
+            .map_err(|_| ErrorCode::FeeMathOverflow)?;
+
+        // The fee is itself rounded up, so the gross-up can fall short by a unit
+        while amount_in.checked_sub(Self::swap_fee(amount_in, pair.swap_fee_bps)?).ok_or(ErrorCode::FeeMathOverflow)? < required_after_fee {
+            amount_in = amount_in.checked_add(1).ok_or(ErrorCode::FeeMathOverflow)?;
+        }
+
+        Self::from_amount_in(pair, futarchy_swap_bps, amount_in, amount_out)
+    }
+
+    fn reserves(pair: &Pair, is_token0_in: bool) -> (u64, u64) {
+        match is_token0_in {
+            true => (pair.reserve0, pair.reserve1),
+            false => (pair.reserve1, pair.reserve0),
+        }
+    }
+
+    /// Applies the swap to the pair's reserves, enforcing `r_cash >= r_out` and `x * y >= last_k`.
+    pub fn apply(&self, pair: &mut Pair, is_token0_in: bool) -> Result<()> {
+        let last_k = (pair.reserve0 as u128).checked_mul(pair.reserve1 as u128).ok_or(ErrorCode::InvariantOverflow)?;
+        let (reserve_in, reserve_out) = Self::reserves(pair, is_token0_in);
+
+        // Calculate the amount in with the LP portion of the fee:
+        // amount_in_with_lp_fee = amount_in - swap_fee + lp_fee = amount_in - futarchy_fee
+        let amount_in_with_lp_fee = self.amount_in.checked_sub(self.protocol_fee).ok_or(ErrorCode::Overflow)?;
+        let new_reserve_in = reserve_in.checked_add(amount_in_with_lp_fee).ok_or(ErrorCode::Overflow)?;
+        let new_reserve_out = reserve_out.checked_sub(self.amount_out).ok_or(ErrorCode::Overflow)?;
+
+        // 1. r_cash >= r_out
+        match is_token0_in {
+            true => require_gte!(pair.cash_reserve1, self.amount_out, ErrorCode::InsufficientCashReserve1),
+            false => require_gte!(pair.cash_reserve0, self.amount_out, ErrorCode::InsufficientCashReserve0),
+        }
+
+        // Update reserves
+        match is_token0_in {
+            true => {
+                pair.reserve0 = new_reserve_in;
+                pair.reserve1 = new_reserve_out;
+                pair.cash_reserve0 = pair.cash_reserve0.saturating_add(amount_in_with_lp_fee);
+                pair.cash_reserve1 = pair.cash_reserve1.saturating_sub(self.amount_out);
+            },
+            false => {
+                pair.reserve1 = new_reserve_in;
+                pair.reserve0 = new_reserve_out;
+                pair.cash_reserve1 = pair.cash_reserve1.saturating_add(amount_in_with_lp_fee);
+                pair.cash_reserve0 = pair.cash_reserve0.saturating_sub(self.amount_out);
+            }
+        }
+
+        // 2. x * y >= last_k
+        require_gte!((pair.reserve0 as u128).checked_mul(pair.reserve1 as u128).ok_or(ErrorCode::Overflow)?, last_k, ErrorCode::BrokenInvariant);
+        Ok(())
+    }
+}
+
 #[event_cpi]
 #[derive(Accounts)]
 pub struct Swap<'info> { 
@@ -34,9 +166,15 @@ pub struct Swap<'info> {
 
     #[account(
         mut,
-        address = pair.rate_model,
+        address = pair.rate_model0,
+    )]
+    pub rate_model0: Account<'info, RateModel>,
+
+    #[account(
+        mut,
+        address = pair.rate_model1,
     )]
-    pub rate_model: Account<'info, RateModel>,
+    pub rate_model1: Account<'info, RateModel>,
 
     #[account(
         seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
@@ -98,7 +236,18 @@ impl<'info> Swap<'info> {
 
         require!(amount_in > 0, ErrorCode::AmountZero);
         require_gte!(self.user_token_in_account.amount, amount_in, ErrorCode::InsufficientBalance);
-        
+
+        self.validate_vaults()
+    }
+
+    pub fn validate_exact_out(&self, args: &SwapExactOutArgs) -> Result<()> {
+        require!(args.amount_out > 0, ErrorCode::AmountZero);
+        require!(args.max_amount_in > 0, ErrorCode::AmountZero);
+
+        self.validate_vaults()
+    }
+
+    fn validate_vaults(&self) -> Result<()> {
         // Ensure token_in_vault and token_out_vault are different accounts
         require_keys_neq!(
             self.token_in_vault.key(),
@@ -141,7 +290,8 @@ impl<'info> Swap<'info> {
     pub fn update(&mut self) -> Result<()> {
         let pair_key = self.pair.to_account_info().key();
         self.pair.update(
-            &self.rate_model,
+            &self.rate_model0,
+            &self.rate_model1,
             &self.futarchy_authority,
             pair_key,
             Some(self.event_authority.to_account_info()),
@@ -155,11 +305,50 @@ impl<'info> Swap<'info> {
         Ok(())
     }
 
+    pub fn update_and_validate_swap_exact_out(&mut self, args: &SwapExactOutArgs) -> Result<()> {
+        self.update()?;
+        self.validate_exact_out(args)?;
+        Ok(())
+    }
+
     pub fn handle_swap(ctx: Context<Self>, args: SwapArgs) -> Result<()> {
         let SwapArgs { amount_in, min_amount_out } = args;
+        let is_token0_in = ctx.accounts.user_token_in_account.mint == ctx.accounts.pair.token0;
+        let amounts = SwapAmounts::exact_in(
+            &ctx.accounts.pair,
+            ctx.accounts.futarchy_authority.revenue_share.swap_bps,
+            is_token0_in,
+            amount_in,
+        )?;
+
+        require_gte!(amounts.amount_out, min_amount_out, ErrorCode::SlippageExceeded);
+
+        Self::execute_swap(ctx, amounts, is_token0_in, false)
+    }
+
+    pub fn handle_swap_exact_out(ctx: Context<Self>, args: SwapExactOutArgs) -> Result<()> {
+        let SwapExactOutArgs { amount_out, max_amount_in } = args;
+        let is_token0_in = ctx.accounts.user_token_in_account.mint == ctx.accounts.pair.token0;
+        let amounts = SwapAmounts::exact_out(
+            &ctx.accounts.pair,
+            ctx.accounts.futarchy_authority.revenue_share.swap_bps,
+            is_token0_in,
+            amount_out,
+        )?;
+
+        require_gte!(max_amount_in, amounts.amount_in, ErrorCode::SlippageExceeded);
+        require_gte!(
+            ctx.accounts.user_token_in_account.amount,
+            amounts.amount_in,
+            ErrorCode::InsufficientBalance
+        );
+
+        Self::execute_swap(ctx, amounts, is_token0_in, true)
+    }
+
+    fn execute_swap(ctx: Context<Self>, amounts: SwapAmounts, is_token0_in: bool, is_exact_out: bool) -> Result<()> {
         let Swap {
             pair,
-            futarchy_authority,
             token_in_vault,
             token_out_vault,
             user_token_in_account,
@@ -171,63 +360,8 @@ impl<'info> Swap<'info> {
             user,
             ..
         } = ctx.accounts;
-        let last_k = (pair.reserve0 as u128).checked_mul(pair.reserve1 as u128).ok_or(ErrorCode::InvariantOverflow)?;
-        let is_token0_in = user_token_in_account.mint == pair.token0;
-
-        // Swap fee = LP fee + Futarchy fee
-        let swap_fee = ceil_div((amount_in as u128)
-            .checked_mul(pair.swap_fee_bps as u128)
-            .ok_or(ErrorCode::FeeMathOverflow)?,
-            BPS_DENOMINATOR as u128,
-        ).ok_or(ErrorCode::FeeMathOverflow)? as u64;
-
-        // Calculate futarchy fee portion of the swap fee
-        let futarchy_fee = ceil_div((swap_fee as u128)
-            .checked_mul(futarchy_authority.revenue_share.swap_bps as u128)
-            .ok_or(ErrorCode::FeeMathOverflow)?,
-            BPS_DENOMINATOR as u128,
-        ).ok_or(ErrorCode::FeeMathOverflow)? as u64;
-
-        // amount_in_after_swap_fee = amount_in - swap_fee
-        let amount_in_after_swap_fee = amount_in.checked_sub(swap_fee).ok_or(ErrorCode::FeeMathOverflow)?;
-
-        let reserve_in = if is_token0_in { pair.reserve0 } else { pair.reserve1 };
-        let reserve_out = if is_token0_in { pair.reserve1 } else { pair.reserve0 };
-
-        // Δy = (Δx * y) / (x + Δx)
-        let amount_out = CPCurve::calculate_amount_out(reserve_in, reserve_out, amount_in_after_swap_fee)?;
-
-        // Calculate the amount in with the LP portion of the fee:
-        // amount_in_with_lp_fee = amount_in - swap_fee + lp_fee = amount_in - futarchy_fee
-        let amount_in_with_lp_fee = amount_in.checked_sub(futarchy_fee).ok_or(ErrorCode::Overflow)?;
-        let new_reserve_in = reserve_in.checked_add(amount_in_with_lp_fee).ok_or(ErrorCode::Overflow)?;
-        let new_reserve_out = reserve_out.checked_sub(amount_out).ok_or(ErrorCode::Overflow)?;
-
-        require_gte!(amount_out, min_amount_out, ErrorCode::SlippageExceeded);
-        // 1. r_cash >= r_out
-        match is_token0_in {
-            true => require_gte!(pair.cash_reserve1, amount_out, ErrorCode::InsufficientCashReserve1),
-            false => require_gte!(pair.cash_reserve0, amount_out, ErrorCode::InsufficientCashReserve0),
-        }
-
-        // Update reserves
-        match is_token0_in {
-            true => {
-                pair.reserve0 = new_reserve_in;
-                pair.reserve1 = new_reserve_out;
-                pair.cash_reserve0 = pair.cash_reserve0.saturating_add(amount_in_with_lp_fee);
-                pair.cash_reserve1 = pair.cash_reserve1.saturating_sub(amount_out);
-            },
-            false => {
-                pair.reserve1 = new_reserve_in;
-                pair.reserve0 = new_reserve_out;
-                pair.cash_reserve1 = pair.cash_reserve1.saturating_add(amount_in_with_lp_fee);
-                pair.cash_reserve0 = pair.cash_reserve0.saturating_sub(amount_out);
-            }
-        }
 
-        // 2. x * y >= last_k
-        require_gte!((pair.reserve0 as u128).checked_mul(pair.reserve1 as u128).ok_or(ErrorCode::Overflow)?, last_k, ErrorCode::BrokenInvariant);
+        amounts.apply(pair, is_token0_in)?;
 
         // Transfer tokens
         // First: Transfer user's input tokens into the vault
@@ -240,7 +374,7 @@ impl<'info> Swap<'info> {
                 true => token_program.to_account_info(),
                 false => token_2022_program.to_account_info(),
             },
-            amount_in,
+            amounts.amount_in,
             token_in_mint.decimals,
         )?;
 
@@ -254,25 +388,111 @@ impl<'info> Swap<'info> {
                 true => token_program.to_account_info(),
                 false => token_2022_program.to_account_info(),
             },
-            amount_out,
+            amounts.amount_out,
             token_out_mint.decimals,
             &[&generate_gamm_pair_seeds!(pair)[..]],
         )?;
-        
-        let lp_fee = swap_fee.checked_sub(futarchy_fee).unwrap_or(0);
 
         emit_cpi!(SwapEvent {
             metadata: EventMetadata::new(user.key(), pair.key()),
             reserve0: pair.reserve0,
             reserve1: pair.reserve1,
             is_token0_in,
-            amount_in: amount_in,
-            amount_out: amount_out,
-            amount_in_after_fee: amount_in_after_swap_fee as u64,
-            lp_fee,
-            protocol_fee: futarchy_fee,
+            is_exact_out,
+            amount_in: amounts.amount_in,
+            amount_out: amounts.amount_out,
+            amount_in_after_fee: amounts.amount_in_after_fee,
+            lp_fee: amounts.lp_fee,
+            protocol_fee: amounts.protocol_fee,
         });
         
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_pair(reserve0: u64, reserve1: u64) -> Pair {
+        let mut pair = Pair::initialize(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            6,
+            6,
+            Pubkey::new_unique(),
+            30,
+            60_000,
+            Some(8_000),
+            0,
+            [0; 32],
+            VERSION,
+            1,
+            VaultBumps::default(),
+            0,
+            RiskParams::default(),
+        );
+        pair.reserve0 = reserve0;
+        pair.reserve1 = reserve1;
+        pair.cash_reserve0 = reserve0;
+        pair.cash_reserve1 = reserve1;
+        pair
+    }
+
+    #[test]
+    fn exact_out_delivers_requested_amount_with_minimal_input() {
+        let pair = test_pair(1_000_000_000, 2_000_000_000);
+
+        for amount_out in [1, 7, 1_000, 123_456, 50_000_000] {
+            let amounts = SwapAmounts::exact_out(&pair, 2_000, true, amount_out).unwrap();
+            assert_eq!(amounts.amount_out, amount_out);
+
+            // Selling the quoted input must buy at least the requested output...
+            let forward = SwapAmounts::exact_in(&pair, 2_000, true, amounts.amount_in).unwrap();
+            assert!(forward.amount_out >= amount_out);
+            // ...and one unit less must not
+            let short = SwapAmounts::exact_in(&pair, 2_000, true, amounts.amount_in - 1).unwrap();
+            assert!(short.amount_out < amount_out);
+        }
+    }
+
+    #[test]
+    fn exact_out_matches_exact_in_fee_split() {
+        let pair = test_pair(5_000_000_000, 1_000_000_000);
+        let amounts = SwapAmounts::exact_out(&pair, 2_500, false, 10_000_000).unwrap();
+        let forward = SwapAmounts::exact_in(&pair, 2_500, false, amounts.amount_in).unwrap();
+
+        assert_eq!(amounts.amount_in_after_fee, forward.amount_in_after_fee);
+        assert_eq!(amounts.lp_fee, forward.lp_fee);
+        assert_eq!(amounts.protocol_fee, forward.protocol_fee);
+    }
+
+    #[test]
+    fn apply_keeps_invariant_and_moves_cash() {
+        let mut pair = test_pair(1_000_000_000, 1_000_000_000);
+        let last_k = pair.k();
+        let amounts = SwapAmounts::exact_out(&pair, 2_000, true, 1_000_000).unwrap();
+
+        amounts.apply(&mut pair, true).unwrap();
+
+        assert!(pair.k() >= last_k);
+        assert_eq!(pair.cash_reserve1, 1_000_000_000 - 1_000_000);
+        assert_eq!(pair.cash_reserve0, 1_000_000_000 + amounts.amount_in - amounts.protocol_fee);
+    }
+
+    #[test]
+    fn apply_rejects_output_above_cash() {
+        let mut pair = test_pair(1_000_000_000, 1_000_000_000);
+        pair.cash_reserve1 = 10;
+        let amounts = SwapAmounts::exact_out(&pair, 0, true, 11).unwrap();
+
+        assert!(amounts.apply(&mut pair, true).is_err());
+    }
+
+    #[test]
+    fn exact_out_rejects_draining_reserve() {
+        let pair = test_pair(1_000_000_000, 1_000_000_000);
+        assert!(SwapAmounts::exact_out(&pair, 0, true, 1_000_000_000).is_err());
+    }
+}
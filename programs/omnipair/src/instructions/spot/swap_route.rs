@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{Token, TokenAccount, Mint},
+    token_interface::{Token2022},
+};
+use crate::{
+    state::*,
+    constants::*,
+    errors::ErrorCode,
+    events::*,
+    instructions::spot::SwapAmounts,
+    utils::token::{transfer_from_user_to_vault, transfer_from_vault_to_user, transfer_from_vault_to_vault},
+    generate_gamm_pair_seeds,
+};
+
+/// Remaining accounts consumed by each hop, in order:
+/// `[pair (mut), rate_model0, rate_model1, token_in_vault (mut), token_out_vault (mut), token_out_mint]`
+pub const SWAP_ROUTE_ACCOUNTS_PER_HOP: usize = 6;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SwapRouteArgs {
+    pub amount_in: u64,
+    /// End-to-end bound on the output of the last hop
+    pub min_amount_out: u64,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    #[account(
+        seeds = [FUTARCHY_AUTHORITY_SEED_PREFIX],
+        bump = futarchy_authority.bump
+    )]
+    pub futarchy_authority: Account<'info, FutarchyAuthority>,
+
+    #[account(
+        mut,
+        constraint = user_token_in_account.mint == token_in_mint.key() @ ErrorCode::InvalidTokenAccount,
+        token::authority = user,
+    )]
+    pub user_token_in_account: Account<'info, TokenAccount>,
+    #[account(mut,
+        constraint = user_token_out_account.mint == token_out_mint.key() @ ErrorCode::InvalidTokenAccount,
+        token::authority = user,
+    )]
+    pub user_token_out_account: Account<'info, TokenAccount>,
+
+    pub token_in_mint: Box<Account<'info, Mint>>,
+    pub token_out_mint: Box<Account<'info, Mint>>,
+
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+}
+
+/// Accounts of a single hop, loaded and checked from remaining accounts.
+struct RouteHop<'info> {
+    pair: Box<Account<'info, Pair>>,
+    rate_model0: Account<'info, RateModel>,
+    rate_model1: Account<'info, RateModel>,
+    token_in_vault: Account<'info, TokenAccount>,
+    token_out_vault: Account<'info, TokenAccount>,
+    token_out_mint: Box<Account<'info, Mint>>,
+    is_token0_in: bool,
+}
+
+impl<'info> RouteHop<'info> {
+    fn load(accounts: &'info [AccountInfo<'info>], token_in_mint: Pubkey) -> Result<Self> {
+        let pair = Box::new(Account::<Pair>::try_from(&accounts[0])?);
+        let expected_pair = Pubkey::create_program_address(&generate_gamm_pair_seeds!(pair), &crate::ID)
+            .map_err(|_| ErrorCode::InvalidSwapRoute)?;
+        require_keys_eq!(pair.key(), expected_pair, ErrorCode::InvalidSwapRoute);
+
+        let rate_model0 = Account::<RateModel>::try_from(&accounts[1])?;
+        require_keys_eq!(rate_model0.key(), pair.rate_model0, ErrorCode::InvalidRateModel);
+        let rate_model1 = Account::<RateModel>::try_from(&accounts[2])?;
+        require_keys_eq!(rate_model1.key(), pair.rate_model1, ErrorCode::InvalidRateModel);
+
+        require!(token_in_mint == pair.token0 || token_in_mint == pair.token1, ErrorCode::InvalidSwapRoute);
+        let is_token0_in = token_in_mint == pair.token0;
+        let token_out_mint = Box::new(Account::<Mint>::try_from(&accounts[5])?);
+        require_keys_eq!(
+            token_out_mint.key(),
+            if is_token0_in { pair.token1 } else { pair.token0 },
+            ErrorCode::InvalidSwapRoute
+        );
+
+        let token_in_vault = Self::load_reserve_vault(&pair, &accounts[3], &token_in_mint)?;
+        let token_out_vault = Self::load_reserve_vault(&pair, &accounts[4], &token_out_mint.key())?;
+
+        Ok(Self { pair, rate_model0, rate_model1, token_in_vault, token_out_vault, token_out_mint, is_token0_in })
+    }
+
+    fn load_reserve_vault(pair: &Account<'info, Pair>, info: &'info AccountInfo<'info>, mint: &Pubkey) -> Result<Account<'info, TokenAccount>> {
+        let pair_key = pair.key();
+        let expected_vault = Pubkey::create_program_address(
+            &[
+                RESERVE_VAULT_SEED_PREFIX,
+                pair_key.as_ref(),
+                mint.as_ref(),
+                &[pair.get_reserve_vault_bump(mint)],
+            ],
+            &crate::ID,
+        ).map_err(|_| ErrorCode::InvalidVault)?;
+        require_keys_eq!(info.key(), expected_vault, ErrorCode::InvalidVault);
+        Account::<TokenAccount>::try_from(info)
+    }
+}
+
+impl<'info> SwapRoute<'info> {
+    pub fn validate(&self, args: &SwapRouteArgs) -> Result<()> {
+        require!(args.amount_in > 0, ErrorCode::AmountZero);
+        require_gte!(self.user_token_in_account.amount, args.amount_in, ErrorCode::InsufficientBalance);
+        require_keys_neq!(self.token_in_mint.key(), self.token_out_mint.key(), ErrorCode::InvalidSwapRoute);
+        Ok(())
+    }
+
+    fn token_program_for(&self, mint: &AccountInfo<'info>) -> AccountInfo<'info> {
+        match mint.owner == self.token_program.key {
+            true => self.token_program.to_account_info(),
+            false => self.token_2022_program.to_account_info(),
+        }
+    }
+
+    pub fn handle_swap_route(ctx: Context<'_, '_, 'info, 'info, Self>, args: SwapRouteArgs) -> Result<()> {
+        let SwapRouteArgs { amount_in, min_amount_out } = args;
+        let remaining_accounts = ctx.remaining_accounts;
+
+        let hop_count = remaining_accounts.len() / SWAP_ROUTE_ACCOUNTS_PER_HOP;
+        require!(
+            hop_count > 0 && hop_count * SWAP_ROUTE_ACCOUNTS_PER_HOP == remaining_accounts.len(),
+            ErrorCode::InvalidSwapRoute
+        );
+        require_gte!(MAX_SWAP_ROUTE_HOPS as usize, hop_count, ErrorCode::InvalidSwapRoute);
+
+        // Each pair is deserialized once and written back at the end of its hop,
+        // so a pair appearing twice would overwrite its own earlier state
+        let mut pair_keys: Vec<Pubkey> = Vec::with_capacity(hop_count);
+
+        let user_key = ctx.accounts.user.key();
+        let swap_bps = ctx.accounts.futarchy_authority.revenue_share.swap_bps;
+        let mut token_in_mint = ctx.accounts.token_in_mint.key();
+        let mut hop_amount_in = amount_in;
+        let mut previous_hop: Option<RouteHop<'info>> = None;
+
+        for (index, hop_accounts) in remaining_accounts.chunks(SWAP_ROUTE_ACCOUNTS_PER_HOP).enumerate() {
+            let mut hop = RouteHop::load(hop_accounts, token_in_mint)?;
+            let pair_key = hop.pair.key();
+            require!(!pair_keys.contains(&pair_key), ErrorCode::InvalidSwapRoute);
+            pair_keys.push(pair_key);
+
+            hop.pair.update(
+                &hop.rate_model0,
+                &hop.rate_model1,
+                &ctx.accounts.futarchy_authority,
+                pair_key,
+                Some(ctx.accounts.event_authority.to_account_info()),
+            )?;
+
+            let amounts = SwapAmounts::exact_in(&hop.pair, swap_bps, hop.is_token0_in, hop_amount_in)?;
+            amounts.apply(&mut hop.pair, hop.is_token0_in)?;
+
+            // Fund this hop: from the user on the first hop, otherwise straight from the previous pair
+            match previous_hop.take() {
+                None => transfer_from_user_to_vault(
+                    ctx.accounts.user.to_account_info(),
+                    ctx.accounts.user_token_in_account.to_account_info(),
+                    hop.token_in_vault.to_account_info(),
+                    ctx.accounts.token_in_mint.to_account_info(),
+                    ctx.accounts.token_program_for(&ctx.accounts.token_in_mint.to_account_info()),
+                    amounts.amount_in,
+                    ctx.accounts.token_in_mint.decimals,
+                )?,
+                Some(previous) => transfer_from_vault_to_vault(
+                    previous.pair.to_account_info(),
+                    previous.token_out_vault.to_account_info(),
+                    hop.token_in_vault.to_account_info(),
+                    previous.token_out_mint.to_account_info(),
+                    ctx.accounts.token_program_for(&previous.token_out_mint.to_account_info()),
+                    amounts.amount_in,
+                    previous.token_out_mint.decimals,
+                    &[&generate_gamm_pair_seeds!(previous.pair)[..]],
+                )?,
+            }
+
+            hop.pair.exit(&crate::ID)?;
+
+            emit_cpi!(SwapEvent {
+                metadata: EventMetadata::new(user_key, pair_key),
+                reserve0: hop.pair.reserve0,
+                reserve1: hop.pair.reserve1,
+                is_token0_in: hop.is_token0_in,
+                is_exact_out: false,
+                amount_in: amounts.amount_in,
+                amount_out: amounts.amount_out,
+                amount_in_after_fee: amounts.amount_in_after_fee,
+                lp_fee: amounts.lp_fee,
+                protocol_fee: amounts.protocol_fee,
+            });
+
+            if index + 1 < hop_count {
+                token_in_mint = hop.token_out_mint.key();
+                hop_amount_in = amounts.amount_out;
+                previous_hop = Some(hop);
+                continue;
+            }
+
+            // Last hop: the route must end in the requested token
+            require_keys_eq!(hop.token_out_mint.key(), ctx.accounts.token_out_mint.key(), ErrorCode::InvalidSwapRoute);
+            require_gte!(amounts.amount_out, min_amount_out, ErrorCode::SlippageExceeded);
+
+            transfer_from_vault_to_user(
+                hop.pair.to_account_info(),
+                hop.token_out_vault.to_account_info(),
+                ctx.accounts.user_token_out_account.to_account_info(),
+                ctx.accounts.token_out_mint.to_account_info(),
+                ctx.accounts.token_program_for(&ctx.accounts.token_out_mint.to_account_info()),
+                amounts.amount_out,
+                ctx.accounts.token_out_mint.decimals,
+                &[&generate_gamm_pair_seeds!(hop.pair)[..]],
+            )?;
+        }
+
+        Ok(())
+    }
+}
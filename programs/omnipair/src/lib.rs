@@ -4,12 +4,15 @@ pub mod constants;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+#[cfg(feature = "simulation")]
+pub mod simulation;
 pub mod state;
 pub mod utils;
 
 pub use utils::*;
 pub use instructions::*;
 pub use utils::account::*;
+use state::risk_params::RiskParams;
 pub use instructions::emit_value::{EmitValueArgs, PairViewKind, UserPositionViewKind, ViewPairData, ViewUserPositionData};
 
 #[cfg(not(feature = "no-entrypoint"))]
@@ -96,15 +99,72 @@ pub mod omnipair {
         SetPairReduceOnly::handle_set_pair_reduce_only(ctx, args)
     }
 
+    /// Grows a pair created before the per-side rate models to the current layout.
+    /// This instruction is permissionless - the payer only funds the added rent.
+    pub fn migrate_pair(ctx: Context<MigratePair>) -> Result<()> {
+        MigratePair::handle_migrate_pair(ctx)
+    }
+
+    #[access_control(ctx.accounts.validate())]
     pub fn set_pair_rate_model(ctx: Context<SetPairRateModel>) -> Result<()> {
         SetPairRateModel::handle_set_pair_rate_model(ctx)
     }
 
+    #[access_control(SetPairInsuranceShare::validate(&args))]
+    pub fn set_pair_insurance_share(ctx: Context<SetPairInsuranceShare>, args: SetPairInsuranceShareArgs) -> Result<()> {
+        SetPairInsuranceShare::handle_set_pair_insurance_share(ctx, args)
+    }
+
     #[access_control(CreateRateModel::validate(&args))]
     pub fn create_rate_model(ctx: Context<CreateRateModel>, args: CreateRateModelArgs) -> Result<()> {
         CreateRateModel::handle_create_rate_model(ctx, args)
     }
 
+    #[access_control(UpdateRateModel::validate(&args))]
+    pub fn update_rate_model(ctx: Context<UpdateRateModel>, args: UpdateRateModelArgs) -> Result<()> {
+        UpdateRateModel::handle_update_rate_model(ctx, args)
+    }
+
+    pub fn apply_rate_model_update<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApplyRateModelUpdate<'info>>,
+    ) -> Result<()> {
+        ApplyRateModelUpdate::handle_apply_rate_model_update(ctx)
+    }
+
+    /// Grows a rate model created before the kinked variant to the current layout.
+    /// This instruction is permissionless - the payer only funds the added rent.
+    pub fn migrate_rate_model(ctx: Context<MigrateRateModel>) -> Result<()> {
+        MigrateRateModel::handle_migrate_rate_model(ctx)
+    }
+
+    /// Grows the futarchy authority created before the risk parameters to the current layout.
+    /// This instruction is permissionless - the payer only funds the added rent.
+    pub fn migrate_futarchy_authority(ctx: Context<MigrateFutarchyAuthority>) -> Result<()> {
+        MigrateFutarchyAuthority::handle_migrate_futarchy_authority(ctx)
+    }
+
+    /// Grows a user position created before cross margin to the current layout.
+    /// This instruction is permissionless - the payer only funds the added rent.
+    pub fn migrate_user_position(ctx: Context<MigrateUserPosition>) -> Result<()> {
+        MigrateUserPosition::handle_migrate_user_position(ctx)
+    }
+
+    #[access_control(SetGlobalRiskParams::validate(&risk_params))]
+    pub fn set_global_risk_params(ctx: Context<SetGlobalRiskParams>, risk_params: RiskParams) -> Result<()> {
+        SetGlobalRiskParams::handle_set_global_risk_params(ctx, risk_params)
+    }
+
+    #[access_control(SetPairRiskParams::validate(&args))]
+    pub fn set_pair_risk_params(ctx: Context<SetPairRiskParams>, args: SetPairRiskParamsArgs) -> Result<()> {
+        SetPairRiskParams::handle_set_pair_risk_params(ctx, args)
+    }
+
+    /// Sets the caps on a pair's total debt, utilization and total collateral.
+    #[access_control(SetPairCaps::validate(&args))]
+    pub fn set_pair_caps(ctx: Context<SetPairCaps>, args: SetPairCapsArgs) -> Result<()> {
+        SetPairCaps::handle_set_pair_caps(ctx, args)
+    }
+
     // Pair instructions
     #[access_control(ctx.accounts.validate(&args))]
     pub fn initialize(ctx: Context<InitializeAndBootstrap>, args: InitializeAndBootstrapArgs) -> Result<()> {
@@ -135,6 +195,22 @@ pub mod omnipair {
         Swap::handle_swap(ctx, args)
     }
 
+    #[access_control(ctx.accounts.update_and_validate_swap_exact_out(&args))]
+    pub fn swap_exact_out(
+        ctx: Context<Swap>,
+        args: SwapExactOutArgs,
+    ) -> Result<()> {
+        Swap::handle_swap_exact_out(ctx, args)
+    }
+
+    #[access_control(ctx.accounts.validate(&args))]
+    pub fn swap_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        args: SwapRouteArgs,
+    ) -> Result<()> {
+        SwapRoute::handle_swap_route(ctx, args)
+    }
+
     // Lending instructions
     #[access_control(ctx.accounts.update_and_validate_add(&args))]
     pub fn add_collateral(ctx: Context<AddCollateral>, args: AdjustCollateralArgs) -> Result<()> {
@@ -161,6 +237,59 @@ pub mod omnipair {
         Liquidate::handle_liquidate(ctx)
     }
 
+    /// Liquidation in which the caller repays the debt instead of it being written off against the reserves.
+    #[access_control(ctx.accounts.update_and_validate_liquidate_with_repay(&args))]
+    pub fn liquidate_with_repay(ctx: Context<LiquidateWithRepay>, args: LiquidateWithRepayArgs) -> Result<()> {
+        LiquidateWithRepay::handle_liquidate_with_repay(ctx, args)
+    }
+
+    #[access_control(ctx.accounts.update_and_validate_open_leveraged(&args))]
+    pub fn open_leveraged(ctx: Context<OpenLeveraged>, args: OpenLeveragedArgs) -> Result<()> {
+        OpenLeveraged::handle_open_leveraged(ctx, args)
+    }
+
+    #[access_control(ctx.accounts.update_and_validate_close(&args))]
+    pub fn close_position(ctx: Context<ClosePosition>, args: ClosePositionArgs) -> Result<()> {
+        ClosePosition::handle_close(ctx, args)
+    }
+
+    /// Switches a position between isolated and cross margin.
+    #[access_control(ctx.accounts.update())]
+    pub fn set_margin_mode(ctx: Context<SetMarginMode>, args: SetMarginModeArgs) -> Result<()> {
+        SetMarginMode::handle_set_margin_mode(ctx, args)
+    }
+
+    /// Sets, replaces or revokes the delegate of a position.
+    pub fn set_position_delegate(ctx: Context<SetPositionDelegate>, args: SetPositionDelegateArgs) -> Result<()> {
+        SetPositionDelegate::handle_set_position_delegate(ctx, args)
+    }
+
+    /// Moves the collateral and debt of a position into an empty or new position of another owner.
+    pub fn transfer_position(ctx: Context<TransferPosition>, args: TransferPositionArgs) -> Result<()> {
+        TransferPosition::handle_transfer_position(ctx, args)
+    }
+
+    /// Closes an empty position account and returns its rent to the owner.
+    pub fn close_position_account(ctx: Context<ClosePositionAccount>) -> Result<()> {
+        ClosePositionAccount::handle_close_position_account(ctx)
+    }
+
+    /// Places a stop-loss or take-profit order that closes one side of a position once its price trigger is met.
+    pub fn place_position_order(ctx: Context<PlacePositionOrder>, args: PlacePositionOrderArgs) -> Result<()> {
+        PlacePositionOrder::handle_place_position_order(ctx, args)
+    }
+
+    /// Cancels a position order and returns its rent to the owner.
+    pub fn cancel_position_order(ctx: Context<CancelPositionOrder>) -> Result<()> {
+        CancelPositionOrder::handle_cancel_position_order(ctx)
+    }
+
+    /// Executes a triggered position order for its keeper bounty; callable by anyone.
+    #[access_control(ctx.accounts.update_and_validate_execute())]
+    pub fn execute_position_order(ctx: Context<ExecutePositionOrder>) -> Result<()> {
+        ExecutePositionOrder::handle_execute_position_order(ctx)
+    }
+
     // Flash loan instruction
     #[access_control(ctx.accounts.update_and_validate(&args))]
     pub fn flashloan<'info>(ctx: Context<'_, '_, '_, 'info, Flashloan<'info>>, args: FlashloanArgs) -> Result<()> {
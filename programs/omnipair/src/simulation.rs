@@ -0,0 +1,465 @@
+//! Off-chain simulation of pair state transitions.
+//!
+//! Runs the state transitions of the instruction handlers on plain `Pair` / `UserPosition` snapshots,
+//! with the slot passed in instead of read from the clock sysvar. There are no accounts, sysvars
+//! or CPIs involved, so quotes can be computed anywhere the state types build, and match the
+//! on-chain result of a transaction landing at the simulated slot.
+//!
+//! Only checks on pair and position state are simulated; checks on accounts
+//! (token balances, signers, the liquidity delta circuit breaker) are left to the caller.
+//!
+//! Off-chain only, behind the `simulation` feature.
+
+use anchor_lang::prelude::{error, require, require_gte, Pubkey, Result};
+
+use crate::{
+    errors::ErrorCode,
+    instructions::{
+        lending::{
+            borrow::apply_borrow,
+            close_position::{apply_close_position, validate_close_position},
+            execute_position_order::{apply_position_order, validate_position_order},
+            liquidate::{apply_liquidation, validate_liquidation},
+            liquidate_with_repay::validate_repay_liquidation,
+            repay::resolve_repay_amount,
+        },
+        AddLiquidityAmounts, AddLiquidityArgs, AdjustDebtArgs, ClosePositionAmounts, ClosePositionArgs, LiquidateWithRepayArgs, LiquidationAmounts,
+        RemoveLiquidityAmounts, RepayLiquidationAmounts, RemoveLiquidityArgs, SwapAmounts, SwapArgs, SwapExactOutArgs,
+    },
+    state::{
+        futarchy_authority::FutarchyAuthority,
+        pair::{InterestAccrual, Pair},
+        position_order::PositionOrder,
+        rate_model::RateModel,
+        user_position::{DebtDecreaseReason, UserPosition},
+    },
+};
+
+/// Post-state of the pair and the outputs of a simulated instruction.
+#[derive(Clone)]
+pub struct Simulated<T> {
+    pub pair: Pair,
+    pub output: T,
+}
+
+/// Post-state of the pair and position and the outputs of a simulated lending instruction.
+#[derive(Clone)]
+pub struct SimulatedPosition<T> {
+    pub pair: Pair,
+    pub user_position: UserPosition,
+    pub output: T,
+}
+
+/// Simulates instructions against the pair's rate models and the protocol fee configuration at `slot`.
+///
+/// Every instruction first brings the pair up to `slot` (interest, rates and EMAs), as the handlers do.
+pub struct Simulator<'a> {
+    /// `pair.rate_model0` / `pair.rate_model1`
+    pub rate_model0: &'a RateModel,
+    pub rate_model1: &'a RateModel,
+    pub futarchy_authority: &'a FutarchyAuthority,
+    pub slot: u64,
+}
+
+impl<'a> Simulator<'a> {
+    pub fn new(
+        rate_model0: &'a RateModel,
+        rate_model1: &'a RateModel,
+        futarchy_authority: &'a FutarchyAuthority,
+        slot: u64,
+    ) -> Self {
+        Self { rate_model0, rate_model1, futarchy_authority, slot }
+    }
+
+    /// `Pair::update` at the simulated slot. The output is the interest accrued, if any time elapsed.
+    pub fn update(&self, pair: &Pair) -> Result<Simulated<Option<InterestAccrual>>> {
+        let mut pair = pair.clone();
+        pair.sync_risk_params(&self.futarchy_authority.risk_params);
+        let accrual = pair.update_at(
+            self.rate_model0,
+            self.rate_model1,
+            self.futarchy_authority.revenue_share.interest_bps,
+            self.slot,
+        )?;
+        Ok(Simulated { pair, output: accrual })
+    }
+
+    fn updated(&self, pair: &Pair) -> Result<Pair> {
+        Ok(self.update(pair)?.pair)
+    }
+
+    pub fn swap(&self, pair: &Pair, is_token0_in: bool, args: &SwapArgs) -> Result<Simulated<SwapAmounts>> {
+        let mut pair = self.updated(pair)?;
+        require!(args.amount_in > 0, ErrorCode::AmountZero);
+
+        let amounts = SwapAmounts::exact_in(
+            &pair,
+            self.futarchy_authority.revenue_share.swap_bps,
+            is_token0_in,
+            args.amount_in,
+        )?;
+        require_gte!(amounts.amount_out, args.min_amount_out, ErrorCode::SlippageExceeded);
+
+        amounts.apply(&mut pair, is_token0_in)?;
+        Ok(Simulated { pair, output: amounts })
+    }
+
+    pub fn swap_exact_out(&self, pair: &Pair, is_token0_in: bool, args: &SwapExactOutArgs) -> Result<Simulated<SwapAmounts>> {
+        let mut pair = self.updated(pair)?;
+        require!(args.amount_out > 0, ErrorCode::AmountZero);
+        require!(args.max_amount_in > 0, ErrorCode::AmountZero);
+
+        let amounts = SwapAmounts::exact_out(
+            &pair,
+            self.futarchy_authority.revenue_share.swap_bps,
+            is_token0_in,
+            args.amount_out,
+        )?;
+        require_gte!(args.max_amount_in, amounts.amount_in, ErrorCode::SlippageExceeded);
+
+        amounts.apply(&mut pair, is_token0_in)?;
+        Ok(Simulated { pair, output: amounts })
+    }
+
+    pub fn add_liquidity(&self, pair: &Pair, args: &AddLiquidityArgs) -> Result<Simulated<AddLiquidityAmounts>> {
+        let mut pair = self.updated(pair)?;
+        require!(
+            !self.futarchy_authority.is_reduce_only(pair.reduce_only),
+            ErrorCode::ReduceOnlyMode
+        );
+        require!(args.amount0_in > 0 && args.amount1_in > 0, ErrorCode::AmountZero);
+
+        let amounts = AddLiquidityAmounts::new(&pair, args)?;
+        require!(
+            amounts.liquidity >= args.min_liquidity_out,
+            ErrorCode::SlippageExceeded
+        );
+
+        amounts.apply(&mut pair)?;
+        Ok(Simulated { pair, output: amounts })
+    }
+
+    pub fn remove_liquidity(&self, pair: &Pair, args: &RemoveLiquidityArgs) -> Result<Simulated<RemoveLiquidityAmounts>> {
+        let mut pair = self.updated(pair)?;
+        require!(args.liquidity_in > 0, ErrorCode::AmountZero);
+        require!(
+            args.liquidity_in <= pair.total_supply,
+            ErrorCode::InsufficientLiquidity
+        );
+
+        let amounts = RemoveLiquidityAmounts::new(&pair, args.liquidity_in)?;
+        require!(
+            amounts.amount0_out >= args.min_amount0_out,
+            ErrorCode::SlippageExceeded
+        );
+        require!(
+            amounts.amount1_out >= args.min_amount1_out,
+            ErrorCode::SlippageExceeded
+        );
+
+        amounts.apply(&mut pair, args.liquidity_in)?;
+        Ok(Simulated { pair, output: amounts })
+    }
+
+    /// The output is the amount borrowed, which resolves `u64::MAX` to the remaining borrow limit.
+    pub fn borrow(
+        &self,
+        pair: &Pair,
+        user_position: &UserPosition,
+        debt_token: &Pubkey,
+        args: &AdjustDebtArgs,
+    ) -> Result<SimulatedPosition<u64>> {
+        let mut pair = self.updated(pair)?;
+        let mut user_position = user_position.clone();
+        require!(
+            !self.futarchy_authority.is_reduce_only(pair.reduce_only),
+            ErrorCode::ReduceOnlyMode
+        );
+        require!(args.amount > 0, ErrorCode::AmountZero);
+
+        let borrow_amount = apply_borrow(&mut pair, &mut user_position, debt_token, args.amount)?;
+        Ok(SimulatedPosition { pair, user_position, output: borrow_amount })
+    }
+
+    /// The output is the amount repaid, which resolves `u64::MAX` to the whole debt.
+    pub fn repay(
+        &self,
+        pair: &Pair,
+        user_position: &UserPosition,
+        debt_token: &Pubkey,
+        args: &AdjustDebtArgs,
+    ) -> Result<SimulatedPosition<u64>> {
+        let mut pair = self.updated(pair)?;
+        let mut user_position = user_position.clone();
+
+        let debt_to_repay = resolve_repay_amount(&pair, &user_position, debt_token, args.amount)?;
+        user_position.decrease_debt(&mut pair, debt_token, debt_to_repay, DebtDecreaseReason::Repayment)?;
+        Ok(SimulatedPosition { pair, user_position, output: debt_to_repay })
+    }
+
+    /// Liquidates the debt backed by `collateral_token`. Fails with `NotUndercollateralized` if the position is healthy.
+    ///
+    /// For a cross-margin position, first nets its collateral of `collateral_token` against its debt of
+    /// the same token (`LiquidationAmounts::collateral_netted`); see `validate_liquidation`.
+    pub fn liquidate(
+        &self,
+        pair: &Pair,
+        user_position: &UserPosition,
+        collateral_token: &Pubkey,
+    ) -> Result<SimulatedPosition<LiquidationAmounts>> {
+        let mut pair = self.updated(pair)?;
+        let mut user_position = user_position.clone();
+        let is_collateral_token0 = *collateral_token == pair.token0;
+        validate_liquidation(&pair, &user_position, is_collateral_token0)?;
+
+        let amounts = apply_liquidation(&mut pair, &mut user_position, is_collateral_token0)?;
+        Ok(SimulatedPosition { pair, user_position, output: amounts })
+    }
+
+    /// Liquidates the debt backed by `collateral_token` with the caller repaying `args.repay_amount` of it.
+    pub fn liquidate_with_repay(
+        &self,
+        pair: &Pair,
+        user_position: &UserPosition,
+        collateral_token: &Pubkey,
+        args: &LiquidateWithRepayArgs,
+    ) -> Result<SimulatedPosition<RepayLiquidationAmounts>> {
+        let mut pair = self.updated(pair)?;
+        let mut user_position = user_position.clone();
+        let is_collateral_token0 = *collateral_token == pair.token0;
+        require!(args.repay_amount > 0, ErrorCode::AmountZero);
+        validate_repay_liquidation(&pair, &user_position, is_collateral_token0)?;
+
+        let amounts = RepayLiquidationAmounts::new(&pair, &user_position, is_collateral_token0, args.repay_amount)?;
+        require_gte!(amounts.caller_collateral, args.min_collateral_out, ErrorCode::SlippageExceeded);
+        amounts.apply(&mut pair, &mut user_position, is_collateral_token0)?;
+        Ok(SimulatedPosition { pair, user_position, output: amounts })
+    }
+
+    /// Closes the `collateral_token` side of the position, repaying its debt out of its collateral.
+    pub fn close_position(
+        &self,
+        pair: &Pair,
+        user_position: &UserPosition,
+        collateral_token: &Pubkey,
+        args: &ClosePositionArgs,
+    ) -> Result<SimulatedPosition<ClosePositionAmounts>> {
+        let mut pair = self.updated(pair)?;
+        let mut user_position = user_position.clone();
+        let is_collateral_token0 = *collateral_token == pair.token0;
+        validate_close_position(&pair, &user_position, is_collateral_token0)?;
+
+        let amounts = apply_close_position(
+            &mut pair,
+            &mut user_position,
+            self.futarchy_authority.revenue_share.swap_bps,
+            is_collateral_token0,
+            args.min_collateral_out,
+        )?;
+        Ok(SimulatedPosition { pair, user_position, output: amounts })
+    }
+
+    /// Executes `position_order` on its position. Fails with `PositionOrderNotTriggered` if the trigger
+    /// is not met at the simulated slot. `collateral_out` of the output includes the keeper bounty.
+    pub fn execute_position_order(
+        &self,
+        pair: &Pair,
+        user_position: &UserPosition,
+        position_order: &PositionOrder,
+    ) -> Result<SimulatedPosition<ClosePositionAmounts>> {
+        let mut pair = self.updated(pair)?;
+        let mut user_position = user_position.clone();
+        validate_position_order(&pair, &user_position, position_order)?;
+
+        let amounts = apply_position_order(
+            &mut pair,
+            &mut user_position,
+            position_order,
+            self.futarchy_authority.revenue_share.swap_bps,
+        )?;
+        Ok(SimulatedPosition { pair, user_position, output: amounts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::*,
+        state::{DelegatePermission, RiskParams, VaultBumps},
+    };
+
+    fn test_rate_model() -> RateModel {
+        RateModel::new(
+            TARGET_UTIL_START_BPS,
+            TARGET_UTIL_END_BPS,
+            DEFAULT_RATE_HALF_LIFE_MS,
+            DEFAULT_MIN_RATE_BPS,
+            0,
+            DEFAULT_INITIAL_RATE_BPS,
+        )
+    }
+
+    fn test_futarchy_authority() -> FutarchyAuthority {
+        FutarchyAuthority::initialize(
+            Pubkey::new_unique(),
+            2_000,
+            1_000,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            10_000,
+            0,
+            0,
+            1,
+        )
+        .unwrap()
+    }
+
+    fn test_pair(rate_model: &RateModel, reserve: u64) -> Pair {
+        let mut pair = Pair::initialize(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            6,
+            6,
+            Pubkey::new_unique(),
+            30,
+            60_000,
+            Some(8_000),
+            100,
+            [0; 32],
+            VERSION,
+            1,
+            VaultBumps::default(),
+            rate_model.initial_rate,
+            RiskParams::default(),
+        );
+        pair.reserve0 = reserve;
+        pair.reserve1 = reserve;
+        pair.cash_reserve0 = reserve;
+        pair.cash_reserve1 = reserve;
+        pair.last_price0_ema.symmetric = pair.spot_price0_nad();
+        pair.last_price0_ema.directional = pair.spot_price0_nad();
+        pair.last_price1_ema.symmetric = pair.spot_price1_nad();
+        pair.last_price1_ema.directional = pair.spot_price1_nad();
+        pair
+    }
+
+    fn test_position(collateral0: u64) -> UserPosition {
+        UserPosition {
+            owner: Pubkey::new_unique(),
+            pair: Pubkey::new_unique(),
+            collateral0_liquidation_cf_bps: 0,
+            collateral1_liquidation_cf_bps: 0,
+            collateral0,
+            collateral1: 0,
+            debt0_shares: 0,
+            debt1_shares: 0,
+            bump: 1,
+            cross_margin: false,
+            position_index: 0,
+            delegate: Pubkey::default(),
+            delegate_permission: DelegatePermission::None,
+            delegate_expiry_slot: 0,
+            open_orders: 0,
+        }
+    }
+
+    #[test]
+    fn swap_matches_swap_amounts_and_leaves_snapshot_untouched() {
+        let rate_model = test_rate_model();
+        let futarchy_authority = test_futarchy_authority();
+        let pair = test_pair(&rate_model, 1_000_000_000);
+        let simulator = Simulator::new(&rate_model, &rate_model, &futarchy_authority, pair.last_update);
+
+        let args = SwapArgs { amount_in: 1_000_000, min_amount_out: 0 };
+        let simulated = simulator.swap(&pair, true, &args).unwrap();
+
+        let mut expected = pair.clone();
+        let amounts = SwapAmounts::exact_in(&expected, 2_000, true, args.amount_in).unwrap();
+        amounts.apply(&mut expected, true).unwrap();
+        assert_eq!(simulated.output.amount_out, amounts.amount_out);
+        assert_eq!(simulated.pair.reserve0, expected.reserve0);
+        assert_eq!(simulated.pair.reserve1, expected.reserve1);
+        assert_eq!(pair.reserve0, 1_000_000_000);
+    }
+
+    #[test]
+    fn stale_snapshot_ema_at_slot_matches_updated_pair() {
+        let rate_model = test_rate_model();
+        let futarchy_authority = test_futarchy_authority();
+        let pair = test_pair(&rate_model, 1_000_000_000);
+        let swapped = Simulator::new(&rate_model, &rate_model, &futarchy_authority, pair.last_update)
+            .swap(&pair, true, &SwapArgs { amount_in: 100_000_000, min_amount_out: 0 })
+            .unwrap()
+            .pair;
+
+        let slot = swapped.last_update + 1_000;
+        let updated = Simulator::new(&rate_model, &rate_model, &futarchy_authority, slot).update(&swapped).unwrap().pair;
+        assert_eq!(swapped.ema_price0_nad_at(slot), updated.ema_price0_nad());
+        assert_eq!(swapped.ema_price1_nad_at(slot), updated.ema_price1_nad());
+        // Priced at its own `last_update` the stale snapshot hasn't moved towards the spot price yet
+        assert_eq!(swapped.ema_price0_nad(), pair.ema_price0_nad());
+        assert!(updated.ema_price0_nad() < swapped.ema_price0_nad());
+    }
+
+    #[test]
+    fn repay_all_after_accrual_clears_debt() {
+        let rate_model = test_rate_model();
+        let futarchy_authority = test_futarchy_authority();
+        let mut pair = test_pair(&rate_model, 1_000_000_000);
+        let user_position = test_position(100_000_000);
+        pair.total_collateral0 = user_position.collateral0;
+        let token1 = pair.token1;
+
+        let borrowed = Simulator::new(&rate_model, &rate_model, &futarchy_authority, pair.last_update)
+            .borrow(&pair, &user_position, &token1, &AdjustDebtArgs { amount: 10_000_000 })
+            .unwrap();
+        assert_eq!(borrowed.output, 10_000_000);
+
+        let simulator = Simulator::new(&rate_model, &rate_model, &futarchy_authority, pair.last_update + 1_000_000);
+        let accrued = simulator.update(&borrowed.pair).unwrap();
+        assert!(accrued.output.is_some());
+        assert!(accrued.pair.total_debt1 > borrowed.pair.total_debt1);
+
+        let repaid = simulator
+            .repay(&borrowed.pair, &borrowed.user_position, &token1, &AdjustDebtArgs { amount: u64::MAX })
+            .unwrap();
+        assert_eq!(repaid.output, accrued.pair.total_debt1);
+        assert_eq!(repaid.user_position.debt1_shares, 0);
+        assert_eq!(repaid.pair.total_debt1, 0);
+    }
+
+    #[test]
+    fn debt_accrues_under_its_own_side_rate_model() {
+        let rate_model = test_rate_model();
+        let expensive = RateModel::new(
+            TARGET_UTIL_START_BPS,
+            TARGET_UTIL_END_BPS,
+            DEFAULT_RATE_HALF_LIFE_MS,
+            10 * DEFAULT_MIN_RATE_BPS,
+            0,
+            10 * DEFAULT_MIN_RATE_BPS,
+        );
+        let futarchy_authority = test_futarchy_authority();
+        let mut pair = test_pair(&rate_model, 1_000_000_000);
+        let user_position = test_position(100_000_000);
+        pair.total_collateral0 = user_position.collateral0;
+        let token1 = pair.token1;
+        let borrowed = Simulator::new(&rate_model, &rate_model, &futarchy_authority, pair.last_update)
+            .borrow(&pair, &user_position, &token1, &AdjustDebtArgs { amount: 10_000_000 })
+            .unwrap();
+
+        let debt1_at = |rate_model0: &RateModel, rate_model1: &RateModel| {
+            Simulator::new(rate_model0, rate_model1, &futarchy_authority, pair.last_update + 1_000_000)
+                .update(&borrowed.pair)
+                .unwrap()
+                .pair
+                .total_debt1
+        };
+        let baseline = debt1_at(&rate_model, &rate_model);
+        assert_eq!(debt1_at(&expensive, &rate_model), baseline);
+        assert!(debt1_at(&rate_model, &expensive) > baseline);
+    }
+}
@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 #[allow(unused_imports)]
 use crate::constants::*;
 use crate::errors::ErrorCode;
+use crate::state::risk_params::RiskParams;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
 pub struct RevenueShare {
@@ -51,10 +52,14 @@ pub struct FutarchyAuthority {
     pub global_reduce_only: bool,
 
     pub bump: u8,
+
+    /// Default risk parameters of pairs without an override. Last field so that the account
+    /// is upgraded in place by `migrate_futarchy_authority`
+    pub risk_params: RiskParams,
 }
 
 impl FutarchyAuthority {
-    pub const CURRENT_VERSION: u8 = 1;
+    pub const CURRENT_VERSION: u8 = 2;
 
     pub fn validate(&self) -> Result<()> {
         if !self.revenue_distribution.is_valid() {
@@ -105,6 +110,7 @@ impl FutarchyAuthority {
             revenue_distribution,
             global_reduce_only: false,
             bump,
+            risk_params: RiskParams::default(),
         })
     }
 }
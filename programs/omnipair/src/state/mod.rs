@@ -2,8 +2,14 @@ pub mod pair;
 pub mod rate_model;
 pub mod user_position;
 pub mod futarchy_authority;
+pub mod risk_params;
+pub mod pair_caps;
+pub mod position_order;
 
 pub use pair::*;
 pub use rate_model::*;
 pub use user_position::*;
-pub use futarchy_authority::*;
\ No newline at end of file
+pub use futarchy_authority::*;
+pub use risk_params::*;
+pub use pair_caps::*;
+pub use position_order::*;
\ No newline at end of file
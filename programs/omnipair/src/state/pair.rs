@@ -1,9 +1,9 @@
 use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::errors::ErrorCode;
-use crate::utils::gamm_math::pessimistic_max_debt;
-use crate::utils::math::{compute_ema, slots_to_ms, ceil_div};
-use crate::state::RateModel;
+use crate::utils::gamm_math::{pessimistic_max_debt, CfParams};
+use crate::utils::math::{compute_ema_at, slots_to_ms, ceil_div};
+use crate::state::{PairCaps, RateModel, RiskParams};
 use crate::events::{UpdatePairEvent, EventMetadata};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
@@ -23,6 +23,23 @@ pub struct LastPriceEMA {
     pub directional: u64,
 }
 
+/// Interest applied by one [`Pair::update_at`] step.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InterestAccrual {
+    /// Total interest applied to borrowers = lp_interest + insurance_interest + protocol_interest
+    pub accrued_interest0: u128,
+    pub accrued_interest1: u128,
+    /// Interest to LPs, added to reserves
+    pub lp_interest0: u64,
+    pub lp_interest1: u64,
+    /// Share of the LP interest moved from the cash reserves to the insurance reserves
+    pub insurance_interest0: u64,
+    pub insurance_interest1: u64,
+    /// Interest to the protocol, charged on top of the LP interest
+    pub protocol_interest0: u64,
+    pub protocol_interest1: u64,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Pair {
@@ -32,7 +49,8 @@ pub struct Pair {
     pub lp_mint: Pubkey,
 
     // pair parameters
-    pub rate_model: Pubkey,
+    /// Rate model of token0 borrowing; token1's is `rate_model1`
+    pub rate_model0: Pubkey,
     pub swap_fee_bps: u16,
     pub half_life: u64,
     // Fixed collateral factor (BPS). If Some, use this instead of dynamic CF
@@ -77,6 +95,35 @@ pub struct Pair {
 
     /// Per-pair reduce-only mode - when enabled, blocks borrowing and adding liquidity for this pair
     pub reduce_only: bool,
+
+    /// Rate model of token1 borrowing. Last field so that pairs created before it
+    /// are upgraded in place by `migrate_pair`
+    pub rate_model1: Pubkey,
+
+    /// Insurance funds held in the reserve vaults, outside of the cash reserves and protocol revenue.
+    /// Cover the shortfall of insolvent liquidations before it is socialized to LPs
+    pub insurance_reserve0: u64,
+    pub insurance_reserve1: u64,
+    /// Share (BPS) of the LP part of liquidation penalties paid into the insurance reserves
+    pub insurance_penalty_share_bps: u16,
+    /// Share (BPS) of LP interest paid into the insurance reserves
+    pub insurance_interest_share_bps: u16,
+
+    // Bad debt tracking, cumulative
+    /// Shortfall of insolvent liquidations
+    pub total_bad_debt0: u64,
+    pub total_bad_debt1: u64,
+    /// Part of the shortfall not covered by the insurance reserves, lost by LPs
+    pub total_socialized_debt0: u64,
+    pub total_socialized_debt1: u64,
+
+    /// Effective risk parameters: the override set by `set_pair_risk_params`,
+    /// or a copy of `FutarchyAuthority::risk_params` refreshed by `update`
+    pub risk_params: RiskParams,
+    pub risk_params_overridden: bool,
+
+    /// Caps on total debt, utilization and total collateral, set by `set_pair_caps`
+    pub caps: PairCaps,
 }
 
 impl Pair {
@@ -86,7 +133,7 @@ impl Pair {
         lp_mint: Pubkey,
         token0_decimals: u8,
         token1_decimals: u8,
-        rate_model: Pubkey,
+        rate_model: Pubkey, // Initial rate model of both sides
         swap_fee_bps: u16,
         half_life: u64,
         fixed_cf_bps: Option<u16>,
@@ -96,6 +143,7 @@ impl Pair {
         bump: u8,
         vault_bumps: VaultBumps,
         initial_rate: u64, // NAD-scaled initial rate from rate model
+        risk_params: RiskParams, // Global defaults from the futarchy authority
     ) -> Self {
         Self {
             token0,
@@ -105,7 +153,8 @@ impl Pair {
             token1_decimals,
 
             // pair parameters
-            rate_model,
+            rate_model0: rate_model,
+            rate_model1: rate_model,
             swap_fee_bps,
             half_life,
             fixed_cf_bps,
@@ -140,7 +189,57 @@ impl Pair {
             // don't use default values for vault bumps
             vault_bumps,
             reduce_only: false,
+            insurance_reserve0: 0,
+            insurance_reserve1: 0,
+            insurance_penalty_share_bps: DEFAULT_INSURANCE_PENALTY_SHARE_BPS,
+            insurance_interest_share_bps: DEFAULT_INSURANCE_INTEREST_SHARE_BPS,
+            total_bad_debt0: 0,
+            total_bad_debt1: 0,
+            total_socialized_debt0: 0,
+            total_socialized_debt1: 0,
+            risk_params,
+            risk_params_overridden: false,
+            caps: PairCaps::default(),
+        }
+    }
+
+    /// Follows the global risk parameters unless the pair overrides them.
+    pub fn sync_risk_params(&mut self, global_risk_params: &RiskParams) {
+        if !self.risk_params_overridden {
+            self.risk_params = *global_risk_params;
+        }
+    }
+
+    /// Checks the debt and utilization caps of the `debt_token` side, after a borrow.
+    pub fn check_borrow_caps(&self, debt_token: &Pubkey) -> Result<()> {
+        let is_token0 = *debt_token == self.token0;
+        let (util0, util1) = self.utilizations_nad();
+        let (total_debt, utilization) = match is_token0 {
+            true => (self.total_debt0, util0),
+            false => (self.total_debt1, util1),
+        };
+
+        if let Some(max_debt) = self.caps.max_debt(is_token0) {
+            require_gte!(max_debt, total_debt, ErrorCode::BorrowCapExceeded);
+        }
+        if let Some(max_utilization) = self.caps.max_utilization_nad() {
+            require_gte!(max_utilization, utilization, ErrorCode::UtilizationCapExceeded);
+        }
+        Ok(())
+    }
+
+    /// Checks the collateral cap of the `collateral_token` side, after collateral is added.
+    pub fn check_collateral_cap(&self, collateral_token: &Pubkey) -> Result<()> {
+        let is_token0 = *collateral_token == self.token0;
+        let total_collateral = match is_token0 {
+            true => self.total_collateral0,
+            false => self.total_collateral1,
+        };
+
+        if let Some(max_collateral) = self.caps.max_collateral(is_token0) {
+            require_gte!(max_collateral, total_collateral, ErrorCode::CollateralCapExceeded);
         }
+        Ok(())
     }
 
     pub fn k(&self) -> u128 {
@@ -182,67 +281,101 @@ impl Pair {
         }
     }
 
-    /// EMA prices scaled by 1e9
+    /// EMA prices scaled by 1e9, as of `last_update`.
+    ///
+    /// Handlers bring the pair up to the current slot with `update` before pricing, so this is the
+    /// EMA at the current slot on-chain. Use the `_at` variants to price a stale snapshot.
     pub fn ema_price0_nad(&self) -> u64 {
+        self.ema_price0_nad_at(self.last_update)
+    }
+
+    pub fn ema_price1_nad(&self) -> u64 {
+        self.ema_price1_nad_at(self.last_update)
+    }
+
+    pub fn directional_ema_price0_nad(&self) -> u64 {
+        self.directional_ema_price0_nad_at(self.last_update)
+    }
+
+    pub fn directional_ema_price1_nad(&self) -> u64 {
+        self.directional_ema_price1_nad_at(self.last_update)
+    }
+
+    /// EMA prices scaled by 1e9, as of `current_slot` (not before `last_update`).
+    pub fn ema_price0_nad_at(&self, current_slot: u64) -> u64 {
         if self.reserve0 == 0 {
             0
         } else {
             let spot_price = self.spot_price0_nad();
-            compute_ema(
+            compute_ema_at(
                 self.last_price0_ema.symmetric, 
                 self.last_update, 
                 spot_price, 
-                self.half_life
+                self.half_life,
+                current_slot,
             )
         }
     }
 
-    pub fn ema_price1_nad(&self) -> u64 {
+    pub fn ema_price1_nad_at(&self, current_slot: u64) -> u64 {
         if self.reserve1 == 0 {
             0
         } else {
             let spot_price = self.spot_price1_nad();
-            compute_ema(
+            compute_ema_at(
                 self.last_price1_ema.symmetric, 
                 self.last_update, 
                 spot_price, 
-                self.half_life
+                self.half_life,
+                current_slot,
             )
         }
     }
 
-    pub fn directional_ema_price0_nad(&self) -> u64 {
+    pub fn directional_ema_price0_nad_at(&self, current_slot: u64) -> u64 {
         if self.reserve0 == 0 {
             0
         } else {
             let spot_price = self.spot_price0_nad();
-            compute_ema(
+            compute_ema_at(
                 self.last_price0_ema.directional, 
                 self.last_update, 
                 spot_price, 
-                DIRECTIONAL_EMA_HALF_LIFE_MS
+                DIRECTIONAL_EMA_HALF_LIFE_MS,
+                current_slot,
             )
         }
     }
 
-    pub fn directional_ema_price1_nad(&self) -> u64 {
+    pub fn directional_ema_price1_nad_at(&self, current_slot: u64) -> u64 {
         if self.reserve1 == 0 {
             0
         } else {
             let spot_price = self.spot_price1_nad();
-            compute_ema(
+            compute_ema_at(
                 self.last_price1_ema.directional, 
                 self.last_update, 
                 spot_price, 
-                DIRECTIONAL_EMA_HALF_LIFE_MS
+                DIRECTIONAL_EMA_HALF_LIFE_MS,
+                current_slot,
             )
         }
     }
 
-    pub fn get_rates(&self, rate_model: &Account<RateModel>) -> Result<(u64, u64)> {
+    pub fn get_rates(&self, rate_model0: &RateModel, rate_model1: &RateModel) -> Result<(u64, u64)> {
         let current_slot = Clock::get()?.slot;
         let time_elapsed = slots_to_ms(self.last_update, current_slot).unwrap_or(0);
 
+        let (util0, util1) = self.utilizations_nad();
+
+        Ok((
+            rate_model0.calculate_rate(self.last_rate0, time_elapsed, util0).0, 
+            rate_model1.calculate_rate(self.last_rate1, time_elapsed, util1).0
+        ))
+    }
+
+    /// Utilization (total debt / virtual reserve) of each side, scaled by 1e9
+    pub fn utilizations_nad(&self) -> (u64, u64) {
         let util0 = match self.reserve0 {
             0 => 0,
             _ => {
@@ -257,11 +390,7 @@ impl Pair {
                 u64::try_from(util).unwrap_or(u64::MAX)
             }
         };
-
-        Ok((
-            rate_model.calculate_rate(self.last_rate0, time_elapsed, util0).0, 
-            rate_model.calculate_rate(self.last_rate1, time_elapsed, util1).0
-        ))
+        (util0, util1)
     }
 
     pub fn is_initialized(&self) -> bool {
@@ -277,7 +406,7 @@ impl Pair {
     /// Returns a tuple containing:
     /// - The maximum debt possible for the given collateral amount
     /// - The maximum collateral factor in BPS
-    /// - The liquidation collateral factor in BPS (max_allowed_cf_bps - LTV_BUFFER_BPS)
+    /// - The liquidation collateral factor in BPS (max_allowed_cf_bps - risk_params.ltv_buffer_bps)
     /// 
     /// If `fixed_cf_bps` is `Some`, uses the fixed collateral factor instead of dynamic calculation.
     /// 
@@ -305,10 +434,49 @@ impl Pair {
             collateral_amm_reserve,
             debt_amm_reserve,
             debt_total,
-            pair.fixed_cf_bps,
+            CfParams::new(pair.fixed_cf_bps, &pair.risk_params),
         )
     }
 
+    /// Adds `amount` of the token, already transferred to its reserve vault, to the insurance reserve.
+    pub fn add_to_insurance(&mut self, is_token0: bool, amount: u64) {
+        match is_token0 {
+            true => self.insurance_reserve0 = self.insurance_reserve0.saturating_add(amount),
+            false => self.insurance_reserve1 = self.insurance_reserve1.saturating_add(amount),
+        }
+    }
+
+    /// Records `bad_debt` of `debt_token` written off by an insolvent liquidation, covering what it can
+    /// from the insurance reserve. The covered part moves back to the cash and virtual reserves.
+    ///
+    /// Returns the part covered by the insurance reserve; the rest is socialized to LPs.
+    pub fn cover_bad_debt(&mut self, debt_token: &Pubkey, bad_debt: u64) -> u64 {
+        let (insurance_reserve, reserve, cash_reserve, total_bad_debt, total_socialized_debt) = match *debt_token == self.token0 {
+            true => (
+                &mut self.insurance_reserve0,
+                &mut self.reserve0,
+                &mut self.cash_reserve0,
+                &mut self.total_bad_debt0,
+                &mut self.total_socialized_debt0,
+            ),
+            false => (
+                &mut self.insurance_reserve1,
+                &mut self.reserve1,
+                &mut self.cash_reserve1,
+                &mut self.total_bad_debt1,
+                &mut self.total_socialized_debt1,
+            ),
+        };
+        let covered = bad_debt.min(*insurance_reserve);
+        *insurance_reserve -= covered;
+        // ΔR_virtual = ΔR_cash: the tokens are already in the reserve vault
+        *reserve = reserve.saturating_add(covered);
+        *cash_reserve = cash_reserve.saturating_add(covered);
+        *total_bad_debt = total_bad_debt.saturating_add(bad_debt);
+        *total_socialized_debt = total_socialized_debt.saturating_add(bad_debt - covered);
+        covered
+    }
+
     pub fn get_reserve_vault_bump(&self, reserve_token_mint: &Pubkey) -> u8 {
         match reserve_token_mint == &self.token0 {
             true => self.vault_bumps.reserve0,
@@ -325,12 +493,73 @@ impl Pair {
 
     pub fn update<'info>(
         &mut self,
-        rate_model: &Account<RateModel>,
+        rate_model0: &RateModel,
+        rate_model1: &RateModel,
         futarchy_authority: &crate::state::FutarchyAuthority,
         pair_key: Pubkey,
         event_authority: Option<AccountInfo<'info>>,
     ) -> Result<()> {
         let current_slot = Clock::get()?.slot;
+        self.sync_risk_params(&futarchy_authority.risk_params);
+        let accrual = self.update_at(rate_model0, rate_model1, futarchy_authority.revenue_share.interest_bps, current_slot)?;
+
+        if let (Some(accrual), Some(event_authority)) = (accrual, event_authority) {
+            let (_, event_authority_bump) =
+                Pubkey::find_program_address(&[b"__event_authority"], &crate::ID);
+
+            struct EventCpiAccounts<'a> {
+                event_authority: AccountInfo<'a>,
+            }
+            struct EventCpiBumps {
+                event_authority: u8,
+            }
+            struct EventCpiContext<'a> {
+                accounts: EventCpiAccounts<'a>,
+                bumps: EventCpiBumps,
+            }
+            let ctx = EventCpiContext {
+                accounts: EventCpiAccounts {
+                    event_authority,
+                },
+                bumps: EventCpiBumps {
+                    event_authority: event_authority_bump,
+                },
+            };
+
+            emit_cpi!(UpdatePairEvent {
+                metadata: EventMetadata::new(Pubkey::default(), pair_key),
+                price0_ema: self.last_price0_ema.symmetric,
+                price1_ema: self.last_price1_ema.symmetric,
+                rate0: self.last_rate0,
+                rate1: self.last_rate1,
+                accrued_interest0: accrual.accrued_interest0,
+                accrued_interest1: accrual.accrued_interest1,
+                lp_interest0: accrual.lp_interest0,
+                lp_interest1: accrual.lp_interest1,
+                insurance_interest0: accrual.insurance_interest0,
+                insurance_interest1: accrual.insurance_interest1,
+                protocol_interest0: accrual.protocol_interest0,
+                protocol_interest1: accrual.protocol_interest1,
+                cash_reserve0: self.cash_reserve0,
+                cash_reserve1: self.cash_reserve1,
+                reserve0_after_interest: self.reserve0,
+                reserve1_after_interest: self.reserve1,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Brings price EMAs, rates and interest up to `current_slot`.
+    ///
+    /// Returns the interest applied, or `None` if no time has elapsed since `last_update`.
+    pub fn update_at(
+        &mut self,
+        rate_model0: &RateModel,
+        rate_model1: &RateModel,
+        interest_bps: u16,
+        current_slot: u64,
+    ) -> Result<Option<InterestAccrual>> {
         let spot_price0 = self.spot_price0_nad();
         let spot_price1 = self.spot_price1_nad();
 
@@ -338,37 +567,42 @@ impl Pair {
         self.last_price0_ema.directional = self.last_price0_ema.directional.min(spot_price0);
         self.last_price1_ema.directional = self.last_price1_ema.directional.min(spot_price1);
         
+        let mut accrual = None;
         if current_slot > self.last_update {
             // Update oracles
             let time_elapsed = slots_to_ms(self.last_update, current_slot).unwrap();
             if time_elapsed > 0 {
                 // Update price EMAs
-                self.last_price0_ema.symmetric = compute_ema(
+                self.last_price0_ema.symmetric = compute_ema_at(
                     self.last_price0_ema.symmetric,
                     self.last_update,
                     spot_price0,
-                    self.half_life
+                    self.half_life,
+                    current_slot,
                 );
-                self.last_price1_ema.symmetric = compute_ema(
+                self.last_price1_ema.symmetric = compute_ema_at(
                     self.last_price1_ema.symmetric,
                     self.last_update,
                     spot_price1,
-                    self.half_life
+                    self.half_life,
+                    current_slot,
                 );
 
-                let new_ema0 = compute_ema(
+                let new_ema0 = compute_ema_at(
                     self.last_price0_ema.directional,
                     self.last_update,
                     spot_price0,
-                    DIRECTIONAL_EMA_HALF_LIFE_MS
+                    DIRECTIONAL_EMA_HALF_LIFE_MS,
+                    current_slot,
                 );
                 self.last_price0_ema.directional = if spot_price0 < new_ema0 { spot_price0 } else { new_ema0 };
                 
-                let new_ema1 = compute_ema(
+                let new_ema1 = compute_ema_at(
                     self.last_price1_ema.directional,
                     self.last_update,
                     spot_price1,
-                    DIRECTIONAL_EMA_HALF_LIFE_MS
+                    DIRECTIONAL_EMA_HALF_LIFE_MS,
+                    current_slot,
                 );
                 self.last_price1_ema.directional = if spot_price1 < new_ema1 { spot_price1 } else { new_ema1 };
                 
@@ -389,12 +623,12 @@ impl Pair {
                 };
                 
                 // Calculate new rates
-                let (new_rate0, integral0) = rate_model.calculate_rate(
+                let (new_rate0, integral0) = rate_model0.calculate_rate(
                     self.last_rate0, 
                     time_elapsed, 
                     util0
                 );
-                let (new_rate1, integral1) = rate_model.calculate_rate(
+                let (new_rate1, integral1) = rate_model1.calculate_rate(
                     self.last_rate1, 
                     time_elapsed, 
                     util1
@@ -410,16 +644,16 @@ impl Pair {
 
                 // Calculate protocol fee as an extra fee on top of interest (not a share of interest)
                 // Borrowers pay: interest + protocol_fee
-                // LPs receive: interest (full amount)
+                // LPs receive: interest (less the insurance share below)
                 // Protocol receives: protocol_fee (extra fee charged to borrowers)
                 let protocol_fee0: u64 = u64::try_from(
-                    (total_interest0 * futarchy_authority.revenue_share.interest_bps as u128) / BPS_DENOMINATOR as u128
+                    (total_interest0 * interest_bps as u128) / BPS_DENOMINATOR as u128
                 ).unwrap_or(u64::MAX);
                 let protocol_fee1: u64 = u64::try_from(
-                    (total_interest1 * futarchy_authority.revenue_share.interest_bps as u128) / BPS_DENOMINATOR as u128
+                    (total_interest1 * interest_bps as u128) / BPS_DENOMINATOR as u128
                 ).unwrap_or(u64::MAX);
-                let lp_interest0 = u64::try_from(total_interest0).unwrap_or(u64::MAX);
-                let lp_interest1 = u64::try_from(total_interest1).unwrap_or(u64::MAX);
+                let gross_lp_interest0 = u64::try_from(total_interest0).unwrap_or(u64::MAX);
+                let gross_lp_interest1 = u64::try_from(total_interest1).unwrap_or(u64::MAX);
 
                 // Total amount borrowers owe = interest + protocol_fee (extra fee)
                 let total_borrower_cost0 = total_interest0.checked_add(protocol_fee0 as u128).expect("Interest overflow");
@@ -448,64 +682,44 @@ impl Pair {
                 let cash_covered_fee0 = protocol_fee0.min(self.cash_reserve0);
                 let cash_covered_fee1 = protocol_fee1.min(self.cash_reserve1);
 
-                // 2. Update virtual reserves
+                // 2. The insurance share of the LP interest is moved out of the remaining cash the same way,
+                // any uncovered part stays with LPs: lp_interest = gross_lp_interest - insurance_interest
+                let insurance_interest0 = (((gross_lp_interest0 as u128) * self.insurance_interest_share_bps as u128
+                    / BPS_DENOMINATOR as u128) as u64).min(self.cash_reserve0 - cash_covered_fee0);
+                let insurance_interest1 = (((gross_lp_interest1 as u128) * self.insurance_interest_share_bps as u128
+                    / BPS_DENOMINATOR as u128) as u64).min(self.cash_reserve1 - cash_covered_fee1);
+                let lp_interest0 = gross_lp_interest0 - insurance_interest0;
+                let lp_interest1 = gross_lp_interest1 - insurance_interest1;
+
+                // 3. Update virtual reserves
                 // ΔV = lp_interest + (protocol_fee - cash_covered_fee)
                 self.reserve0 = self.reserve0.saturating_add(lp_interest0 + (protocol_fee0 - cash_covered_fee0));
                 self.reserve1 = self.reserve1.saturating_add(lp_interest1 + (protocol_fee1 - cash_covered_fee1));
 
-                // 3. Update physical cash reserves
+                // 4. Update physical cash reserves
                 // Cash reserves are reduced by the amount we can afford to take (as r_cash can't go below zero), 
                 // Any uncovered fee remains in virtual reserves, so LP's gets the claim on the uncovered fee
-                self.cash_reserve0 -= cash_covered_fee0; // won't underflow because cash_covered_fee <= cash_reserve
-                self.cash_reserve1 -= cash_covered_fee1;
-
-                if let Some(event_authority) = event_authority {
-                    let (_, event_authority_bump) =
-                        Pubkey::find_program_address(&[b"__event_authority"], &crate::ID);
-
-                    struct EventCpiAccounts<'a> {
-                        event_authority: AccountInfo<'a>,
-                    }
-                    struct EventCpiBumps {
-                        event_authority: u8,
-                    }
-                    struct EventCpiContext<'a> {
-                        accounts: EventCpiAccounts<'a>,
-                        bumps: EventCpiBumps,
-                    }
-                    let ctx = EventCpiContext {
-                        accounts: EventCpiAccounts {
-                            event_authority,
-                        },
-                        bumps: EventCpiBumps {
-                            event_authority: event_authority_bump,
-                        },
-                    };
-
-                    emit_cpi!(UpdatePairEvent {
-                        metadata: EventMetadata::new(Pubkey::default(), pair_key),
-                        price0_ema: self.last_price0_ema.symmetric,
-                        price1_ema: self.last_price1_ema.symmetric,
-                        rate0: self.last_rate0,
-                        rate1: self.last_rate1,
-                        accrued_interest0: total_borrower_cost0,
-                        accrued_interest1: total_borrower_cost1,
-                        lp_interest0,
-                        lp_interest1,
-                        protocol_interest0: protocol_fee0,
-                        protocol_interest1: protocol_fee1,
-                        cash_reserve0: self.cash_reserve0,
-                        cash_reserve1: self.cash_reserve1,
-                        reserve0_after_interest: self.reserve0,
-                        reserve1_after_interest: self.reserve1,
-                    });
-                }
+                self.cash_reserve0 -= cash_covered_fee0 + insurance_interest0; // won't underflow, both are capped by cash_reserve
+                self.cash_reserve1 -= cash_covered_fee1 + insurance_interest1;
+                self.insurance_reserve0 = self.insurance_reserve0.saturating_add(insurance_interest0);
+                self.insurance_reserve1 = self.insurance_reserve1.saturating_add(insurance_interest1);
+
+                accrual = Some(InterestAccrual {
+                    accrued_interest0: total_borrower_cost0,
+                    accrued_interest1: total_borrower_cost1,
+                    lp_interest0,
+                    lp_interest1,
+                    insurance_interest0,
+                    insurance_interest1,
+                    protocol_interest0: protocol_fee0,
+                    protocol_interest1: protocol_fee1,
+                });
             }
             
             self.last_update = current_slot;
         }
         
-        Ok(())
+        Ok(accrual)
     }
 }
 
@@ -520,4 +734,118 @@ macro_rules! generate_gamm_pair_seeds {
             &[$pair.bump],
         ]
     };
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::{
+            lending::{
+                borrow::apply_borrow,
+                liquidate::{apply_liquidation, validate_liquidation},
+            },
+            SwapAmounts,
+        },
+        state::{DelegatePermission, UserPosition, VaultBumps},
+    };
+
+    const RESERVE: u64 = 1_000_000_000;
+    const COLLATERAL: u64 = 100_000_000;
+
+    fn test_rate_model() -> RateModel {
+        RateModel::new(
+            TARGET_UTIL_START_BPS,
+            TARGET_UTIL_END_BPS,
+            DEFAULT_RATE_HALF_LIFE_MS,
+            DEFAULT_MIN_RATE_BPS,
+            0,
+            DEFAULT_INITIAL_RATE_BPS,
+        )
+    }
+
+    fn test_pair(rate_model: &RateModel) -> Pair {
+        let mut pair = Pair::initialize(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            6,
+            6,
+            Pubkey::new_unique(),
+            30,
+            60_000,
+            Some(8_000),
+            100,
+            [0; 32],
+            VERSION,
+            1,
+            VaultBumps::default(),
+            rate_model.initial_rate,
+            RiskParams::default(),
+        );
+        pair.reserve0 = RESERVE;
+        pair.reserve1 = RESERVE;
+        pair.cash_reserve0 = RESERVE;
+        pair.cash_reserve1 = RESERVE;
+        pair.last_price0_ema.symmetric = pair.spot_price0_nad();
+        pair.last_price0_ema.directional = pair.spot_price0_nad();
+        pair.last_price1_ema.symmetric = pair.spot_price1_nad();
+        pair.last_price1_ema.directional = pair.spot_price1_nad();
+        pair
+    }
+
+    fn test_position() -> UserPosition {
+        UserPosition {
+            owner: Pubkey::new_unique(),
+            pair: Pubkey::new_unique(),
+            collateral0_liquidation_cf_bps: 0,
+            collateral1_liquidation_cf_bps: 0,
+            collateral0: COLLATERAL,
+            collateral1: 0,
+            debt0_shares: 0,
+            debt1_shares: 0,
+            bump: 1,
+            cross_margin: false,
+            position_index: 0,
+            delegate: Pubkey::default(),
+            delegate_permission: DelegatePermission::None,
+            delegate_expiry_slot: 0,
+            open_orders: 0,
+        }
+    }
+
+    #[test]
+    fn insolvent_liquidation_draws_on_insurance_before_lps() {
+        let rate_model = test_rate_model();
+        let mut pair = test_pair(&rate_model);
+        let mut user_position = test_position();
+        pair.total_collateral0 = COLLATERAL;
+        let token1 = pair.token1;
+        apply_borrow(&mut pair, &mut user_position, &token1, u64::MAX).unwrap();
+
+        // Crash token0 far enough to leave the position insolvent and let the EMA catch up
+        SwapAmounts::exact_in(&pair, 0, true, 10 * RESERVE).unwrap().apply(&mut pair, true).unwrap();
+        // Only the reserve set below backs the shortfall
+        pair.insurance_interest_share_bps = 0;
+        pair.update_at(&rate_model, &rate_model, 0, pair.last_update + 100_000).unwrap();
+        validate_liquidation(&pair, &user_position, true).unwrap();
+
+        let (mut uninsured, mut uninsured_position) = (pair.clone(), user_position.clone());
+        let amounts = apply_liquidation(&mut uninsured, &mut uninsured_position, true).unwrap();
+        let bad_debt = amounts.bad_debt;
+        assert!(bad_debt > 0);
+        assert_eq!(amounts.bad_debt_covered, 0);
+        assert_eq!(uninsured.total_bad_debt1, pair.total_bad_debt1 + bad_debt);
+        assert_eq!(uninsured.total_socialized_debt1, pair.total_socialized_debt1 + bad_debt);
+
+        let (mut insured, mut insured_position) = (pair.clone(), user_position.clone());
+        insured.insurance_reserve1 += bad_debt / 2;
+        let amounts = apply_liquidation(&mut insured, &mut insured_position, true).unwrap();
+        let covered = amounts.bad_debt_covered;
+        assert_eq!(covered, bad_debt / 2);
+        assert_eq!(amounts.socialized_debt(), bad_debt - covered);
+        assert_eq!(insured.insurance_reserve1, uninsured.insurance_reserve1);
+        assert_eq!(insured.reserve1, uninsured.reserve1 + covered);
+        assert_eq!(insured.cash_reserve1, uninsured.cash_reserve1 + covered);
+        assert_eq!(insured.total_socialized_debt1, pair.total_socialized_debt1 + bad_debt - covered);
+    }
+}
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::ErrorCode;
+
+/// Caps on how far lending on a pair can grow, set by the futarchy authority through `set_pair_caps`.
+///
+/// A cap of 0 is disabled, so pairs start, and are migrated, without caps. Caps only block the
+/// instructions that grow the capped total; a pair above a lowered cap can still be unwound.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct PairCaps {
+    /// Cap on `total_debt0`, checked by borrows of token0
+    pub max_debt0: u64,
+    /// Cap on `total_debt1`, checked by borrows of token1
+    pub max_debt1: u64,
+    /// Cap on the utilization (total debt / virtual reserve) of the borrowed side after a borrow
+    pub max_utilization_bps: u16,
+    /// Cap on `total_collateral0`, checked when token0 collateral is added
+    pub max_collateral0: u64,
+    /// Cap on `total_collateral1`, checked when token1 collateral is added
+    pub max_collateral1: u64,
+}
+
+impl PairCaps {
+    pub fn validate(&self) -> Result<()> {
+        require_gte!(BPS_DENOMINATOR, self.max_utilization_bps, ErrorCode::InvalidPairCaps);
+        Ok(())
+    }
+
+    /// Cap on the total debt of one side, `None` if disabled.
+    pub fn max_debt(&self, is_token0: bool) -> Option<u64> {
+        let cap = if is_token0 { self.max_debt0 } else { self.max_debt1 };
+        (cap > 0).then_some(cap)
+    }
+
+    /// Utilization cap scaled by 1e9 like `Pair::utilizations_nad`, `None` if disabled.
+    pub fn max_utilization_nad(&self) -> Option<u64> {
+        (self.max_utilization_bps > 0)
+            .then(|| self.max_utilization_bps as u64 * NAD / BPS_DENOMINATOR as u64)
+    }
+
+    /// Cap on the total collateral of one side, `None` if disabled.
+    pub fn max_collateral(&self, is_token0: bool) -> Option<u64> {
+        let cap = if is_token0 { self.max_collateral0 } else { self.max_collateral1 };
+        (cap > 0).then_some(cap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::lending::borrow::apply_borrow,
+        state::{DelegatePermission, Pair, RiskParams, UserPosition, VaultBumps},
+    };
+
+    const RESERVE: u64 = 1_000_000_000;
+
+    fn test_pair() -> Pair {
+        let mut pair = Pair::initialize(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            6,
+            6,
+            Pubkey::new_unique(),
+            30,
+            60_000,
+            Some(8_000),
+            0,
+            [0; 32],
+            VERSION,
+            1,
+            VaultBumps::default(),
+            0,
+            RiskParams::default(),
+        );
+        pair.reserve0 = RESERVE;
+        pair.reserve1 = RESERVE;
+        pair.cash_reserve0 = RESERVE;
+        pair.cash_reserve1 = RESERVE;
+        pair.last_price0_ema.symmetric = pair.spot_price0_nad();
+        pair.last_price0_ema.directional = pair.spot_price0_nad();
+        pair.last_price1_ema.symmetric = pair.spot_price1_nad();
+        pair.last_price1_ema.directional = pair.spot_price1_nad();
+        pair.total_collateral0 = 100_000_000;
+        pair
+    }
+
+    fn test_position() -> UserPosition {
+        UserPosition {
+            owner: Pubkey::new_unique(),
+            pair: Pubkey::new_unique(),
+            collateral0_liquidation_cf_bps: 0,
+            collateral1_liquidation_cf_bps: 0,
+            collateral0: 100_000_000,
+            collateral1: 0,
+            debt0_shares: 0,
+            debt1_shares: 0,
+            bump: 1,
+            cross_margin: false,
+            position_index: 0,
+            delegate: Pubkey::default(),
+            delegate_permission: DelegatePermission::None,
+            delegate_expiry_slot: 0,
+            open_orders: 0,
+        }
+    }
+
+    #[test]
+    fn borrows_stop_at_the_debt_and_utilization_caps() {
+        let borrow = |caps: PairCaps, amount: u64| {
+            let mut pair = Pair { caps, ..test_pair() };
+            let token1 = pair.token1;
+            apply_borrow(&mut pair, &mut test_position(), &token1, amount).map(|_| pair.total_debt1)
+        };
+
+        let max_debt1 = PairCaps { max_debt1: 10_000_000, ..PairCaps::default() };
+        assert_eq!(borrow(max_debt1, 10_000_000).unwrap(), 10_000_000);
+        assert_eq!(borrow(max_debt1, 10_000_001).unwrap_err(), error!(ErrorCode::BorrowCapExceeded));
+        // Caps are per token
+        assert!(borrow(PairCaps { max_debt0: 1, ..PairCaps::default() }, 10_000_001).is_ok());
+
+        // 1% of the 1e9 virtual reserve
+        let max_utilization = PairCaps { max_utilization_bps: 100, ..PairCaps::default() };
+        assert!(borrow(max_utilization, 10_000_000).is_ok());
+        assert_eq!(borrow(max_utilization, 10_000_001).unwrap_err(), error!(ErrorCode::UtilizationCapExceeded));
+    }
+}
@@ -0,0 +1,232 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use super::Pair;
+
+/// Price a [`PositionOrder`] trigger is compared against, after the pair is brought up to date.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum OrderPriceSource {
+    /// Symmetric EMA price, slow to move within a transaction
+    #[default]
+    Ema,
+    /// Spot price of the reserves. A swap in the same transaction can push it across the trigger,
+    /// so the EMA price must be across it as well
+    Spot,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum TriggerCondition {
+    /// Triggered while the price is strictly below the trigger price
+    #[default]
+    Below,
+    /// Triggered while the price is strictly above the trigger price
+    Above,
+}
+
+/// Price condition of a [`PositionOrder`], e.g. `ema_price0_nad < price_nad` for a stop-loss on
+/// token0 collateral.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct OrderTrigger {
+    pub price_source: OrderPriceSource,
+    /// Compares the price of token0 in token1 if true, else the price of token1 in token0
+    pub is_price0: bool,
+    pub condition: TriggerCondition,
+    /// Trigger price (NAD)
+    pub price_nad: u64,
+}
+
+impl OrderTrigger {
+    /// Current price the trigger is compared against.
+    pub fn price(&self, pair: &Pair) -> u64 {
+        match (self.price_source, self.is_price0) {
+            (OrderPriceSource::Ema, true) => pair.ema_price0_nad(),
+            (OrderPriceSource::Ema, false) => pair.ema_price1_nad(),
+            (OrderPriceSource::Spot, true) => pair.spot_price0_nad(),
+            (OrderPriceSource::Spot, false) => pair.spot_price1_nad(),
+        }
+    }
+
+    pub fn is_met(&self, pair: &Pair) -> bool {
+        let is_across = |price: u64| match self.condition {
+            TriggerCondition::Below => price < self.price_nad,
+            TriggerCondition::Above => price > self.price_nad,
+        };
+        let ema_price = match self.is_price0 {
+            true => pair.ema_price0_nad(),
+            false => pair.ema_price1_nad(),
+        };
+        is_across(ema_price) && is_across(self.price(pair))
+    }
+}
+
+/// Stop-loss or take-profit order on a [`super::UserPosition`], placed by its owner.
+///
+/// Once the trigger is met, any keeper may close the ordered collateral side of the position
+/// through `execute_position_order` for `keeper_bounty`, paid out of the released collateral.
+#[account]
+#[derive(InitSpace)]
+pub struct PositionOrder {
+    pub user_position: Pubkey,
+    pub owner: Pubkey,
+    pub pair: Pubkey,
+    /// Distinguishes the orders of one position, part of the order's address
+    pub order_index: u16,
+    /// Collateral side closed on execution; the debt repaid is in the other token
+    pub is_collateral_token0: bool,
+    pub trigger: OrderTrigger,
+    /// Slippage bound on the collateral returned to the owner, after the keeper bounty
+    pub min_collateral_out: u64,
+    /// Collateral paid to the executing keeper
+    pub keeper_bounty: u64,
+    pub bump: u8,
+}
+
+impl PositionOrder {
+    /// Collateral the close must release to cover both the bounty and the owner's slippage bound.
+    pub fn min_close_collateral_out(&self) -> Result<u64> {
+        Ok(self.min_collateral_out.checked_add(self.keeper_bounty).ok_or(ErrorCode::Overflow)?)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::*,
+        instructions::{
+            lending::{
+                borrow::apply_borrow,
+                close_position::apply_close_position,
+                execute_position_order::{apply_position_order, validate_position_order},
+            },
+            SwapAmounts,
+        },
+        state::{DelegatePermission, RateModel, RiskParams, UserPosition, VaultBumps},
+    };
+
+    const RESERVE: u64 = 1_000_000_000;
+
+    fn test_pair() -> Pair {
+        let mut pair = Pair::initialize(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            6,
+            6,
+            Pubkey::new_unique(),
+            30,
+            60_000,
+            Some(8_000),
+            0,
+            [0; 32],
+            VERSION,
+            1,
+            VaultBumps::default(),
+            0,
+            RiskParams::default(),
+        );
+        pair.reserve0 = RESERVE;
+        pair.reserve1 = RESERVE;
+        pair.cash_reserve0 = RESERVE;
+        pair.cash_reserve1 = RESERVE;
+        pair.last_price0_ema.symmetric = pair.spot_price0_nad();
+        pair.last_price0_ema.directional = pair.spot_price0_nad();
+        pair.last_price1_ema.symmetric = pair.spot_price1_nad();
+        pair.last_price1_ema.directional = pair.spot_price1_nad();
+        pair
+    }
+
+    fn test_position() -> UserPosition {
+        UserPosition {
+            owner: Pubkey::new_unique(),
+            pair: Pubkey::new_unique(),
+            collateral0_liquidation_cf_bps: 0,
+            collateral1_liquidation_cf_bps: 0,
+            collateral0: 100_000_000,
+            collateral1: 0,
+            debt0_shares: 0,
+            debt1_shares: 0,
+            bump: 1,
+            cross_margin: false,
+            position_index: 0,
+            delegate: Pubkey::default(),
+            delegate_permission: DelegatePermission::None,
+            delegate_expiry_slot: 0,
+            open_orders: 1,
+        }
+    }
+
+    #[test]
+    fn position_order_closes_like_close_position_once_triggered() {
+        let mut pair = test_pair();
+        let mut user_position = test_position();
+        pair.total_collateral0 = user_position.collateral0;
+        let token1 = pair.token1;
+        apply_borrow(&mut pair, &mut user_position, &token1, 10_000_000).unwrap();
+        let price0 = pair.ema_price0_nad();
+
+        // Stop-loss on the token0 collateral
+        let mut order = PositionOrder {
+            user_position: Pubkey::new_unique(),
+            owner: user_position.owner,
+            pair: Pubkey::new_unique(),
+            order_index: 0,
+            is_collateral_token0: true,
+            trigger: OrderTrigger {
+                price_source: OrderPriceSource::Ema,
+                is_price0: true,
+                condition: TriggerCondition::Below,
+                price_nad: price0,
+            },
+            min_collateral_out: 0,
+            keeper_bounty: 100_000,
+            bump: 1,
+        };
+        let err = validate_position_order(&pair, &user_position, &order).unwrap_err();
+        assert_eq!(err, ErrorCode::PositionOrderNotTriggered.into());
+
+        order.trigger.price_nad = price0 + 1;
+        validate_position_order(&pair, &user_position, &order).unwrap();
+        let (mut executed, mut executed_position) = (pair.clone(), user_position.clone());
+        let executed_amounts = apply_position_order(&mut executed, &mut executed_position, &order, 0).unwrap();
+        let closed_amounts = apply_close_position(&mut pair.clone(), &mut user_position.clone(), 0, true, 0).unwrap();
+        assert_eq!(executed_amounts, closed_amounts);
+        assert_eq!(executed_position.collateral0, 0);
+        assert_eq!(executed_position.debt1_shares, 0);
+        assert_eq!(executed_position.open_orders, 0);
+
+        // The owner's bound applies after the bounty
+        order.min_collateral_out = closed_amounts.collateral_out - order.keeper_bounty + 1;
+        let err = apply_position_order(&mut pair.clone(), &mut user_position.clone(), &order, 0).unwrap_err();
+        assert_eq!(err, ErrorCode::SlippageExceeded.into());
+    }
+
+    #[test]
+    fn spot_trigger_waits_for_the_ema() {
+        let rate_model = RateModel::new(
+            TARGET_UTIL_START_BPS,
+            TARGET_UTIL_END_BPS,
+            DEFAULT_RATE_HALF_LIFE_MS,
+            DEFAULT_MIN_RATE_BPS,
+            0,
+            DEFAULT_INITIAL_RATE_BPS,
+        );
+        let mut pair = test_pair();
+        let trigger = OrderTrigger {
+            price_source: OrderPriceSource::Spot,
+            is_price0: true,
+            condition: TriggerCondition::Below,
+            price_nad: pair.spot_price0_nad() * 9 / 10,
+        };
+
+        // A swap pushes spot across the trigger within the slot, the EMA has not moved yet
+        SwapAmounts::exact_in(&pair, 0, true, 200_000_000).unwrap().apply(&mut pair, true).unwrap();
+        assert!(pair.spot_price0_nad() < trigger.price_nad);
+        assert!(!trigger.is_met(&pair));
+
+        // Once the EMA follows, the trigger is met
+        pair.update_at(&rate_model, &rate_model, 0, pair.last_update + 100_000).unwrap();
+        assert!(pair.ema_price0_nad() < trigger.price_nad);
+        assert!(trigger.is_met(&pair));
+    }
+}
@@ -1,7 +1,44 @@
 use anchor_lang::prelude::*;
 use crate::constants::*;
+use crate::errors::ErrorCode;
 use crate::utils::math::*;
 
+/// Shape of the rate curve of a [`RateModel`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum RateModelKind {
+    /// Rate drifts exponentially towards the target utilization band (doubles or halves every `half_life_ms`)
+    #[default]
+    Exponential,
+    /// Rate is a piecewise-linear function of utilization (base rate, `slope1` up to the kink,
+    /// `slope2` above it) and applies instantly
+    Kinked,
+}
+
+/// Curve fields of a [`RateModel`], i.e. everything `calculate_rate` reads.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct RateModelParams {
+    pub exp_rate: u64,
+    pub target_util_start: u64,
+    pub target_util_end: u64,
+    pub half_life_ms: u64,
+    pub min_rate: u64,
+    pub max_rate: u64,
+    pub initial_rate: u64,
+    pub kind: RateModelKind,
+    pub kink_util: u64,
+    pub base_rate: u64,
+    pub slope1: u64,
+    pub slope2: u64,
+}
+
+/// Update queued by `update_rate_model`, applied by `apply_rate_model_update` from `eta_slot` on.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct PendingRateModelUpdate {
+    pub params: RateModelParams,
+    pub update_delay_slots: u64,
+    pub eta_slot: u64,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct RateModel {
@@ -23,6 +60,22 @@ pub struct RateModel {
     pub max_rate: u64,
     /// Initial interest rate for new pairs using this model (NAD-scaled)
     pub initial_rate: u64,
+    /// Curve used by `calculate_rate`. Models created before the kinked variant are `Exponential`
+    pub kind: RateModelKind,
+    /// Kinked only: utilization where `slope2` takes over (NAD-scaled, below NAD)
+    pub kink_util: u64,
+    /// Kinked only: rate at zero utilization (NAD-scaled)
+    pub base_rate: u64,
+    /// Kinked only: rate added between zero utilization and the kink (NAD-scaled)
+    pub slope1: u64,
+    /// Kinked only: rate added between the kink and full utilization (NAD-scaled)
+    pub slope2: u64,
+    /// Timelock of `update_rate_model`, in slots
+    pub update_delay_slots: u64,
+    /// Update waiting for its timelock, if any
+    pub pending_update: Option<PendingRateModelUpdate>,
+    /// Pair sides (`rate_model0` / `rate_model1`) set to this model, all accrued by `apply_rate_model_update`
+    pub pair_references: u64,
 }
 
 impl RateModel {
@@ -54,9 +107,133 @@ impl RateModel {
             min_rate: Self::bps_to_nad(min_rate_bps),
             max_rate: if max_rate_bps == 0 { 0 } else { Self::bps_to_nad(max_rate_bps) },
             initial_rate: Self::bps_to_nad(initial_rate_bps),
+            kind: RateModelKind::Exponential,
+            kink_util: 0,
+            base_rate: 0,
+            slope1: 0,
+            slope2: 0,
+            update_delay_slots: DEFAULT_RATE_MODEL_UPDATE_DELAY_SLOTS,
+            pending_update: None,
+            pair_references: 0,
+        }
+    }
+
+    /// Creates a kinked (jump-rate) RateModel
+    ///
+    /// # Arguments
+    /// * `kink_util_bps` - Utilization where the curve switches from `slope1` to `slope2` (bps)
+    /// * `base_rate_bps` - Rate at zero utilization (bps)
+    /// * `slope1_bps` - Rate added from zero utilization up to the kink (bps)
+    /// * `slope2_bps` - Rate added from the kink up to full utilization (bps)
+    /// * `max_rate_bps` - Maximum rate ceiling (bps, 0 = no cap)
+    pub fn new_kinked(
+        kink_util_bps: u64,
+        base_rate_bps: u64,
+        slope1_bps: u64,
+        slope2_bps: u64,
+        max_rate_bps: u64,
+    ) -> Self {
+        let base_rate = Self::bps_to_nad(base_rate_bps);
+
+        Self {
+            exp_rate: 0,
+            target_util_start: 0,
+            target_util_end: 0,
+            half_life_ms: 0,
+            min_rate: base_rate,
+            max_rate: if max_rate_bps == 0 { 0 } else { Self::bps_to_nad(max_rate_bps) },
+            // Pairs start with no debt
+            initial_rate: base_rate,
+            kind: RateModelKind::Kinked,
+            kink_util: Self::bps_to_nad(kink_util_bps),
+            base_rate,
+            slope1: Self::bps_to_nad(slope1_bps),
+            slope2: Self::bps_to_nad(slope2_bps),
+            update_delay_slots: DEFAULT_RATE_MODEL_UPDATE_DELAY_SLOTS,
+            pending_update: None,
+            pair_references: 0,
+        }
+    }
+
+    pub fn params(&self) -> RateModelParams {
+        RateModelParams {
+            exp_rate: self.exp_rate,
+            target_util_start: self.target_util_start,
+            target_util_end: self.target_util_end,
+            half_life_ms: self.half_life_ms,
+            min_rate: self.min_rate,
+            max_rate: self.max_rate,
+            initial_rate: self.initial_rate,
+            kind: self.kind,
+            kink_util: self.kink_util,
+            base_rate: self.base_rate,
+            slope1: self.slope1,
+            slope2: self.slope2,
         }
     }
 
+    fn set_params(&mut self, params: &RateModelParams) {
+        self.exp_rate = params.exp_rate;
+        self.target_util_start = params.target_util_start;
+        self.target_util_end = params.target_util_end;
+        self.half_life_ms = params.half_life_ms;
+        self.min_rate = params.min_rate;
+        self.max_rate = params.max_rate;
+        self.initial_rate = params.initial_rate;
+        self.kind = params.kind;
+        self.kink_util = params.kink_util;
+        self.base_rate = params.base_rate;
+        self.slope1 = params.slope1;
+        self.slope2 = params.slope2;
+    }
+
+    /// Queues `params` and the next `update_delay_slots` behind the current timelock,
+    /// replacing any pending update. Returns the pending update.
+    pub fn queue_update(
+        &mut self,
+        params: RateModelParams,
+        update_delay_slots: u64,
+        current_slot: u64,
+    ) -> Result<PendingRateModelUpdate> {
+        require!(
+            (MIN_RATE_MODEL_UPDATE_DELAY_SLOTS..=MAX_RATE_MODEL_UPDATE_DELAY_SLOTS).contains(&update_delay_slots),
+            ErrorCode::InvalidRateModelUpdateDelay
+        );
+        let pending_update = PendingRateModelUpdate {
+            params,
+            update_delay_slots,
+            eta_slot: current_slot.checked_add(self.update_delay_slots).ok_or(ErrorCode::Overflow)?,
+        };
+        self.pending_update = Some(pending_update);
+        Ok(pending_update)
+    }
+
+    /// Applies the pending update once its timelock has passed. Returns the applied update.
+    ///
+    /// Pairs using this model must be accrued up to `current_slot` first,
+    /// otherwise the new curve also prices their interest since their last update.
+    pub fn apply_pending_update(&mut self, current_slot: u64) -> Result<PendingRateModelUpdate> {
+        let pending_update = self.pending_update.ok_or(ErrorCode::NoPendingRateModelUpdate)?;
+        require_gte!(current_slot, pending_update.eta_slot, ErrorCode::RateModelUpdateNotReady);
+
+        self.set_params(&pending_update.params);
+        self.update_delay_slots = pending_update.update_delay_slots;
+        self.pending_update = None;
+        Ok(pending_update)
+    }
+
+    /// Counts a pair side newly set to this model.
+    pub fn add_pair_reference(&mut self) -> Result<()> {
+        self.pair_references = self.pair_references.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Uncounts a pair side moved off this model.
+    pub fn remove_pair_reference(&mut self) -> Result<()> {
+        self.pair_references = self.pair_references.checked_sub(1).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
     /// Validates that utilization bounds are valid:
     /// - start < end
     /// - both within [100, 10000] bps
@@ -117,10 +294,44 @@ impl RateModel {
         true
     }
 
+    /// Validates kinked rate model parameters
+    /// - kink_util_bps within [MIN_TARGET_UTIL_BPS, MAX_TARGET_UTIL_BPS)
+    /// - rate at full utilization (base + slope1 + slope2) <= MAX_ALLOWED_RATE_BPS
+    /// - base_rate_bps <= max_rate_bps <= MAX_ALLOWED_RATE_BPS (if max is set)
+    pub fn validate_kinked_params(
+        kink_util_bps: u64,
+        base_rate_bps: u64,
+        slope1_bps: u64,
+        slope2_bps: u64,
+        max_rate_bps: u64,
+    ) -> bool {
+        // The kink must leave room for slope2
+        if !(MIN_TARGET_UTIL_BPS..MAX_TARGET_UTIL_BPS).contains(&kink_util_bps) {
+            return false;
+        }
+
+        let full_util_rate_bps = base_rate_bps
+            .checked_add(slope1_bps)
+            .and_then(|rate| rate.checked_add(slope2_bps));
+        if full_util_rate_bps.map_or(true, |rate| rate > MAX_ALLOWED_RATE_BPS) {
+            return false;
+        }
+
+        if max_rate_bps > 0 && (max_rate_bps > MAX_ALLOWED_RATE_BPS || base_rate_bps > max_rate_bps) {
+            return false;
+        }
+
+        true
+    }
+
     /// Returns (current_rate_NAD, integral_NAD) where:
     /// - current_rate_NAD is APR in NAD
     /// - integral_NAD = rate * (dt / YEAR) in NAD, suitable for: interest = debt * integral / NAD
     pub fn calculate_rate(&self, last_rate: u64, time_elapsed: u64, last_util: u64) -> (u64, u64) {
+        if self.kind == RateModelKind::Kinked {
+            return self.calculate_kinked_rate(time_elapsed, last_util);
+        }
+
         let dt = time_elapsed as u128;
         if dt == 0 {
             return (last_rate, 0);
@@ -217,6 +428,29 @@ impl RateModel {
         (last.min(u64::MAX as u128) as u64, integral.min(u64::MAX as u128) as u64)
     }
 
+    /// Kinked curve: the rate at `last_util` applies over the whole window, regardless of `last_rate`.
+    /// Returns the same (current_rate_NAD, integral_NAD) pair as `calculate_rate`.
+    fn calculate_kinked_rate(&self, time_elapsed: u64, last_util: u64) -> (u64, u64) {
+        let util = (last_util as u128).min(NAD as u128);
+        let kink = self.kink_util as u128;
+
+        let rate = if util <= kink {
+            self.base_rate as u128 + (self.slope1 as u128).saturating_mul(util) / kink.max(1)
+        } else {
+            let above_kink = (self.slope2 as u128).saturating_mul(util - kink) / (NAD as u128 - kink).max(1);
+            self.base_rate as u128 + self.slope1 as u128 + above_kink
+        };
+        let rate = match self.max_rate {
+            0 => rate,
+            max_rate => rate.min(max_rate as u128),
+        };
+
+        let dt = time_elapsed as u128;
+        let integral = ceil_div(rate.saturating_mul(dt), MILLISECONDS_PER_YEAR as u128)
+            .unwrap_or(rate.saturating_mul(dt) / (MILLISECONDS_PER_YEAR as u128));
+        (rate.min(u64::MAX as u128) as u64, integral.min(u64::MAX as u128) as u64)
+    }
+
     /// Closed-form time to reach target using ln.
     /// up=false : r(t) = r0 * e^{-k t} <= target  ⇒  t = ln(r0/target) / k = ln(r0/target) * NAD / exp_rate
     /// up=true  : r(t) = r0 * e^{+k t} >= target  ⇒  t = ln(target/r0) / k
@@ -317,6 +551,14 @@ mod tests {
             min_rate: RateModel::bps_to_nad(100),  // OLD: hardcoded MIN_RATE_BPS = 100
             max_rate: 0,  // OLD: no max cap
             initial_rate: RateModel::bps_to_nad(200),  // OLD: hardcoded INITIAL_RATE_BPS = 200
+            kind: RateModelKind::Exponential,
+            kink_util: 0,
+            base_rate: 0,
+            slope1: 0,
+            slope2: 0,
+            update_delay_slots: DEFAULT_RATE_MODEL_UPDATE_DELAY_SLOTS,
+            pending_update: None,
+            pair_references: 0,
         }
     }
 
@@ -540,4 +782,81 @@ mod tests {
             MIN_INITIAL_RATE_BPS
         ));
     }
+
+    #[test]
+    fn test_kinked_rate_follows_curve_instantly() {
+        // 2% base, +8% up to the 80% kink, +100% above it
+        let model = RateModel::new_kinked(8000, 200, 800, 10_000, 0);
+        let hour = 3_600_000;
+
+        // The last rate does not matter, the curve applies instantly
+        for last_rate in [0, RateModel::bps_to_nad(5000)] {
+            assert_eq!(model.calculate_rate(last_rate, hour, 0).0, RateModel::bps_to_nad(200));
+            assert_eq!(model.calculate_rate(last_rate, hour, RateModel::bps_to_nad(4000)).0, RateModel::bps_to_nad(600));
+            assert_eq!(model.calculate_rate(last_rate, hour, RateModel::bps_to_nad(8000)).0, RateModel::bps_to_nad(1000));
+            assert_eq!(model.calculate_rate(last_rate, hour, RateModel::bps_to_nad(9000)).0, RateModel::bps_to_nad(6000));
+            // Utilization above 100% is treated as 100%
+            assert_eq!(model.calculate_rate(last_rate, hour, 2 * NAD).0, RateModel::bps_to_nad(11_000));
+        }
+
+        // Flat over the window: integral = rate * dt / YEAR
+        let rate = RateModel::bps_to_nad(1000) as u128;
+        let (_, integral) = model.calculate_rate(0, MS_PER_DAY, RateModel::bps_to_nad(8000));
+        assert_eq!(integral as u128, ceil_div(rate * MS_PER_DAY as u128, MILLISECONDS_PER_YEAR as u128).unwrap());
+        assert_eq!(model.calculate_rate(0, 0, RateModel::bps_to_nad(8000)), (rate as u64, 0));
+    }
+
+    #[test]
+    fn test_kinked_max_cap_enforced() {
+        let model = RateModel::new_kinked(8000, 200, 800, 10_000, 3000);
+        let (rate, _) = model.calculate_rate(0, 3_600_000, NAD);
+        assert_eq!(rate, RateModel::bps_to_nad(3000));
+    }
+
+    #[test]
+    fn test_kinked_validation() {
+        assert!(RateModel::validate_kinked_params(8000, 200, 800, 10_000, 0));
+        // Kink at 100% leaves no room for slope2
+        assert!(!RateModel::validate_kinked_params(MAX_TARGET_UTIL_BPS, 200, 800, 10_000, 0));
+        // Kink below the minimum utilization
+        assert!(!RateModel::validate_kinked_params(MIN_TARGET_UTIL_BPS - 1, 200, 800, 10_000, 0));
+        // Rate at full utilization above the allowed maximum
+        assert!(!RateModel::validate_kinked_params(8000, 200, 800, MAX_ALLOWED_RATE_BPS, 0));
+        // Base above the cap
+        assert!(!RateModel::validate_kinked_params(8000, 500, 800, 10_000, 400));
+    }
+
+    #[test]
+    fn test_update_waits_for_timelock() {
+        let mut model = default_rate_model();
+        let kinked = RateModel::new_kinked(8000, 200, 800, 10_000, 0);
+        let queued_at = 1_000;
+
+        let pending = model.queue_update(kinked.params(), MIN_RATE_MODEL_UPDATE_DELAY_SLOTS, queued_at).unwrap();
+        assert_eq!(pending.eta_slot, queued_at + DEFAULT_RATE_MODEL_UPDATE_DELAY_SLOTS);
+        assert!(model.apply_pending_update(pending.eta_slot - 1).is_err());
+        assert_eq!(model.params(), default_rate_model().params());
+
+        model.apply_pending_update(pending.eta_slot).unwrap();
+        assert_eq!(model.params(), kinked.params());
+        assert!(model.pending_update.is_none());
+        assert!(model.apply_pending_update(pending.eta_slot).is_err());
+
+        // The next update waits for the new delay
+        let pending = model.queue_update(default_rate_model().params(), MIN_RATE_MODEL_UPDATE_DELAY_SLOTS, queued_at).unwrap();
+        assert_eq!(pending.eta_slot, queued_at + MIN_RATE_MODEL_UPDATE_DELAY_SLOTS);
+        assert!(model.queue_update(kinked.params(), MIN_RATE_MODEL_UPDATE_DELAY_SLOTS - 1, queued_at).is_err());
+        assert!(model.queue_update(kinked.params(), MAX_RATE_MODEL_UPDATE_DELAY_SLOTS + 1, queued_at).is_err());
+    }
+
+    #[test]
+    fn test_pair_references_are_counted() {
+        let mut model = default_rate_model();
+        model.add_pair_reference().unwrap();
+        model.add_pair_reference().unwrap();
+        model.remove_pair_reference().unwrap();
+        assert_eq!(model.pair_references, 1);
+        model.remove_pair_reference().unwrap();
+        assert!(model.remove_pair_reference().is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::errors::ErrorCode;
+
+/// Risk parameters of a pair, in BPS.
+///
+/// The global defaults live on `FutarchyAuthority`; a pair may override them as a whole.
+/// Handlers read the effective values from `Pair::risk_params`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct RiskParams {
+    /// Share of the debt of a solvent position closed by one liquidation
+    pub close_factor_bps: u16,
+    /// Cap on the dynamic collateral factor
+    pub max_collateral_factor_bps: u16,
+    /// Buffer between the borrow limit and the liquidation threshold
+    pub ltv_buffer_bps: u16,
+    /// Liquidator incentive at the liquidation threshold
+    pub liquidation_incentive_bps: u16,
+    /// Incentive added per bps the debt exceeds the borrow limit, in BPS
+    pub liquidation_incentive_slope_bps: u16,
+    pub max_liquidation_incentive_bps: u16,
+    /// Paid by the borrower on top of the seized collateral, the incentive comes out of it
+    pub liquidation_penalty_bps: u16,
+    pub flashloan_fee_bps: u16,
+    /// Fee on liquidity withdrawals, to the remaining LPs
+    pub liquidity_withdrawal_fee_bps: u16,
+    /// Debt coverage required after a liquidity withdrawal
+    pub post_withdraw_debt_coverage_bps: u16,
+}
+
+impl Default for RiskParams {
+    fn default() -> Self {
+        Self {
+            close_factor_bps: DEFAULT_CLOSE_FACTOR_BPS,
+            max_collateral_factor_bps: DEFAULT_MAX_COLLATERAL_FACTOR_BPS,
+            ltv_buffer_bps: DEFAULT_LTV_BUFFER_BPS,
+            liquidation_incentive_bps: DEFAULT_LIQUIDATION_INCENTIVE_BPS,
+            liquidation_incentive_slope_bps: DEFAULT_LIQUIDATION_INCENTIVE_SLOPE_BPS,
+            max_liquidation_incentive_bps: DEFAULT_MAX_LIQUIDATION_INCENTIVE_BPS,
+            liquidation_penalty_bps: DEFAULT_LIQUIDATION_PENALTY_BPS,
+            flashloan_fee_bps: DEFAULT_FLASHLOAN_FEE_BPS,
+            liquidity_withdrawal_fee_bps: DEFAULT_LIQUIDITY_WITHDRAWAL_FEE_BPS,
+            post_withdraw_debt_coverage_bps: DEFAULT_POST_WITHDRAW_DEBT_COVERAGE_BPS,
+        }
+    }
+}
+
+impl RiskParams {
+    /// Checks the hard bounds. The incentive can't exceed the penalty, so liquidations never cost LPs.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            (MIN_CLOSE_FACTOR_BPS..=BPS_DENOMINATOR).contains(&self.close_factor_bps)
+                && (1..=MAX_COLLATERAL_FACTOR_BPS).contains(&self.max_collateral_factor_bps)
+                && (MIN_LTV_BUFFER_BPS..=MAX_LTV_BUFFER_BPS).contains(&self.ltv_buffer_bps)
+                && self.liquidation_incentive_bps <= self.max_liquidation_incentive_bps
+                && self.max_liquidation_incentive_bps <= self.liquidation_penalty_bps
+                && self.liquidation_penalty_bps <= MAX_LIQUIDATION_PENALTY_BPS
+                && self.liquidation_incentive_slope_bps <= BPS_DENOMINATOR
+                && self.flashloan_fee_bps <= MAX_FLASHLOAN_FEE_BPS
+                && self.liquidity_withdrawal_fee_bps <= MAX_LIQUIDITY_WITHDRAWAL_FEE_BPS
+                && (BPS_DENOMINATOR..=MAX_POST_WITHDRAW_DEBT_COVERAGE_BPS).contains(&self.post_withdraw_debt_coverage_bps),
+            ErrorCode::InvalidRiskParams
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Pair, VaultBumps};
+
+    fn test_pair() -> Pair {
+        Pair::initialize(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            6,
+            6,
+            Pubkey::new_unique(),
+            30,
+            60_000,
+            Some(8_000),
+            0,
+            [0; 32],
+            VERSION,
+            1,
+            VaultBumps::default(),
+            0,
+            RiskParams::default(),
+        )
+    }
+
+    #[test]
+    fn defaults_are_within_bounds() {
+        assert!(RiskParams::default().validate().is_ok());
+    }
+
+    #[test]
+    fn incentive_above_penalty_is_rejected() {
+        let params = RiskParams {
+            max_liquidation_incentive_bps: DEFAULT_LIQUIDATION_PENALTY_BPS + 1,
+            ..RiskParams::default()
+        };
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn pairs_follow_global_risk_params_unless_overridden() {
+        let global = RiskParams { close_factor_bps: 10_000, ..RiskParams::default() };
+
+        let mut following = test_pair();
+        following.sync_risk_params(&global);
+        assert_eq!(following.risk_params, global);
+
+        let mut overridden = test_pair();
+        overridden.risk_params.close_factor_bps = 2_000;
+        overridden.risk_params_overridden = true;
+        overridden.sync_risk_params(&global);
+        assert_eq!(overridden.risk_params.close_factor_bps, 2_000);
+    }
+}
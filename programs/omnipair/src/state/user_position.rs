@@ -12,6 +12,26 @@ pub enum DebtDecreaseReason {
     WriteOff(u128),
 }
 
+/// What the delegate of a [`UserPosition`] may do on the owner's behalf, set by `set_position_delegate`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum DelegatePermission {
+    /// No delegate
+    #[default]
+    None,
+    /// Everything the owner can do in `borrow` and `remove_collateral`; the tokens still go to the owner
+    Manage,
+}
+
+impl DelegatePermission {
+    /// Whether a delegate holding `self` may act where `required` is needed.
+    pub fn covers(self, required: DelegatePermission) -> bool {
+        match (self, required) {
+            (_, DelegatePermission::None) | (DelegatePermission::None, _) => false,
+            (DelegatePermission::Manage, DelegatePermission::Manage) => true,
+        }
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct UserPosition {
@@ -31,6 +51,52 @@ pub struct UserPosition {
 
     // PDA bump
     pub bump: u8,
+
+    /// Cross-margin mode: both collateral tokens back the debt of both tokens, netted per token
+    /// (see `NetExposure`). Isolated (false) by default, set by `set_margin_mode`
+    pub cross_margin: bool,
+
+    /// Index among the owner's positions in the pair, the last PDA seed (see `UserPosition::index_seed`).
+    /// 0 is the default position, whose address predates sub-accounts
+    pub position_index: u16,
+
+    /// Operator acting on the owner's behalf within `delegate_permission`, see `UserPosition::is_authorized`
+    pub delegate: Pubkey,
+    pub delegate_permission: DelegatePermission,
+    /// Last slot in which the delegation is valid
+    pub delegate_expiry_slot: u64,
+
+    /// Open `PositionOrder`s on the position, which are bound to the position's address only, so it
+    /// can't be closed or transferred while any is open
+    pub open_orders: u16,
+}
+
+/// Per-token balance of a cross-margin position: collateral minus debt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NetExposure {
+    pub net0: i128,
+    pub net1: i128,
+}
+
+impl NetExposure {
+    /// The token in surplus backing the token in deficit: `(is_collateral_token0, collateral, debt)`.
+    /// `None` if neither token is in deficit; if both are, the collateral is 0 and the debt is the
+    /// deficit of token1 plus that of token0 converted at `price0_nad` (token0 in token1).
+    pub fn deficit(&self, price0_nad: u64) -> Option<(bool, u64, u64)> {
+        let surplus = |net: i128| u64::try_from(net.max(0)).unwrap_or(u64::MAX);
+        let deficit = |net: i128| u64::try_from(net.min(0).unsigned_abs()).unwrap_or(u64::MAX);
+        match (self.net0 < 0, self.net1 < 0) {
+            (false, false) => None,
+            (false, true) => Some((true, surplus(self.net0), deficit(self.net1))),
+            (true, false) => Some((false, surplus(self.net1), deficit(self.net0))),
+            (true, true) => {
+                let deficit0_in_token1 = (deficit(self.net0) as u128 * price0_nad as u128 / NAD as u128)
+                    .try_into()
+                    .unwrap_or(u64::MAX);
+                Some((true, 0, deficit(self.net1).saturating_add(deficit0_in_token1)))
+            }
+        }
+    }
 }
 
 impl UserPosition {
@@ -38,20 +104,102 @@ impl UserPosition {
         &mut self,
         owner: Pubkey,
         pair: Pubkey,
+        position_index: u16,
         bump: u8,
     ) -> Result<()> {
         self.owner = owner;
         self.pair = pair;
+        self.position_index = position_index;
         self.bump = bump;
         self.collateral0_liquidation_cf_bps = 0;
         self.collateral1_liquidation_cf_bps = 0;
+        self.cross_margin = false;
         Ok(())
     }
 
+    /// Last PDA seed of the position at `position_index`: empty for the default position, so its
+    /// address stays `[POSITION_SEED_PREFIX, pair, owner]`, the little-endian index otherwise.
+    pub fn index_seed(position_index: u16) -> Vec<u8> {
+        match position_index {
+            0 => Vec::new(),
+            _ => position_index.to_le_bytes().to_vec(),
+        }
+    }
+
+    /// Whether `signer` may act on the position where `required` is needed at `slot`: always the owner,
+    /// the delegate while its permission covers `required` and has not expired.
+    pub fn is_authorized(&self, signer: &Pubkey, required: DelegatePermission, slot: u64) -> bool {
+        *signer == self.owner
+            || (*signer == self.delegate && self.delegate_permission.covers(required) && slot <= self.delegate_expiry_slot)
+    }
+
     pub fn is_initialized(&self) -> bool {
         self.owner != Pubkey::default() && self.pair != Pubkey::default()
     }
 
+    /// No collateral and no debt left.
+    pub fn is_empty(&self) -> bool {
+        self.collateral0 == 0 && self.collateral1 == 0 && self.debt0_shares == 0 && self.debt1_shares == 0
+    }
+
+    /// Moves the collateral, debt shares, locked liquidation CFs and margin mode to `destination`,
+    /// which must be empty, leaving this position empty. Health is unchanged.
+    pub fn transfer_to(&mut self, destination: &mut UserPosition) -> Result<()> {
+        require!(destination.is_empty(), ErrorCode::UserPositionNotEmpty);
+        destination.collateral0 = std::mem::take(&mut self.collateral0);
+        destination.collateral1 = std::mem::take(&mut self.collateral1);
+        destination.debt0_shares = std::mem::take(&mut self.debt0_shares);
+        destination.debt1_shares = std::mem::take(&mut self.debt1_shares);
+        destination.collateral0_liquidation_cf_bps = std::mem::take(&mut self.collateral0_liquidation_cf_bps);
+        destination.collateral1_liquidation_cf_bps = std::mem::take(&mut self.collateral1_liquidation_cf_bps);
+        destination.cross_margin = self.cross_margin;
+        Ok(())
+    }
+
+    pub fn net_exposure(&self, pair: &Pair) -> Result<NetExposure> {
+        let debt0 = self.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?;
+        let debt1 = self.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?;
+        Ok(NetExposure {
+            net0: self.collateral0 as i128 - debt0 as i128,
+            net1: self.collateral1 as i128 - debt1 as i128,
+        })
+    }
+
+    /// Borrow limit check of a cross-margin position, the counterpart of the per-side checks of
+    /// `borrow` and `remove_collateral`: the token in surplus, valued as collateral at the pessimistic
+    /// EMA price, must cover the token in deficit. Locks in the liquidation CF of the deficit token.
+    pub fn check_net_borrow_limit(&mut self, pair: &Pair) -> Result<()> {
+        if let Some((is_collateral_token0, collateral, debt)) = self.net_exposure(pair)?.deficit(pair.ema_price0_nad()) {
+            let (collateral_token, debt_token) = match is_collateral_token0 {
+                true => (pair.token0, pair.token1),
+                false => (pair.token1, pair.token0),
+            };
+            let (borrow_limit, _, liquidation_cf_bps) = pair.get_max_debt_and_cf_bps_for_collateral(pair, &collateral_token, collateral)?;
+            require_gte!(borrow_limit, debt, ErrorCode::BorrowingPowerExceeded);
+            self.set_liquidation_cf_for_debt_token(&debt_token, pair, liquidation_cf_bps);
+        }
+        Ok(())
+    }
+
+    /// Borrow limit check of an isolated position on both sides: each collateral token must cover
+    /// the debt of the other token. Locks in the liquidation CF of each side with debt.
+    pub fn check_isolated_borrow_limits(&mut self, pair: &Pair) -> Result<()> {
+        let debt0 = self.calculate_debt0(pair.total_debt0, pair.total_debt0_shares)?;
+        let debt1 = self.calculate_debt1(pair.total_debt1, pair.total_debt1_shares)?;
+        for (collateral_token, debt_token, collateral, debt) in [
+            (pair.token0, pair.token1, self.collateral0, debt1),
+            (pair.token1, pair.token0, self.collateral1, debt0),
+        ] {
+            if debt == 0 {
+                continue;
+            }
+            let (borrow_limit, _, liquidation_cf_bps) = pair.get_max_debt_and_cf_bps_for_collateral(pair, &collateral_token, collateral)?;
+            require_gte!(borrow_limit, debt, ErrorCode::BorrowingPowerExceeded);
+            self.set_liquidation_cf_for_debt_token(&debt_token, pair, liquidation_cf_bps);
+        }
+        Ok(())
+    }
+
     /// Set the fixed liquidation CF for a specific debt token.
     /// Called on borrow, remove_collateral, and liquidation to lock in the CF.
     pub fn set_liquidation_cf_for_debt_token(&mut self, debt_token: &Pubkey, pair: &Pair, liquidation_cf_bps: u16) {
@@ -63,7 +211,7 @@ impl UserPosition {
     }
 
     /// Derive max CF (borrow limit CF with LTV buffer) from liquidation CF.
-    /// max_cf = liquidation_cf * (BPS - LTV_BUFFER_BPS) / BPS
+    /// max_cf = liquidation_cf * (BPS - ltv_buffer_bps) / BPS
     pub fn get_max_cf_bps_for_debt_token(&self, pair: &Pair, debt_token: &Pubkey) -> u16 {
         let liquidation_cf = if *debt_token == pair.token1 {
             self.collateral0_liquidation_cf_bps
@@ -71,7 +219,7 @@ impl UserPosition {
             self.collateral1_liquidation_cf_bps
         };
         ((liquidation_cf as u32)
-            .saturating_mul((BPS_DENOMINATOR - LTV_BUFFER_BPS) as u32)
+            .saturating_mul((BPS_DENOMINATOR - pair.risk_params.ltv_buffer_bps) as u32)
             / BPS_DENOMINATOR as u32) as u16
     }
 
@@ -246,9 +394,9 @@ impl UserPosition {
                 .ok_or(ErrorCode::DebtMathOverflow)?,
         };
         
-        // Apply LTV buffer to the CF: reduce borrow limit by LTV_BUFFER_BPS to create a buffer before liquidation
+        // Apply LTV buffer to the CF: reduce borrow limit by ltv_buffer_bps to create a buffer before liquidation
         let cf_with_buffer = (applied_min_cf_bps as u128)
-            .saturating_mul((BPS_DENOMINATOR - LTV_BUFFER_BPS) as u128)
+            .saturating_mul((BPS_DENOMINATOR - pair.risk_params.ltv_buffer_bps) as u128)
             .checked_div(BPS_DENOMINATOR as u128)
             .ok_or(ErrorCode::DebtMathOverflow)?;
         Ok((collateral_value as u128)
@@ -396,7 +544,16 @@ macro_rules! generate_user_position_seeds {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::VaultBumps;
+    use crate::{
+        instructions::{
+            lending::{
+                borrow::apply_borrow,
+                liquidate::{apply_liquidation, validate_liquidation},
+            },
+            SwapAmounts,
+        },
+        state::{RateModel, RiskParams, VaultBumps},
+    };
 
     fn test_pair() -> Pair {
         Pair::initialize(
@@ -415,9 +572,24 @@ mod tests {
             1,
             VaultBumps::default(),
             0,
+            RiskParams::default(),
         )
     }
 
+    /// `test_pair` with 1e9 reserves of each token at a 1:1 price
+    fn test_funded_pair() -> Pair {
+        let mut pair = test_pair();
+        pair.reserve0 = 1_000_000_000;
+        pair.reserve1 = 1_000_000_000;
+        pair.cash_reserve0 = 1_000_000_000;
+        pair.cash_reserve1 = 1_000_000_000;
+        pair.last_price0_ema.symmetric = pair.spot_price0_nad();
+        pair.last_price0_ema.directional = pair.spot_price0_nad();
+        pair.last_price1_ema.symmetric = pair.spot_price1_nad();
+        pair.last_price1_ema.directional = pair.spot_price1_nad();
+        pair
+    }
+
     fn test_position() -> UserPosition {
         UserPosition {
             owner: Pubkey::new_unique(),
@@ -429,6 +601,12 @@ mod tests {
             debt0_shares: 0,
             debt1_shares: 0,
             bump: 1,
+            cross_margin: false,
+            position_index: 0,
+            delegate: Pubkey::default(),
+            delegate_permission: DelegatePermission::None,
+            delegate_expiry_slot: 0,
+            open_orders: 0,
         }
     }
 
@@ -448,4 +626,123 @@ mod tests {
         assert_eq!(pair.cash_reserve0, 123);
         assert_eq!(user_position.debt0_shares, 0);
     }
+
+    #[test]
+    fn net_exposure_deficit_picks_the_backing_side() {
+        let exposure = |net0, net1| NetExposure { net0, net1 };
+        assert_eq!(exposure(10, 0).deficit(NAD), None);
+        assert_eq!(exposure(100, -40).deficit(NAD), Some((true, 100, 40)));
+        assert_eq!(exposure(-40, 100).deficit(NAD), Some((false, 100, 40)));
+    }
+
+    #[test]
+    fn net_exposure_deficit_sums_both_sides_in_token1() {
+        let exposure = |net0, net1| NetExposure { net0, net1 };
+        assert_eq!(exposure(-5, -7).deficit(NAD), Some((true, 0, 12)));
+        // token0 worth 2.5 token1
+        assert_eq!(exposure(-4, -7).deficit(5 * NAD / 2), Some((true, 0, 17)));
+        assert_eq!(exposure(i128::MIN / 2, -1).deficit(2 * NAD), Some((true, 0, u64::MAX)));
+    }
+
+    #[test]
+    fn default_position_keeps_the_legacy_address() {
+        let (pair, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let address = |position_index: u16| {
+            Pubkey::find_program_address(
+                &[POSITION_SEED_PREFIX, pair.as_ref(), owner.as_ref(), &UserPosition::index_seed(position_index)],
+                &crate::ID,
+            )
+            .0
+        };
+        let legacy = Pubkey::find_program_address(&[POSITION_SEED_PREFIX, pair.as_ref(), owner.as_ref()], &crate::ID).0;
+        assert_eq!(address(0), legacy);
+        assert_ne!(address(1), legacy);
+        assert_ne!(address(1), address(256));
+    }
+
+    #[test]
+    fn delegate_is_authorized_within_scope_until_expiry() {
+        let mut user_position = test_position();
+        let (owner, delegate) = (user_position.owner, Pubkey::new_unique());
+        assert!(user_position.is_authorized(&owner, DelegatePermission::Manage, 100));
+        assert!(!user_position.is_authorized(&delegate, DelegatePermission::Manage, 100));
+
+        user_position.delegate = delegate;
+        user_position.delegate_permission = DelegatePermission::Manage;
+        user_position.delegate_expiry_slot = 100;
+        assert!(user_position.is_authorized(&delegate, DelegatePermission::Manage, 100));
+        assert!(!user_position.is_authorized(&delegate, DelegatePermission::Manage, 101));
+        assert!(!user_position.is_authorized(&Pubkey::new_unique(), DelegatePermission::Manage, 100));
+
+        user_position.delegate_permission = DelegatePermission::None;
+        assert!(!user_position.is_authorized(&delegate, DelegatePermission::Manage, 100));
+    }
+
+    #[test]
+    fn transfer_moves_balances_into_an_empty_position_only() {
+        let mut source = test_position();
+        (source.collateral0, source.debt1_shares, source.collateral0_liquidation_cf_bps) = (100, 40, 7_000);
+        source.cross_margin = true;
+        let mut destination = test_position();
+
+        source.transfer_to(&mut destination).unwrap();
+        assert!(source.is_empty());
+        assert_eq!(source.collateral0_liquidation_cf_bps, 0);
+        assert_eq!(
+            (destination.collateral0, destination.debt1_shares, destination.collateral0_liquidation_cf_bps),
+            (100, 40, 7_000)
+        );
+        assert!(destination.cross_margin);
+
+        let err = test_position().transfer_to(&mut destination).unwrap_err();
+        assert_eq!(err, error!(ErrorCode::UserPositionNotEmpty));
+    }
+
+    #[test]
+    fn cross_margin_nets_same_token_collateral_before_liquidating() {
+        let rate_model = RateModel::new(
+            TARGET_UTIL_START_BPS,
+            TARGET_UTIL_END_BPS,
+            DEFAULT_RATE_HALF_LIFE_MS,
+            DEFAULT_MIN_RATE_BPS,
+            0,
+            DEFAULT_INITIAL_RATE_BPS,
+        );
+        let mut pair = test_funded_pair();
+        let mut user_position = test_position();
+        user_position.collateral0 = 100_000_000;
+        pair.total_collateral0 = user_position.collateral0;
+        let (token0, token1) = (pair.token0, pair.token1);
+
+        // Isolated token0 collateral only backs token1 debt
+        let err = apply_borrow(&mut pair.clone(), &mut user_position.clone(), &token0, 40_000_000).unwrap_err();
+        assert_eq!(err, error!(ErrorCode::BorrowingPowerExceeded));
+        let isolated_max = apply_borrow(&mut pair.clone(), &mut user_position.clone(), &token1, u64::MAX).unwrap();
+
+        user_position.cross_margin = true;
+        apply_borrow(&mut pair, &mut user_position, &token0, 40_000_000).unwrap();
+        let borrowed = apply_borrow(&mut pair, &mut user_position, &token1, u64::MAX).unwrap();
+        // Only the 60M net surplus of token0 backs the token1 debt
+        assert!(borrowed > 0 && borrowed < isolated_max);
+        let err = validate_liquidation(&pair, &user_position, true).unwrap_err();
+        assert_eq!(err, error!(ErrorCode::NotUndercollateralized));
+
+        // Crash token0 and let the EMA catch up
+        SwapAmounts::exact_in(&pair, 0, true, 1_000_000_000).unwrap().apply(&mut pair, true).unwrap();
+        pair.update_at(&rate_model, &rate_model, 0, pair.last_update + 100_000).unwrap();
+
+        // The token0 collateral must be netted against the token0 debt first
+        let err = validate_liquidation(&pair, &user_position, false).unwrap_err();
+        assert_eq!(err, error!(ErrorCode::CrossMarginNettingRequired));
+
+        let debt0 = user_position.calculate_debt0(pair.total_debt0, pair.total_debt0_shares).unwrap();
+        let cash_reserve0 = pair.cash_reserve0;
+        validate_liquidation(&pair, &user_position, true).unwrap();
+        let amounts = apply_liquidation(&mut pair, &mut user_position, true).unwrap();
+        assert_eq!(amounts.collateral_netted, debt0);
+        assert_eq!(user_position.debt0_shares, 0);
+        assert_eq!(pair.cash_reserve0, cash_reserve0 + debt0 + amounts.collateral_to_reserves);
+        assert!(amounts.debt_to_writeoff > 0);
+        assert_eq!(user_position.collateral0, 100_000_000 - debt0 - amounts.collateral_seized);
+    }
 }
@@ -1,4 +1,8 @@
-use anchor_lang::Space;
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Space,
+};
 
 /// Calculates the total size needed for an account including the 8-byte discriminator.
 /// 
@@ -17,4 +21,29 @@ pub fn get_size_with_discriminator<T: Space>() -> usize {
 /// @return usize The total size in bytes needed for the account
 pub fn get_size_with_discriminator_and_custom_size(custom_size: usize) -> usize {
     8 + custom_size
-} 
\ No newline at end of file
+} 
+/// Grows `account` to `new_len` bytes, zero-filling the tail
+/// @notice `payer` tops up the rent exemption of the added space
+/// @dev Used to migrate accounts whose type gained fields at the end
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let rent_due = Rent::get()?.minimum_balance(new_len).saturating_sub(account.lamports());
+    if rent_due > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    account.realloc(new_len, true)?;
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::errors::ErrorCode;
+use crate::state::risk_params::RiskParams;
 use crate::utils::math::{ceil_div, SqrtU128};
 use std::cmp::min;
 
@@ -174,6 +175,27 @@ fn calculate_max_allowed_total_debt(
     CPCurve::calculate_amount_out(collateral_ema_reserve, debt_ema_reserve, total_collateral_amount)
 }
 
+/// Collateral factor configuration of a pair
+#[derive(Clone, Copy, Debug)]
+pub struct CfParams {
+    /// Optional fixed collateral factor. If Some, used instead of the AMM-based CF
+    pub fixed_cf_bps: Option<u16>,
+    /// Cap on the dynamic CF (`RiskParams::max_collateral_factor_bps`)
+    pub max_collateral_factor_bps: u16,
+    /// Buffer between borrow limit and liquidation threshold (`RiskParams::ltv_buffer_bps`)
+    pub ltv_buffer_bps: u16,
+}
+
+impl CfParams {
+    pub fn new(fixed_cf_bps: Option<u16>, risk_params: &RiskParams) -> Self {
+        Self {
+            fixed_cf_bps,
+            max_collateral_factor_bps: risk_params.max_collateral_factor_bps,
+            ltv_buffer_bps: risk_params.ltv_buffer_bps,
+        }
+    }
+}
+
 /// Maximum borrowable amount of tokenY using either a fixed CF or an impact-aware CF
 ///
 /// Inputs:
@@ -183,11 +205,11 @@ fn calculate_max_allowed_total_debt(
 /// - collateral_amm_reserve: R0 (raw X units)
 /// - debt_amm_reserve: R1 (raw Y units)
 /// - total_debt: existing total debt (raw Y units)
-/// - fixed_cf_bps: Optional fixed collateral factor. If Some, uses this directly instead of AMM-based CF
+/// - cf_params: fixed CF if any, dynamic CF cap and LTV buffer
 ///
 /// Returns:
 /// - final_borrow_limit (raw Y units)
-/// - max_allowed_cf_bps (liquidation_cf_bps * 95%)
+/// - max_allowed_cf_bps (liquidation_cf_bps * (1 - ltv_buffer))
 /// - liquidation_cf_bps 
 pub fn pessimistic_max_debt(
     collateral_amount: u64,
@@ -196,8 +218,9 @@ pub fn pessimistic_max_debt(
     collateral_amm_reserve: u64,
     debt_amm_reserve: u64,
     total_debt: u64,
-    fixed_cf_bps: Option<u16>,
+    cf_params: CfParams,
 ) -> Result<(u64, u16, u16)> {
+    let CfParams { fixed_cf_bps, max_collateral_factor_bps, ltv_buffer_bps } = cf_params;
     // sanity checks
     if collateral_amount == 0
         || collateral_ema_price_nad == 0
@@ -264,7 +287,7 @@ pub fn pessimistic_max_debt(
     // Apply spot/EMA divergence cap to fixed cf only for preventing EMA lag front-running
     // CF_final = min(fixed_cf_bps, fixed_cf_bps * spot/ema)
     // fixed CF: capped at [100 bps, CF_final]
-    // dynamic CF: capped at MAX_COLLATERAL_FACTOR_BPS bps
+    // dynamic CF: capped at max_collateral_factor_bps
     let liquidation_cf_bps = if fixed_cf_bps.is_some() {
         // If spot > ema: CF stays at fixed_cf_bps
         // If spot < ema: CF reduces proportionally to render front-running non-profitable
@@ -277,15 +300,15 @@ pub fn pessimistic_max_debt(
         // Apply divergence cap: min(fixed_cf_bps, fixed_cf_bps * spot/ema)
         min(base, shrunk).max(100) as u16
     } else {
-        // apply 85% maximum cap on dynamic CF
+        // apply maximum cap (85% by default) on dynamic CF
         // no need to apply divergence cap as base_cf_bps is based on impact with on virtual reserves at pessimistic price
-        base_cf_bps.min(MAX_COLLATERAL_FACTOR_BPS as u64) as u16
+        base_cf_bps.min(max_collateral_factor_bps as u64) as u16
     };
 
-    // Max allowed CF BPS = liquidation CF * (1 - LTV_BUFFER_BPS / BPS_DENOMINATOR)
+    // Max allowed CF BPS = liquidation CF * (1 - ltv_buffer_bps / BPS_DENOMINATOR)
     // This creates a buffer between borrow limit and liquidation threshold
     let max_allowed_cf_bps = ((liquidation_cf_bps as u32)
-        .saturating_mul((BPS_DENOMINATOR - LTV_BUFFER_BPS) as u32)
+        .saturating_mul((BPS_DENOMINATOR - ltv_buffer_bps) as u32)
         / BPS_DENOMINATOR as u32) as u16;
 
     // Final borrow limit = V_impact * max_allowed_cf_bps / BPS
@@ -527,7 +550,7 @@ mod tests {
         // user_max=500k, base_cf=500k*10000/500k=10000bps (capped to 8500), max_cf=8075
         // limit = 500k * 8075 / 10000 = 403,750
         let (limit, max_cf, liq_cf) = pessimistic_max_debt(
-            1_000_000, NAD, NAD, 1_000_000, 1_000_000, 0, None
+            1_000_000, NAD, NAD, 1_000_000, 1_000_000, 0, CfParams::new(None, &RiskParams::default())
         ).unwrap();
         
         assert_eq!((liq_cf, max_cf, limit), (8500, 8075, 403_750));
@@ -541,12 +564,12 @@ mod tests {
         // impact_value = amount_out(1M, 1M, 500k) = 333,333
         // @0: user_max=333,333, base_cf=333,333*10000/333,333=10000 (capped 8500), max_cf=8075
         //     limit=333,333*8075/10000=269,166
-        let (l0, cf0, _) = pessimistic_max_debt(500_000, NAD, NAD, 1_000_000, 1_000_000, 0, None).unwrap();
+        let (l0, cf0, _) = pessimistic_max_debt(500_000, NAD, NAD, 1_000_000, 1_000_000, 0, CfParams::new(None, &RiskParams::default())).unwrap();
         assert_eq!((l0, cf0), (269_166, 8075));
         
         // @200k: user_max=228,571, base_cf=228,571*10000/333,333=6857, max_cf=6514
         //        limit=333,333*6514/10000=217,133
-        let (l200k, cf200k, _) = pessimistic_max_debt(500_000, NAD, NAD, 1_000_000, 1_000_000, 200_000, None).unwrap();
+        let (l200k, cf200k, _) = pessimistic_max_debt(500_000, NAD, NAD, 1_000_000, 1_000_000, 200_000, CfParams::new(None, &RiskParams::default())).unwrap();
         assert_eq!((l200k, cf200k), (217_133, 6514));
         
         assert_eq!(l0 - l200k, 52_033);
@@ -680,7 +703,7 @@ mod tests {
             collateral_amm_reserve,
             debt_amm_reserve,
             total_debt,
-            None,
+            CfParams::new(None, &RiskParams::default()),
         );
         assert!(result.is_ok(), "Should not overflow for large price asymmetry pools");
         let (borrow_limit, max_cf, liq_cf) = result.unwrap();
@@ -857,7 +880,7 @@ mod tests {
         for (user_coll, label) in &test_cases {
             let (borrow_lim, _, liq_cf) = pessimistic_max_debt(
                 *user_coll, ema_price, ema_price,
-                collateral_reserve, debt_reserve, total_debt, None,
+                collateral_reserve, debt_reserve, total_debt, CfParams::new(None, &RiskParams::default()),
             ).unwrap();
             let liq_lim = liquidation_limit(*user_coll, liq_cf);
             let buffer = liq_lim.saturating_sub(borrow_lim);
@@ -883,7 +906,7 @@ mod tests {
         for (user_coll, label) in &test_cases {
             let (borrow_lim, _, liq_cf) = pessimistic_max_debt(
                 *user_coll, ema_price, ema_price,
-                collateral_reserve, debt_reserve, total_debt, Some(fixed_cf),
+                collateral_reserve, debt_reserve, total_debt, CfParams::new(Some(fixed_cf), &RiskParams::default()),
             ).unwrap();
             let liq_lim = liquidation_limit(*user_coll, liq_cf);
             let buffer = liq_lim.saturating_sub(borrow_lim);
@@ -899,7 +922,7 @@ mod tests {
             );
         }
 
-        // Verify the buffer is always positive (strict safety margin from LTV_BUFFER_BPS)
+        // Verify the buffer is always positive (strict safety margin from the LTV buffer)
         // For any collateral size, since both use impact value:
         //   borrow_limit = V_impact * liq_cf * 0.95 / BPS
         //   liq_limit    = V_impact * liq_cf / BPS
@@ -907,7 +930,7 @@ mod tests {
         let large_coll: u64 = 500_000;
         let (borrow_lim, _, liq_cf) = pessimistic_max_debt(
             large_coll, ema_price, ema_price,
-            collateral_reserve, debt_reserve, total_debt, None,
+            collateral_reserve, debt_reserve, total_debt, CfParams::new(None, &RiskParams::default()),
         ).unwrap();
         let liq_lim = liquidation_limit(large_coll, liq_cf);
         assert!(
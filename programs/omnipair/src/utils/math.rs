@@ -1,5 +1,4 @@
 use crate::constants::*;
-use anchor_lang::prelude::{Clock, *};
 
 /// Approximates the elapsed time in milliseconds between two slots.
 pub fn slots_to_ms(start_slot: u64, end_slot: u64) -> Option<u64> {
@@ -8,8 +7,10 @@ pub fn slots_to_ms(start_slot: u64, end_slot: u64) -> Option<u64> {
         .checked_mul(TARGET_MS_PER_SLOT)
 }
 
-pub fn compute_ema(last_ema: u64, last_update: u64, input: u64, half_life: u64) -> u64 {
-    let dt = slots_to_ms(last_update, Clock::get().unwrap().slot).unwrap();
+/// EMA of `input` at `current_slot`, from `last_ema` as of `last_update`.
+/// A `current_slot` before `last_update` leaves the EMA unchanged.
+pub fn compute_ema_at(last_ema: u64, last_update: u64, input: u64, half_life: u64, current_slot: u64) -> u64 {
+    let dt = slots_to_ms(last_update, current_slot).unwrap_or(0);
     
     if dt > 0 && half_life > 0 {
         // Calculate x in NAD scale
@@ -168,4 +169,18 @@ pub fn ceil_div(a: u128, b: u128) -> Option<u128> {
         return None;
     }
     a.checked_add(b - 1)?.checked_div(b)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ema_is_unchanged_before_its_last_update() {
+        let half_life = 60_000;
+        assert_eq!(compute_ema_at(NAD, 1_000, 2 * NAD, half_life, 999), NAD);
+        assert_eq!(compute_ema_at(NAD, 1_000, 2 * NAD, half_life, 1_000), NAD);
+        let ema = compute_ema_at(NAD, 1_000, 2 * NAD, half_life, 1_150);
+        assert!(ema > NAD && ema < 2 * NAD);
+    }
+}
@@ -68,11 +68,14 @@ async function main() {
 
     // Get pair account to get rate model
     const pairAccount = await program.account.pair.fetch(pairPda);
-    console.log('Rate model address:', pairAccount.rateModel.toBase58());
+    console.log('Rate model0 address:', pairAccount.rateModel0.toBase58());
+    console.log('Rate model1 address:', pairAccount.rateModel1.toBase58());
     
-    const RATE_MODEL = pairAccount.rateModel;
+    const RATE_MODEL0 = pairAccount.rateModel0;
+    const RATE_MODEL1 = pairAccount.rateModel1;
 
-    console.log('Rate Model address:', RATE_MODEL.toBase58());
+    console.log('Rate Model0 address:', RATE_MODEL0.toBase58());
+    console.log('Rate Model1 address:', RATE_MODEL1.toBase58());
 
     // Find PDA for futarchy authority
     const [futarchyAuthorityPda] = PublicKey.findProgramAddressSync(
@@ -131,7 +134,8 @@ async function main() {
         .accountsPartial({
             user: DEPLOYER_KEYPAIR.publicKey,
             pair: pairPda,
-            rateModel: RATE_MODEL,
+            rateModel0: RATE_MODEL0,
+            rateModel1: RATE_MODEL1,
             futarchyAuthority: futarchyAuthorityPda,
             userPosition: userPositionPda,
             collateralVault: collateralToken0 ? token0Vault : token1Vault,
@@ -64,9 +64,11 @@ async function main() {
 
     // Get pair account to get rate model
     const pairAccount = await program.account.pair.fetch(pairPda);
-    console.log('Rate model address:', pairAccount.rateModel.toBase58());
+    console.log('Rate model0 address:', pairAccount.rateModel0.toBase58());
+    console.log('Rate model1 address:', pairAccount.rateModel1.toBase58());
     
-    const RATE_MODEL = pairAccount.rateModel;
+    const RATE_MODEL0 = pairAccount.rateModel0;
+    const RATE_MODEL1 = pairAccount.rateModel1;
 
     // Find PDA for futarchy authority
     const [futarchyAuthorityPda] = PublicKey.findProgramAddressSync(
@@ -121,7 +123,8 @@ async function main() {
         .accountsPartial({
             user: DEPLOYER_KEYPAIR.publicKey,
             pair: pairPda,
-            rateModel: RATE_MODEL,
+            rateModel0: RATE_MODEL0,
+            rateModel1: RATE_MODEL1,
             futarchyAuthority: futarchyAuthorityPda,
             token0Vault: token0Vault,
             token1Vault: token1Vault,
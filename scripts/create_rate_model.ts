@@ -40,20 +40,21 @@ async function main() {
     provider.opts.commitment = 'confirmed';
     provider.opts.preflightCommitment = 'confirmed';
 
-    // Fetch pair to get current rate model
+    // Fetch pair to get current rate models
     const pair = await program.account.pair.fetch(PAIR_ADDRESS);
     console.log('Pair:', PAIR_ADDRESS.toBase58());
-    console.log('Current rate model:', pair.rateModel.toBase58());
+    console.log('Current rate model0:', pair.rateModel0.toBase58());
+    console.log('Current rate model1:', pair.rateModel1.toBase58());
 
-    // Fetch current rate model
-    const currentRM = await program.account.rateModel.fetch(pair.rateModel);
+    // Fetch current token0 debt rate model, the new parameters start from it
+    const currentRM = await program.account.rateModel.fetch(pair.rateModel0);
     const currentMinBps = nadToBps(currentRM.minRate);
     const currentMaxBps = nadToBps(currentRM.maxRate);
     const currentInitBps = nadToBps(currentRM.initialRate);
     const currentUtilStart = nadToBps(currentRM.targetUtilStart);
     const currentUtilEnd = nadToBps(currentRM.targetUtilEnd);
 
-    console.log('\n--- Current Rate Model ---');
+    console.log('\n--- Current Rate Model (token0 debt) ---');
     console.log(`  util band:     ${currentUtilStart} - ${currentUtilEnd} bps (${currentUtilStart / 100}% - ${currentUtilEnd / 100}%)`);
     console.log(`  half_life_ms:  ${currentRM.halfLifeMs.toString()}`);
     console.log(`  min_rate:      ${currentMinBps} bps (${currentMinBps / 100}%)`);
@@ -108,8 +109,9 @@ async function main() {
     console.log('Signature:', tx);
     console.log('\nNew rate model address:', rateModelKeypair.publicKey.toBase58());
     console.log('\nTo apply to the pair, call set_pair_rate_model with:');
-    console.log(`  pair:           ${PAIR_ADDRESS.toBase58()}`);
-    console.log(`  new_rate_model: ${rateModelKeypair.publicKey.toBase58()}`);
+    console.log(`  pair:            ${PAIR_ADDRESS.toBase58()}`);
+    console.log(`  new_rate_model0: ${rateModelKeypair.publicKey.toBase58()}`);
+    console.log(`  new_rate_model1: ${rateModelKeypair.publicKey.toBase58()} (either side can be omitted)`);
 }
 
 main().catch(error => {
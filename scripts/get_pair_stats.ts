@@ -23,13 +23,14 @@ import {
     pairPda: PublicKey,
     getter: any, // Enum variant object
     args?: any, // EmitValueArgs for functions that need additional parameters
-    rateModelPda?: PublicKey // Rate model PDA for getRates function
+    rateModels?: [PublicKey, PublicKey] // Rate models of token0 and token1 debt, required by getRates
   ): Promise<{ label: string; value0: string; value1: string; formattedValue0: number; formattedValue1: number }> {
     const accounts: any = { pair: pairPda };
     
-    // Add rate model account - required for all ViewPairData functions
-    if (rateModelPda) {
-      accounts.rateModel = rateModelPda;
+    // Add rate model accounts - required for all ViewPairData functions
+    if (rateModels) {
+      accounts.rateModel0 = rateModels[0];
+      accounts.rateModel1 = rateModels[1];
     }
     
     const sim = await program.methods
@@ -125,7 +126,9 @@ import {
     console.log('Reserve 1:', pairAccount.reserve1.toString(), Number(pairAccount.reserve1.toString()) / 10 ** 6);
     console.log('Total Debt 0:', pairAccount.totalDebt0.toString(), Number(pairAccount.totalDebt0.toString()) / 10 ** 6);
     console.log('Total Debt 1:', pairAccount.totalDebt1.toString(), Number(pairAccount.totalDebt1.toString()) / 10 ** 6);
-    console.log('Rate Model:', pairAccount.rateModel.toBase58());
+    console.log('Rate Model0:', pairAccount.rateModel0.toBase58());
+    console.log('Rate Model1:', pairAccount.rateModel1.toBase58());
+    const rateModels: [PublicKey, PublicKey] = [pairAccount.rateModel0, pairAccount.rateModel1];
   
     console.log('Simulating on-chain values for pair:', pairPda.toBase58());
   
@@ -140,7 +143,7 @@ import {
     ];
   
     for (const getter of enumVariants) {
-      const { label, value0, value1, formattedValue0, formattedValue1 } = await simulateGetter(program, pairPda, getter, undefined, pairAccount.rateModel);
+      const { label, value0, value1, formattedValue0, formattedValue1 } = await simulateGetter(program, pairPda, getter, undefined, rateModels);
       console.log(`${label}: ${value0} (${formattedValue0})${label === 'getRates' ? `, ${value1} (${formattedValue1})` : ''}`);
       // Note: value1 is OptionalU64(None) for single-value functions, so we don't display it
     }
@@ -153,7 +156,7 @@ import {
       pairPda, 
       { getMinCollateralForDebt: {} },
       { debtAmount: debtAmount, collateralAmount: null, collateralToken: null },
-      pairAccount.rateModel
+      rateModels
     );
     console.log(`${minCollateralResult.label} Token0: ${minCollateralResult.value0} (${minCollateralResult.formattedValue0})`);
     console.log(`${minCollateralResult.label} Token1: ${minCollateralResult.value1} (${minCollateralResult.formattedValue1})`);
@@ -174,7 +177,7 @@ import {
       pairPda, 
       { getBorrowLimitAndCfBpsForCollateral: {} },
       { debtAmount: null, collateralAmount: testCollateralAmount0, collateralToken: TOKEN0_MINT },
-      pairAccount.rateModel
+      rateModels
     );
     console.log(borrowLimitResult0);
     console.log(`${borrowLimitResult0.label} with Token0 collateral - Max Debt: ${borrowLimitResult0.value0} (${borrowLimitResult0.formattedValue0}), CF BPS: ${borrowLimitResult0.value1} (${borrowLimitResult0.formattedValue1 / 100}%)`);
@@ -185,7 +188,7 @@ import {
       pairPda, 
       { getBorrowLimitAndCfBpsForCollateral: {} },
       { debtAmount: null, collateralAmount: testCollateralAmount1, collateralToken: TOKEN1_MINT },
-      pairAccount.rateModel
+      rateModels
     );
     console.log(`${borrowLimitResult1.label} with Token1 collateral - Max Debt: ${borrowLimitResult1.value0} (${borrowLimitResult1.formattedValue0}), CF BPS: ${borrowLimitResult1.value1} (${borrowLimitResult1.formattedValue1 / 100}%)`);
   }
@@ -60,11 +60,14 @@ async function main() {
     console.log('Pair total debt1:', pairAccount.totalDebt1.toString());
     console.log('Pair total debt0 shares:', pairAccount.totalDebt0Shares.toString());
     console.log('Pair total debt1 shares:', pairAccount.totalDebt1Shares.toString());
-    console.log('Rate model address:', pairAccount.rateModel.toBase58());
+    console.log('Rate model0 address:', pairAccount.rateModel0.toBase58());
+    console.log('Rate model1 address:', pairAccount.rateModel1.toBase58());
     
-    const RATE_MODEL = pairAccount.rateModel;
+    const RATE_MODEL0 = pairAccount.rateModel0;
+    const RATE_MODEL1 = pairAccount.rateModel1;
 
-    console.log('Rate Model address:', RATE_MODEL.toBase58());
+    console.log('Rate Model0 address:', RATE_MODEL0.toBase58());
+    console.log('Rate Model1 address:', RATE_MODEL1.toBase58());
 
     // Find PDA for futarchy authority
     const [futarchyAuthorityPda] = PublicKey.findProgramAddressSync(
@@ -139,7 +142,8 @@ async function main() {
             payer: DEPLOYER_KEYPAIR.publicKey,
             positionOwner: userPublicKey,
             pair: pairPda,
-            rateModel: RATE_MODEL,
+            rateModel0: RATE_MODEL0,
+            rateModel1: RATE_MODEL1,
             futarchyAuthority: futarchyAuthorityPda,
             userPosition: userPositionPda,
             collateralVault: liquidateToken0 ? token0Vault : token1Vault,
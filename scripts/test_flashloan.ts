@@ -63,8 +63,10 @@ async function main() {
     
     // Get pair account to find rate model
     const pairAccount = await omnipairProgram.account.pair.fetch(pairPda);
-    const rateModel = pairAccount.rateModel;
-    console.log('Rate Model:', rateModel.toBase58());
+    const rateModel0 = pairAccount.rateModel0;
+    const rateModel1 = pairAccount.rateModel1;
+    console.log('Rate Model0:', rateModel0.toBase58());
+    console.log('Rate Model1:', rateModel1.toBase58());
     
     // Get token vaults (ATAs owned by the pair)
     const token0Vault = await getAssociatedTokenAddress(
@@ -131,7 +133,8 @@ async function main() {
             })
             .accountsPartial({
                 pair: pairPda,
-                rateModel: rateModel,
+                rateModel0: rateModel0,
+                rateModel1: rateModel1,
                 token0Vault: token0Vault,
                 token1Vault: token1Vault,
                 token0Mint: TOKEN0_MINT,